@@ -0,0 +1,113 @@
+//! Sugerencias de patrones con un asistente de IA externo (el comando de
+//! `[ai]`), y la API key que ese comando necesita.
+
+use std::io;
+use tokio::process::Command;
+
+use regex_wysiwyg::secrets;
+
+use crate::app::{App, Background, InputMode};
+use crate::i18n::{self, Notice, Text, tr};
+
+impl App {
+    pub fn start_api_key_setup(&mut self) {
+        self.secret_input.clear();
+        self.input_mode = InputMode::EnteringApiKey;
+        self.status_message = i18n::notice(self.language, Notice::ApiKeyPrompt(&self.config.ai.provider));
+    }
+
+    pub fn save_api_key(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let key = self.secret_input.trim().to_string();
+        self.secret_input.clear();
+        if key.is_empty() {
+            self.notify(tr(self.language, Text::EmptyApiKey));
+            return;
+        }
+        match secrets::set_api_key(&self.config.ai.provider, &key) {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::ApiKeySaved(&self.config.ai.provider))),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::Error(&e))),
+        }
+    }
+
+    pub fn suggest_ai(&mut self) {
+        let ai = self.config.ai.clone();
+        self.notify(i18n::notice(self.language, Notice::QueryingAi(&ai.provider)));
+        
+        let prompt = format!(
+            "Give me ONLY the regex pattern (no text, no backticks, no markdown) to match or extract this: '{}' in the text: '{}'.",
+            self.regex_input, self.source_text
+        );
+
+        let prompt_len = prompt.len();
+        let mut command = Command::new(&ai.command);
+        command.args(&ai.args).arg(prompt);
+        if let Some(var) = &ai.api_key_env {
+            match secrets::api_key(&ai.provider) {
+                Ok(Some(key)) => {
+                    command.env(var, key);
+                }
+                Ok(None) => {
+                    // Primera vez: pedir la key y guardarla antes de consultar.
+                    self.start_api_key_setup();
+                    return;
+                }
+                Err(e) => {
+                    self.notify_error(i18n::notice(self.language, Notice::Error(&e)));
+                    return;
+                }
+            }
+        }
+        tracing::info!(provider = %ai.provider, command = %ai.command, prompt_bytes = prompt_len, "consulta a la IA");
+        let provider = ai.provider;
+        let tasks = self.tasks.clone();
+        self.ai_pending = true;
+        tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            let output = command.output().await;
+            match &output {
+                Ok(out) => tracing::info!(
+                    %provider,
+                    elapsed = ?started.elapsed(),
+                    status = %out.status,
+                    stdout_bytes = out.stdout.len(),
+                    stderr_bytes = out.stderr.len(),
+                    "respuesta de la IA"
+                ),
+                Err(e) => tracing::warn!(%provider, elapsed = ?started.elapsed(), error = %e, "la IA no se pudo ejecutar"),
+            }
+            let _ = tasks.send(Background::AiAnswered(provider, output));
+        });
+    }
+
+    pub fn ai_answered(&mut self, provider: &str, output: io::Result<std::process::Output>) {
+        self.ai_pending = false;
+        match output {
+            Ok(out) if out.status.success() => {
+                let suggestion = String::from_utf8_lossy(&out.stdout).trim().to_string();
+                if !suggestion.is_empty() {
+                    let clean = suggestion
+                        .replace("```regex", "")
+                        .replace("```", "")
+                        .replace("`", "")
+                        .trim()
+                        .to_string();
+                    self.regex_input = clean;
+                    self.notify(tr(self.language, Text::SuggestionApplied));
+                    self.record_history();
+                    self.apply_transform();
+                } else {
+                    self.notify(i18n::notice(self.language, Notice::AiEmpty(provider)));
+                }
+            }
+            Err(e) => {
+                self.notify_error(i18n::notice(self.language, Notice::AiRunFailed(&e)));
+            }
+            Ok(out) => {
+                let err_msg = String::from_utf8_lossy(&out.stderr);
+                let start: String = err_msg.chars().take(30).collect();
+                self.notify_error(i18n::notice(self.language, Notice::AiFailed(provider, &start)));
+            }
+        }
+    }
+}
@@ -0,0 +1,1821 @@
+//! Estado de la aplicación y sus transiciones: qué hace cada acción y cada
+//! tecla según el modo activo.
+
+use anyhow::Result;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+
+use regex_wysiwyg::assertions::{self, SpanScore};
+use regex_wysiwyg::branches::BranchSummary;
+use regex_wysiwyg::cases::{self, CaseResult};
+use regex_wysiwyg::catalog::CATALOG;
+use regex_wysiwyg::config::{Config, Language, LayoutPreset, PaneKind, Split};
+use regex_wysiwyg::coverage::{self, Coverage};
+use regex_wysiwyg::debugger::{self, Step};
+use regex_wysiwyg::export::{ExportInput, Target};
+use regex_wysiwyg::history::History;
+use regex_wysiwyg::introspect::{self, Introspection};
+use regex_wysiwyg::lessons::{self, Lesson, Progress};
+use regex_wysiwyg::nearmiss::{self, NearMiss};
+use regex_wysiwyg::library::{ConflictPolicy, Library, LibraryEntry, LibraryPreview, LibrarySort};
+use regex_wysiwyg::project::OpenProject;
+use regex_wysiwyg::recent::RecentFiles;
+use regex_wysiwyg::stress::{self, StressCase};
+use regex_wysiwyg::engine::MatchLocation;
+use regex_wysiwyg::worker::{Analyses, Timings, Worker};
+use ropey::Rope;
+use regex_wysiwyg::{EngineOptions, ProcessingUnit, RegexCache, RegexFlags, fuzzy};
+
+use crate::i18n::{self, Notice, Text, tr};
+use crate::keymap::{Action, Keymap};
+use crate::session::Session;
+use crate::theme::{self, Palette};
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum InputMode {
+    Normal,
+    EditingSource,
+    EditingRegex,
+    EditingReplace,
+    EditingTests,
+    History,
+    Library,
+    NamingPattern,
+    DescribingPattern,
+    TaggingPattern,
+    ExemplifyingPattern,
+    OpeningFile,
+    Recent,
+    ExportingLibrary,
+    ExportingReport,
+    ImportingLibrary,
+    ImportingRegex101,
+    ImportingTests,
+    Catalog,
+    /// Lista de ejercicios de las lecciones incorporadas.
+    Exercises,
+    ExportingCode,
+    EnteringApiKey,
+    CommandPalette,
+    Loading,
+    Help,
+    /// Una ventana modal (`App::modal`) espera respuesta.
+    Modal,
+    /// Recorrido de bienvenida de la primera ejecución, paso `App::tour_step`.
+    Tour,
+    /// Recorrido paso a paso del patrón sobre el texto (`App::debugger`).
+    Debugging,
+    /// Marcado con el cursor de los tramos que el patrón debería encontrar.
+    MarkingSpans,
+    /// Variantes que casi coinciden con el patrón (`App::near_misses`).
+    NearMisses,
+    /// Edición del patrón B, que se compara con el principal.
+    EditingCompare,
+}
+
+/// Ventana centrada que toma el teclado hasta que se cierra: un error que hay
+/// que leer o una pregunta que se contesta con una tecla.
+pub struct Modal {
+    pub title: String,
+    pub message: String,
+    pub kind: ModalKind,
+}
+
+pub enum ModalKind {
+    /// Se cierra con Enter o Esc.
+    Error,
+    Question(Question),
+}
+
+/// Preguntas que puede hacer una ventana modal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Question {
+    /// La ejecución anterior no terminó bien: ¿recuperar el autoguardado?
+    RestoreAutosave,
+    /// Se canceló una carga: ¿conservar lo leído?
+    KeepPartial,
+    /// Hay nombres repetidos al importar: sobrescribir, conservar o renombrar.
+    ImportConflicts,
+    /// El destino de una exportación ya existe: ¿sobrescribirlo?
+    OverwriteLibrary,
+    OverwriteReport,
+    /// Al sobrescribir un patrón de la biblioteca, casos que pasaban ahora fallan.
+    LibraryRegressions,
+}
+
+impl Question {
+    /// Teclas que la responden; Enter equivale a la primera y Esc a "no".
+    fn keys(self) -> &'static [char] {
+        match self {
+            // Enter conserva lo existente, la opción que no pisa nada.
+            Question::ImportConflicts => &['c', 's', 'r'],
+            // Enter no guarda un patrón que rompe casos.
+            Question::LibraryRegressions => &['n', 's', 'y'],
+            _ => &['s', 'y', 'n'],
+        }
+    }
+}
+
+/// Resultado de una tarea en segundo plano, que vuelve al bucle de la interfaz
+/// para aplicarse sobre el estado.
+pub enum Background {
+    LoadProgress { read: u64, total: u64 },
+    FileLoaded(PathBuf, io::Result<String>),
+    /// Carga cancelada, con lo que se llegó a leer.
+    LoadCancelled(PathBuf, String),
+    LibraryFetched(Result<Vec<LibraryEntry>>),
+    AiAnswered(String, io::Result<std::process::Output>),
+    /// Recorrido del depurador, con el patrón y el texto sobre los que se armó.
+    DebugTraced { pattern: String, source: Rope, group_names: Vec<Option<String>>, steps: Vec<Step> },
+    /// Patrón medido y el resultado de la prueba de estrés.
+    StressMeasured(String, Result<Vec<StressCase>, String>),
+}
+
+/// Espacio de trabajo que no está a la vista: su estado editable, como en una
+/// sesión, y el archivo del que salió el texto fuente.
+pub struct Workspace {
+    pub session: Session,
+    pub source_path: Option<PathBuf>,
+}
+
+/// Aviso pasajero en la esquina inferior derecha; desaparece solo al cabo de
+/// `TOAST_TTL` (los de error, de `ERROR_TOAST_TTL`).
+pub struct Toast {
+    pub text: String,
+    pub error: bool,
+    pub shown: Instant,
+}
+
+/// Estado del depurador: los pasos calculados al abrirlo y el que se muestra.
+pub struct Debugger {
+    pub steps: Vec<Step>,
+    pub current: usize,
+    /// Nombre de cada grupo de captura (índice 0: la coincidencia entera).
+    pub group_names: Vec<Option<String>>,
+    /// Avanza solo, un paso cada `DEBUG_INTERVAL`.
+    pub playing: bool,
+    pub last_step: Instant,
+}
+
+/// Paneles que se pueden ampliar a pantalla completa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Source,
+    Output,
+}
+
+/// Carga de archivo en curso, cancelable desde la interfaz.
+pub struct Loading {
+    pub path: PathBuf,
+    pub read: u64,
+    pub total: u64,
+    pub cancel: Arc<AtomicBool>,
+}
+
+/// Pasos del recorrido de bienvenida: el panel que se resalta y la acción cuya
+/// tecla se muestra. Los textos están en `i18n::tour`.
+pub const TOUR: [(Option<PaneKind>, Option<Action>); 6] = [
+    (None, None),
+    (Some(PaneKind::Source), Some(Action::EditSource)),
+    (Some(PaneKind::Regex), Some(Action::EditRegex)),
+    (Some(PaneKind::Replace), Some(Action::EditReplace)),
+    (Some(PaneKind::Output), Some(Action::ToggleZoom)),
+    (Some(PaneKind::Status), Some(Action::ShowHelp)),
+];
+
+/// Pausa sin teclas tras la cual se recalcula la salida.
+const TRANSFORM_DEBOUNCE: Duration = Duration::from_millis(40);
+
+/// Avisos visibles a la vez; uno nuevo descarta el más viejo.
+const MAX_TOASTS: usize = 4;
+const TOAST_TTL: Duration = Duration::from_secs(4);
+const ERROR_TOAST_TTL: Duration = Duration::from_secs(8);
+
+/// Pasos que calcula el depurador como máximo, y pausa entre pasos al reproducir.
+const DEBUG_STEPS: usize = 50_000;
+const DEBUG_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Coincidencias que se describen una por una en el modo de lector de pantalla.
+const DESCRIBED_MATCHES: usize = 1000;
+
+/// Coincidencias que se ubican para los marcadores del margen del texto fuente.
+const MARKED_MATCHES: usize = 100_000;
+
+/// Variantes de un ejemplo que se generan para la lista de casi coincidencias.
+const NEAR_MISSES: usize = 40;
+
+/// Tamaño de cada entrada de la prueba de estrés, en bytes.
+const STRESS_SIZE: usize = 1_000_000;
+
+pub struct App {
+    /// Texto fuente en un rope: editar y desplazarse por líneas no recorre ni
+    /// copia el documento entero.
+    pub source_text: Rope,
+    pub regex_input: String,
+    /// Posición (en bytes) del cursor en el patrón; se lee con `regex_cursor()`,
+    /// que la ajusta si el patrón cambió por otro lado.
+    pub regex_cursor: usize,
+    pub replace_input: String,
+    /// Casos de prueba, uno por línea, y el resultado de cada uno con el patrón actual.
+    pub test_cases: String,
+    pub case_results: Vec<CaseResult>,
+    /// Salida de referencia de la sesión (ver `Session::golden`).
+    pub golden: Option<String>,
+    pub output_text: String,
+    /// La salida es el texto fuente tal cual (patrón vacío); `output_text` no se usa.
+    pub output_is_source: bool,
+    pub input_mode: InputMode,
+    pub processing_unit: ProcessingUnit,
+    pub flags: RegexFlags,
+    /// Indicación persistente de la barra de ayuda (listo, pedido de clave).
+    pub status_message: String,
+    /// Avisos pasajeros, del más viejo al más nuevo.
+    pub toasts: VecDeque<Toast>,
+    pub match_count: usize,
+    /// Coincidencias que empiezan en cada línea del texto fuente (desde 1), para
+    /// los marcadores del margen.
+    pub match_lines: HashMap<usize, usize>,
+    /// La salida mostrada se cortó en `limits.output_size_limit`.
+    pub output_truncated: bool,
+    /// La salida es el mensaje de error de compilación del patrón.
+    pub output_error: bool,
+    pub source_scroll: u16,
+    pub output_scroll: u16,
+    pub log_path: Option<PathBuf>,
+    pub session_path: Option<PathBuf>,
+    pub config: Config,
+    pub palette: Palette,
+    pub keymap: Keymap,
+    pub language: Language,
+    /// Modo para lectores de pantalla (`accessibility.screen_reader`).
+    pub screen_reader: bool,
+    pub history: History,
+    pub history_query: String,
+    pub history_selected: usize,
+    pub library: Library,
+    pub library_selected: usize,
+    pub library_sort: LibrarySort,
+    pub library_tag: Option<String>,
+    /// Coincidencias de cada entrada sobre el texto fuente, calculadas al abrir la biblioteca.
+    pub library_preview: LibraryPreview,
+    pub name_input: String,
+    pub description_input: String,
+    pub example_input: String,
+    pub tags_input: String,
+    pub project: Option<OpenProject>,
+    pub source_path: Option<PathBuf>,
+    pub path_input: String,
+    pub recent: RecentFiles,
+    pub recent_selected: usize,
+    pub worker: Worker,
+    /// Regex del patrón actual para las comprobaciones de `run_checks`.
+    pub regex_cache: RegexCache,
+    pub transform_due: Option<Instant>,
+    pub last_autosave: Instant,
+    pub config_modified: Option<SystemTime>,
+    pub last_config_check: Instant,
+    pub autosaved: Option<Session>,
+    pub pending_import: Option<Vec<LibraryEntry>>,
+    /// Entrada por guardar cuyos casos de prueba empeoran, a la espera de confirmación.
+    pub pending_entry: Option<LibraryEntry>,
+    pub loading: Option<Loading>,
+    /// Inicio de un archivo cuya carga se canceló, a la espera de conservarlo o no.
+    pub partial_load: Option<(PathBuf, String)>,
+    pub catalog_selected: usize,
+    pub lessons: Vec<Lesson>,
+    pub progress: Progress,
+    /// Ejercicio en curso, como (lección, ejercicio), comprobado con cada cambio del patrón.
+    pub exercise: Option<(usize, usize)>,
+    pub exercise_selected: usize,
+    pub export_selected: usize,
+    /// Fragmento del destino elegido en el exportador, calculado al abrirlo y al
+    /// cambiar de destino.
+    pub export_preview: String,
+    pub secret_input: String,
+    pub command_query: String,
+    pub command_selected: usize,
+    pub help_scroll: u16,
+    pub tour_step: usize,
+    pub debugger: Option<Debugger>,
+    /// Tramos del texto fuente (en bytes) que el patrón debería encontrar, marcados a mano.
+    pub expected_spans: Vec<Range<usize>>,
+    /// Tramos que encuentra el patrón; solo se calculan si hay alguno marcado.
+    pub actual_spans: Vec<Range<usize>>,
+    /// Cursor del modo de marcado (byte del texto fuente) y el inicio del tramo en curso.
+    pub mark_cursor: usize,
+    pub mark_anchor: Option<usize>,
+    /// Ejemplo que el patrón acepta completo y sus variantes, calculados al abrir la lista.
+    pub near_misses: Option<(String, Vec<NearMiss>)>,
+    pub near_miss_selected: usize,
+    pub modal: Option<Modal>,
+    /// Espacios de trabajo, uno por pestaña. El activo es `None`: su estado vive
+    /// en los campos de `App` (texto fuente, patrón, reemplazo, flags…).
+    pub workspaces: Vec<Option<Workspace>>,
+    pub active_workspace: usize,
+    /// Hay una consulta a la IA en curso.
+    pub ai_pending: bool,
+    pub tasks: mpsc::UnboundedSender<Background>,
+    pub finished: mpsc::UnboundedReceiver<Background>,
+    /// Se pidió salir; el bucle principal termina al ver este flag.
+    pub quit: bool,
+    /// Algo visible cambió desde el último dibujo; el bucle solo redibuja entonces.
+    pub dirty: bool,
+    pub show_stats: bool,
+    /// Distribución activa y porcentaje del espacio compartido (alto, o ancho
+    /// lado a lado) que ocupa la salida.
+    pub layout: LayoutPreset,
+    pub split: Split,
+    pub output_share: u16,
+    /// Último panel desplazado o editado, y el que ocupa toda la pantalla, si alguno.
+    pub focus: Pane,
+    pub zoomed: Option<Pane>,
+    /// Tiempos del patrón actual y del anterior, para compararlos.
+    pub timings: Option<(String, Timings)>,
+    pub previous_timings: Option<(String, Timings)>,
+    /// Diagnóstico del motor para el patrón y los flags con que se calculó.
+    pub introspection: Option<(String, RegexFlags, Introspection)>,
+    /// Alternativa de primer nivel de cada coincidencia, con el panel de tiempos abierto.
+    pub branches: Option<BranchSummary>,
+    /// Partes del patrón que participaron en alguna coincidencia, si se pidió verlas.
+    pub coverage: Option<Coverage>,
+    pub show_coverage: bool,
+    /// Patrón B, evaluado sobre el mismo texto para compararlo con el principal.
+    pub compare_input: String,
+    /// Coincidencias de ambos patrones (`correct`), solo del principal
+    /// (`missed`) y solo del B (`extra`), o el error del B.
+    pub comparison: Option<Result<SpanScore, String>>,
+    /// Patrón medido con entradas armadas para forzarlo, y cómo le fue en cada una.
+    pub stress: Option<(String, Vec<StressCase>)>,
+    /// Hay una prueba de estrés en curso.
+    pub stress_pending: bool,
+}
+
+impl Default for App {
+    fn default() -> App {
+        let (tasks, finished) = mpsc::unbounded_channel();
+        let mut app = App {
+            source_text: Rope::from_str("Praliné saber no ocupa el lugar de argentino."),
+            regex_input: String::new(),
+            regex_cursor: 0,
+            replace_input: String::new(),
+            test_cases: String::new(),
+            golden: None,
+            case_results: Vec::new(),
+            output_text: String::new(),
+            output_is_source: false,
+            input_mode: InputMode::Normal,
+            processing_unit: ProcessingUnit::WholeText,
+            flags: RegexFlags::default(),
+            status_message: String::new(),
+            toasts: VecDeque::new(),
+            match_count: 0,
+            match_lines: HashMap::new(),
+            output_truncated: false,
+            output_error: false,
+            source_scroll: 0,
+            output_scroll: 0,
+            log_path: None,
+            session_path: None,
+            config: Config::default(),
+            palette: Palette::default(),
+            keymap: Keymap::default(),
+            language: Language::default(),
+            screen_reader: false,
+            history: History::default(),
+            history_query: String::new(),
+            history_selected: 0,
+            library: Library::default(),
+            library_selected: 0,
+            library_sort: LibrarySort::Recent,
+            library_tag: None,
+            library_preview: LibraryPreview::default(),
+            name_input: String::new(),
+            description_input: String::new(),
+            example_input: String::new(),
+            tags_input: String::new(),
+            project: None,
+            source_path: None,
+            path_input: String::new(),
+            recent: RecentFiles::default(),
+            recent_selected: 0,
+            worker: Worker::spawn(),
+            regex_cache: RegexCache::default(),
+            transform_due: None,
+            last_autosave: Instant::now(),
+            config_modified: None,
+            last_config_check: Instant::now(),
+            autosaved: None,
+            pending_import: None,
+            pending_entry: None,
+            loading: None,
+            partial_load: None,
+            catalog_selected: 0,
+            lessons: lessons::lessons(),
+            progress: Progress::default(),
+            exercise: None,
+            exercise_selected: 0,
+            export_selected: 0,
+            export_preview: String::new(),
+            secret_input: String::new(),
+            command_query: String::new(),
+            command_selected: 0,
+            help_scroll: 0,
+            tour_step: 0,
+            debugger: None,
+            expected_spans: Vec::new(),
+            actual_spans: Vec::new(),
+            mark_cursor: 0,
+            mark_anchor: None,
+            near_misses: None,
+            near_miss_selected: 0,
+            modal: None,
+            workspaces: vec![None],
+            active_workspace: 0,
+            ai_pending: false,
+            tasks,
+            finished,
+            quit: false,
+            dirty: true,
+            show_stats: false,
+            layout: LayoutPreset::Balanced,
+            split: Split::Vertical,
+            output_share: LayoutPreset::Balanced.output_share(),
+            focus: Pane::Output,
+            zoomed: None,
+            timings: None,
+            previous_timings: None,
+            introspection: None,
+            branches: None,
+            coverage: None,
+            show_coverage: false,
+            compare_input: String::new(),
+            comparison: None,
+            stress: None,
+            stress_pending: false,
+        };
+        app.status_message = app.ready_message();
+        app
+    }
+}
+
+
+
+impl App {
+    /// Construye la aplicación a partir de la configuración del usuario.
+    pub fn new(config: Config) -> Result<App> {
+        let palette = theme::palette(&config.colors)?;
+        let keymap = Keymap::from_config(&config.keys)?;
+        let initial_source = config.startup.initial_source()?;
+        let mut app = App {
+            flags: config.flags,
+            layout: config.layout.preset,
+            split: config.layout.split,
+            output_share: config.layout.preset.output_share(),
+            palette,
+            keymap,
+            language: i18n::detect(config.language),
+            screen_reader: config.accessibility.screen_reader,
+            config,
+            history: History::load()?,
+            library: Library::load()?,
+            recent: RecentFiles::load()?,
+            progress: Progress::load()?,
+            config_modified: Config::modified(),
+            ..App::default()
+        };
+        if let Some(text) = initial_source {
+            app.source_text = Rope::from(text);
+        }
+        app.status_message = app.ready_message();
+        if app.config.sync.enabled() {
+            app.sync_library();
+        }
+        Ok(app)
+    }
+
+    /// Mensaje inicial con los atajos principales según el mapa de teclas activo.
+    pub fn ready_message(&self) -> String {
+        let hints = [
+            (Action::EditSource, tr(self.language, Text::SourceHint)),
+            (Action::EditRegex, "Regex"),
+            (Action::EditReplace, tr(self.language, Text::ReplaceHint)),
+            (Action::SuggestAi, tr(self.language, Text::Ai)),
+            (Action::CommitResult, tr(self.language, Text::CommitHint)),
+            (Action::ToggleUnit, tr(self.language, Text::UnitHint)),
+        ];
+        let hints: Vec<String> = hints
+            .iter()
+            .map(|(action, label)| format!("'{}': {}", self.keymap.key_label(*action), label))
+            .collect();
+        let flags = [
+            Action::ToggleCaseInsensitive,
+            Action::ToggleMultiLine,
+            Action::ToggleDotAll,
+            Action::ToggleIgnoreWhitespace,
+        ]
+        .map(|action| self.keymap.key_label(action));
+        format!("{} {}, '{}': Flags", tr(self.language, Text::Ready), hints.join(", "), flags.join("/"))
+    }
+
+    /// Ejecuta una acción del modo EXPLORAR (todas salvo `Quit`, que maneja el bucle).
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::Quit => {}
+            Action::EditSource => {
+                self.focus = Pane::Source;
+                self.input_mode = InputMode::EditingSource;
+                self.source_text = Rope::new();
+            }
+            Action::EditRegex => {
+                self.zoomed = None;
+                self.input_mode = InputMode::EditingRegex;
+                self.regex_input.clear();
+                self.regex_cursor = 0;
+            }
+            Action::EditReplace => {
+                self.zoomed = None;
+                self.input_mode = InputMode::EditingReplace;
+                self.replace_input.clear();
+            }
+            Action::Debug => self.start_debugger(),
+            Action::NearMisses => self.open_near_misses(),
+            Action::EditCompare => {
+                self.zoomed = None;
+                self.input_mode = InputMode::EditingCompare;
+            }
+            Action::StressTest => self.stress_test(),
+            Action::MarkSpans => {
+                self.zoomed = None;
+                self.focus = Pane::Source;
+                self.mark_cursor = self.mark_cursor.min(self.source_text.len_bytes());
+                self.mark_anchor = None;
+                self.input_mode = InputMode::MarkingSpans;
+            }
+            Action::EditTests => {
+                self.zoomed = None;
+                self.input_mode = InputMode::EditingTests;
+            }
+            Action::SuggestAi => self.suggest_ai(),
+            Action::CommitResult => self.commit_result(),
+            Action::OpenHistory => self.open_history(),
+            Action::OpenLibrary => {
+                self.library_selected = 0;
+                self.refresh_library_preview();
+                self.input_mode = InputMode::Library;
+            }
+            Action::SavePattern => {
+                self.name_input.clear();
+                self.input_mode = InputMode::NamingPattern;
+            }
+            Action::SaveSession => self.save_session(),
+            Action::LoadSession => self.load_session(),
+            Action::RecordGolden => self.record_golden(),
+            Action::CheckGolden => self.check_golden(),
+            Action::NextSample => self.next_sample(),
+            Action::OpenFile => {
+                self.path_input.clear();
+                self.input_mode = InputMode::OpeningFile;
+            }
+            Action::OpenRecent => {
+                self.recent_selected = 0;
+                self.input_mode = InputMode::Recent;
+            }
+            Action::ExportLibrary => {
+                self.path_input = "biblioteca.json".to_string();
+                self.input_mode = InputMode::ExportingLibrary;
+            }
+            Action::ExportReport => {
+                self.path_input = "informe.html".to_string();
+                self.input_mode = InputMode::ExportingReport;
+            }
+            Action::ImportRegex101 => {
+                self.path_input.clear();
+                self.input_mode = InputMode::ImportingRegex101;
+            }
+            Action::ImportTests => {
+                self.path_input.clear();
+                self.input_mode = InputMode::ImportingTests;
+            }
+            Action::ImportLibrary => {
+                self.path_input.clear();
+                self.input_mode = InputMode::ImportingLibrary;
+            }
+            Action::OpenExercises => {
+                // Vuelve al ejercicio en curso, o al primero sin resolver.
+                let entries = self.exercise_entries();
+                self.exercise_selected = match self.exercise {
+                    Some(current) => entries.iter().position(|&entry| entry == current).unwrap_or(0),
+                    None => entries
+                        .iter()
+                        .position(|&(lesson, index)| {
+                            !self.progress.is_solved(&lessons::exercise_key(&self.lessons[lesson], index))
+                        })
+                        .unwrap_or(0),
+                };
+                self.input_mode = InputMode::Exercises;
+            }
+            Action::OpenCatalog => {
+                self.catalog_selected = 0;
+                self.input_mode = InputMode::Catalog;
+            }
+            Action::ExportCode => {
+                self.select_export(0);
+                self.input_mode = InputMode::ExportingCode;
+            }
+            Action::SetApiKey => self.start_api_key_setup(),
+            Action::CommandPalette => {
+                self.command_query.clear();
+                self.command_selected = 0;
+                self.input_mode = InputMode::CommandPalette;
+            }
+            Action::ScrollSourceUp => self.scroll(Pane::Source, -1),
+            Action::ScrollSourceDown => self.scroll(Pane::Source, 1),
+            Action::ScrollOutputUp => self.scroll(Pane::Output, -5),
+            Action::ScrollOutputDown => self.scroll(Pane::Output, 5),
+            Action::ShowHelp => {
+                self.help_scroll = 0;
+                self.input_mode = InputMode::Help;
+            }
+            Action::ToggleZoom => self.zoomed = if self.zoomed.is_some() { None } else { Some(self.focus) },
+            Action::ToggleUnit => {
+                self.processing_unit = self.processing_unit.toggle();
+                self.notify(i18n::notice(self.language, Notice::ProcessingUnit(i18n::unit_name(self.language, self.processing_unit))));
+            }
+            Action::ToggleCaseInsensitive => {
+                self.flags.case_insensitive = !self.flags.case_insensitive;
+                self.notify(i18n::notice(self.language, Notice::Flags(&self.flags.summary())));
+            }
+            Action::ToggleMultiLine => {
+                self.flags.multi_line = !self.flags.multi_line;
+                self.notify(i18n::notice(self.language, Notice::Flags(&self.flags.summary())));
+            }
+            Action::ToggleDotAll => {
+                self.flags.dot_matches_new_line = !self.flags.dot_matches_new_line;
+                self.notify(i18n::notice(self.language, Notice::Flags(&self.flags.summary())));
+            }
+            Action::ToggleIgnoreWhitespace => {
+                self.flags.ignore_whitespace = !self.flags.ignore_whitespace;
+                self.notify(i18n::notice(self.language, Notice::Flags(&self.flags.summary())));
+            }
+            Action::ToggleStats => self.show_stats = !self.show_stats,
+            Action::ToggleCoverage => {
+                self.show_coverage = !self.show_coverage;
+                if !self.show_coverage {
+                    self.coverage = None;
+                } else if coverage::tag_parts(&self.regex_input, &self.engine_options()).is_none() {
+                    self.notify(tr(self.language, Text::NothingToCover));
+                }
+            }
+            Action::CycleLayout => {
+                self.layout = self.layout.next();
+                self.output_share = self.layout.output_share();
+                self.notify(i18n::notice(self.language, Notice::Layout(i18n::layout_name(self.language, self.layout))));
+            }
+            Action::GrowOutput => self.output_share = (self.output_share + 10).min(90),
+            Action::ShrinkOutput => self.output_share = self.output_share.saturating_sub(10).max(10),
+            Action::ToggleSplit => {
+                self.split = self.split.toggle();
+                self.notify(i18n::notice(self.language, Notice::Split(i18n::split_name(self.language, self.split))));
+            }
+            Action::NewWorkspace => self.new_workspace(),
+            Action::CloseWorkspace => self.close_workspace(),
+            Action::NextWorkspace => self.switch_workspace((self.active_workspace + 1) % self.workspaces.len()),
+            Action::PreviousWorkspace => {
+                let count = self.workspaces.len();
+                self.switch_workspace((self.active_workspace + count - 1) % count);
+            }
+        }
+    }
+
+    /// Guarda el estado a la vista en su pestaña.
+    fn stash_workspace(&mut self) {
+        self.workspaces[self.active_workspace] =
+            Some(Workspace { session: self.to_session(), source_path: self.source_path.take() });
+    }
+
+    /// Pone a la vista la pestaña `index`, que debe estar guardada.
+    fn show_workspace(&mut self, index: usize) {
+        let Some(workspace) = self.workspaces[index].take() else {
+            return;
+        };
+        self.active_workspace = index;
+        self.restore_session(workspace.session);
+        self.source_path = workspace.source_path;
+        self.zoomed = None;
+        self.apply_transform();
+        self.notify(i18n::notice(self.language, Notice::Workspace(index + 1, self.workspaces.len())));
+    }
+
+    /// Abre una pestaña vacía y pasa a ella.
+    pub fn new_workspace(&mut self) {
+        self.stash_workspace();
+        let session = Session {
+            source_text: String::new(),
+            regex_input: String::new(),
+            replace_input: String::new(),
+            flags: self.config.flags,
+            processing_unit: ProcessingUnit::WholeText,
+            input_mode: InputMode::Normal,
+            source_scroll: 0,
+            output_scroll: 0,
+            test_cases: String::new(),
+            golden: None,
+        };
+        self.workspaces.push(Some(Workspace { session, source_path: None }));
+        self.show_workspace(self.workspaces.len() - 1);
+    }
+
+    /// Pasa a la pestaña `index` (desde 0); si no existe o ya es la activa, no hace nada.
+    pub fn switch_workspace(&mut self, index: usize) {
+        if index == self.active_workspace || index >= self.workspaces.len() {
+            return;
+        }
+        self.stash_workspace();
+        self.show_workspace(index);
+    }
+
+    /// Descarta la pestaña activa y muestra la vecina. La última no se cierra.
+    pub fn close_workspace(&mut self) {
+        if self.workspaces.len() == 1 {
+            self.notify(tr(self.language, Text::OnlyWorkspace));
+            return;
+        }
+        self.workspaces.remove(self.active_workspace);
+        let index = self.active_workspace.min(self.workspaces.len() - 1);
+        self.show_workspace(index);
+    }
+
+    pub fn engine_options(&self) -> EngineOptions {
+        EngineOptions {
+            unit: self.processing_unit,
+            flags: self.flags,
+            size_limit: self.config.limits.regex_size_limit,
+        }
+    }
+
+    /// Encarga el cálculo de la salida al hilo de trabajo; el resultado se
+    /// recoge en `receive_transform`.
+    pub fn apply_transform(&mut self) {
+        self.transform_due = None;
+        self.dirty = true;
+        if self.regex_input.is_empty() {
+            // Sin patrón la salida es el propio texto fuente: no hace falta copiarlo.
+            self.worker.invalidate();
+            self.output_is_source = true;
+            self.output_truncated = false;
+            self.output_error = false;
+            self.match_count = 0;
+            self.match_lines.clear();
+            self.output_text.clear();
+            self.case_results.clear();
+            self.branches = None;
+            self.coverage = None;
+            self.actual_spans.clear();
+            self.comparison = None;
+            return;
+        }
+        // Los tramos marcados que quedaron fuera del texto (porque se acortó) se descartan.
+        let len = self.source_text.len_bytes();
+        self.expected_spans.retain(|span| span.end <= len);
+        let options = self.engine_options();
+        self.worker.submit(
+            &self.source_text,
+            &self.regex_input,
+            &self.replace_input,
+            options,
+            self.config.limits.output_size_limit,
+            Analyses {
+                limit: MARKED_MATCHES,
+                timings: self.show_stats,
+                branches: self.show_stats,
+                coverage: self.show_coverage,
+                spans: !self.expected_spans.is_empty(),
+                compare: (!self.compare_input.is_empty()).then(|| self.compare_input.clone()),
+            },
+        );
+        self.run_checks();
+    }
+
+    /// Comprobaciones que no recorren el texto fuente: casos de prueba y
+    /// ejercicio. Se hacen aquí y no al recibir la salida porque cambian también
+    /// sin que cambie el patrón (y entonces el hilo de trabajo no recalcula nada);
+    /// la regex se compila una vez por patrón.
+    fn run_checks(&mut self) {
+        self.run_test_cases();
+        self.check_exercise();
+    }
+
+    pub fn receive_transform(&mut self) {
+        let Some((result, findings, timings)) = self.worker.poll() else {
+            return;
+        };
+        self.dirty = true;
+        if let Some(timings) = timings {
+            self.record_timings(timings);
+        }
+        self.output_is_source = false;
+        self.output_truncated = false;
+        self.output_error = false;
+        match result {
+            Ok(result) => {
+                self.match_count = result.match_count;
+                self.output_truncated = result.truncated;
+                // Un reemplazo sin coincidencias deja el texto fuente igual.
+                self.output_is_source = !self.replace_input.is_empty() && result.match_count == 0;
+                self.branches = findings.branches;
+                self.coverage = findings.coverage;
+                self.actual_spans = findings.spans;
+                self.comparison = findings.comparison;
+                self.match_lines.clear();
+                for location in &findings.locations {
+                    *self.match_lines.entry(location.line).or_default() += 1;
+                }
+                if self.replace_input.is_empty() && !self.regex_input.is_empty() && result.match_count == 0 {
+                    self.output_text = tr(self.language, Text::NoMatches).to_string();
+                } else if self.screen_reader && self.replace_input.is_empty() && result.match_count > 0 {
+                    self.output_text = self.describe_matches(&findings.locations, result.match_count);
+                } else {
+                    self.output_text = result.output;
+                }
+            }
+            Err(e) => {
+                self.match_count = 0;
+                self.match_lines.clear();
+                self.case_results.clear();
+                self.branches = None;
+                self.coverage = None;
+                self.actual_spans.clear();
+                self.comparison = None;
+                self.output_error = true;
+                self.output_text = i18n::notice(self.language, Notice::RegexError(&e));
+            }
+        }
+    }
+
+    /// Evalúa los casos de prueba con el patrón actual.
+    fn run_test_cases(&mut self) {
+        let options = self.engine_options();
+        self.case_results = match self.regex_cache.get(&self.regex_input, &options) {
+            Ok(re) => cases::evaluate(re, &self.test_cases),
+            Err(_) => Vec::new(),
+        };
+    }
+
+    /// Mide el patrón con entradas armadas para forzarlo, en segundo plano para
+    /// no trabar la interfaz; el resultado llega como `Background::StressMeasured`
+    /// y se muestra en el panel de tiempos.
+    pub fn stress_test(&mut self) {
+        if self.regex_input.is_empty() {
+            self.notify(tr(self.language, Text::PatternFirst));
+            return;
+        }
+        if self.stress_pending {
+            return;
+        }
+        let pattern = self.regex_input.clone();
+        let options = self.engine_options();
+        let tasks = self.tasks.clone();
+        self.stress_pending = true;
+        self.notify(tr(self.language, Text::Stressing));
+        tokio::task::spawn_blocking(move || {
+            let cases = stress::stress(&pattern, &options, STRESS_SIZE);
+            let _ = tasks.send(Background::StressMeasured(pattern, cases));
+        });
+    }
+
+    pub fn stress_measured(&mut self, pattern: String, cases: Result<Vec<StressCase>, String>) {
+        self.stress_pending = false;
+        match cases {
+            Ok(cases) => {
+                match cases.first() {
+                    Some(worst) if worst.is_slow() => {
+                        self.notify_error(i18n::notice(self.language, Notice::SlowInput(&worst.name, worst.mb_per_sec())))
+                    }
+                    _ => self.notify(tr(self.language, Text::NoSlowInput)),
+                }
+                self.stress = Some((pattern, cases));
+                self.show_stats = true;
+            }
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::InvalidPattern(&e))),
+        }
+    }
+
+    /// Arma un ejemplo que el patrón acepta y lista sus variantes de un carácter.
+    pub fn open_near_misses(&mut self) {
+        if self.regex_input.is_empty() {
+            self.notify(tr(self.language, Text::PatternFirst));
+            return;
+        }
+        match nearmiss::near_misses(&self.regex_input, &self.engine_options(), NEAR_MISSES) {
+            Ok(Some(near_misses)) => {
+                self.near_misses = Some(near_misses);
+                self.near_miss_selected = 0;
+                self.input_mode = InputMode::NearMisses;
+            }
+            Ok(None) => self.notify(tr(self.language, Text::NoFullExample)),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::InvalidPattern(&e))),
+        }
+    }
+
+    /// Agrega la variante seleccionada al panel de casos, como texto que debe
+    /// coincidir o no según `must_match`.
+    pub fn add_near_miss_case(&mut self, must_match: bool) {
+        let Some(miss) = self.near_misses.as_ref().and_then(|(_, misses)| misses.get(self.near_miss_selected)) else {
+            return;
+        };
+        let line = format!("{} {}", if must_match { '+' } else { '-' }, miss.text);
+        if !self.test_cases.is_empty() && !self.test_cases.ends_with('\n') {
+            self.test_cases.push('\n');
+        }
+        self.test_cases.push_str(&line);
+        self.notify(i18n::notice(self.language, Notice::CaseAdded(&line)));
+    }
+
+    /// Comparación de los tramos marcados con los encontrados; `None` si no hay marcados.
+    pub fn span_score(&self) -> Option<SpanScore> {
+        (!self.expected_spans.is_empty()).then(|| assertions::score(&self.expected_spans, &self.actual_spans))
+    }
+
+    /// Mueve el cursor de marcado `chars` caracteres.
+    pub fn move_mark_cursor(&mut self, chars: isize) {
+        let rope = &self.source_text;
+        let index = rope.byte_to_char(self.mark_cursor).saturating_add_signed(chars).min(rope.len_chars());
+        self.mark_cursor = rope.char_to_byte(index);
+    }
+
+    /// Mueve el cursor de marcado `lines` líneas, en la misma columna si cabe.
+    pub fn move_mark_line(&mut self, lines: isize) {
+        let rope = &self.source_text;
+        let char_index = rope.byte_to_char(self.mark_cursor);
+        let line = rope.char_to_line(char_index);
+        let column = char_index - rope.line_to_char(line);
+        let target = line.saturating_add_signed(lines).min(rope.len_lines() - 1);
+        let text = rope.line(target);
+        let mut width = text.len_chars();
+        while width > 0 && matches!(text.char(width - 1), '\n' | '\r') {
+            width -= 1;
+        }
+        self.mark_cursor = rope.char_to_byte(rope.line_to_char(target) + column.min(width));
+    }
+
+    /// Empieza un tramo en el cursor o, si hay uno empezado, lo cierra ahí.
+    pub fn toggle_mark(&mut self) {
+        let Some(anchor) = self.mark_anchor.take() else {
+            self.mark_anchor = Some(self.mark_cursor);
+            return;
+        };
+        let span = anchor.min(self.mark_cursor)..anchor.max(self.mark_cursor);
+        if span.is_empty() {
+            return;
+        }
+        self.expected_spans.retain(|existing| existing.end <= span.start || existing.start >= span.end);
+        self.expected_spans.push(span);
+        self.expected_spans.sort_by_key(|span| span.start);
+    }
+
+    /// Quita el tramo marcado que contiene el cursor.
+    pub fn delete_mark(&mut self) {
+        let cursor = self.mark_cursor;
+        self.expected_spans.retain(|span| !span.contains(&cursor));
+    }
+
+    /// Marca el ejercicio en curso como resuelto si el patrón actual lo resuelve.
+    fn check_exercise(&mut self) {
+        let Some((lesson, index)) = self.exercise else {
+            return;
+        };
+        let options = self.engine_options();
+        let Ok(re) = self.regex_cache.get(&self.regex_input, &options) else {
+            return;
+        };
+        if !lessons::check(re, &self.lessons[lesson].exercises[index]) {
+            return;
+        }
+        let key = lessons::exercise_key(&self.lessons[lesson], index);
+        match self.progress.mark_solved(&key) {
+            Ok(true) => {
+                let total = self.exercise_entries().len();
+                self.notify(i18n::notice(self.language, Notice::ExerciseSolved(self.progress.solved_count(), total)));
+            }
+            Ok(false) => {}
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::ProgressNotSaved(&e))),
+        }
+    }
+
+    /// La salida del modo filtro como una línea por coincidencia con su
+    /// posición, para lectores de pantalla. Se describen las primeras
+    /// `DESCRIBED_MATCHES`.
+    fn describe_matches(&self, locations: &[MatchLocation], total: usize) -> String {
+        let described = &locations[..locations.len().min(DESCRIBED_MATCHES)];
+        let mut lines: Vec<String> = described
+            .iter()
+            .enumerate()
+            .map(|(index, location)| i18n::describe_match(self.language, index + 1, total, location))
+            .collect();
+        if total > described.len() {
+            lines.push(i18n::more_matches(self.language, total - described.len()));
+        }
+        lines.join("\n")
+    }
+
+    pub fn push_source(&mut self, c: char) {
+        self.source_text.insert_char(self.source_text.len_chars(), c);
+    }
+
+    pub fn pop_source(&mut self) {
+        let len = self.source_text.len_chars();
+        if len > 0 {
+            self.source_text.remove(len - 1..len);
+        }
+    }
+
+    /// Posición del cursor en el patrón, dentro de él y en el borde de un carácter.
+    pub fn regex_cursor(&self) -> usize {
+        let mut cursor = self.regex_cursor.min(self.regex_input.len());
+        while !self.regex_input.is_char_boundary(cursor) {
+            cursor -= 1;
+        }
+        cursor
+    }
+
+    /// Escribe `c` en el patrón, en el cursor.
+    pub fn insert_regex(&mut self, c: char) {
+        let cursor = self.regex_cursor();
+        self.regex_input.insert(cursor, c);
+        self.regex_cursor = cursor + c.len_utf8();
+    }
+
+    /// Borra el carácter anterior al cursor o, con `forward`, el siguiente.
+    pub fn delete_regex(&mut self, forward: bool) {
+        let cursor = self.regex_cursor();
+        let removed = if forward {
+            self.regex_input[cursor..].chars().next().map(|_| cursor)
+        } else {
+            self.regex_input[..cursor].char_indices().next_back().map(|(at, _)| at)
+        };
+        if let Some(at) = removed {
+            self.regex_input.remove(at);
+            self.regex_cursor = at;
+        }
+    }
+
+    /// Mueve el cursor del patrón `chars` caracteres.
+    pub fn move_regex_cursor(&mut self, chars: isize) {
+        let cursor = self.regex_cursor();
+        self.regex_cursor = if chars < 0 {
+            let before = &self.regex_input[..cursor];
+            before.char_indices().rev().nth(chars.unsigned_abs() - 1).map_or(0, |(at, _)| at)
+        } else {
+            let after = &self.regex_input[cursor..];
+            cursor + after.char_indices().nth(chars as usize).map_or(after.len(), |(at, _)| at)
+        };
+    }
+
+    /// Guarda los tiempos del patrón actual; si el patrón cambió, los que había
+    /// pasan a ser los del anterior.
+    fn record_timings(&mut self, timings: Timings) {
+        if self.timings.as_ref().is_some_and(|(pattern, _)| *pattern != self.regex_input) {
+            self.previous_timings = self.timings.take();
+        }
+        tracing::info!(
+            pattern = %self.regex_input,
+            compile = ?timings.compile,
+            search = ?timings.search,
+            replace = ?timings.replace,
+            matches = timings.matches,
+            "tiempos"
+        );
+        self.timings = Some((self.regex_input.clone(), timings));
+        let current = self
+            .introspection
+            .as_ref()
+            .is_some_and(|(pattern, flags, _)| *pattern == self.regex_input && *flags == self.flags);
+        if !current {
+            self.introspection = introspect::introspect(&self.regex_input, &self.engine_options())
+                .ok()
+                .map(|introspection| (self.regex_input.clone(), self.flags, introspection));
+        }
+    }
+
+    /// Pide recalcular la salida tras una pausa breve, para no hacerlo en cada
+    /// tecla al escribir rápido.
+    pub fn schedule_transform(&mut self) {
+        self.transform_due = Some(Instant::now() + TRANSFORM_DEBOUNCE);
+    }
+
+    pub fn transform_tick(&mut self) {
+        if self.transform_due.is_some_and(|due| Instant::now() >= due) {
+            self.apply_transform();
+        }
+    }
+
+    /// Espera máxima por eventos: hasta el próximo recálculo pendiente, si lo hay.
+    pub fn poll_timeout(&self) -> Duration {
+        let idle = if self.worker.busy() { Duration::from_millis(20) } else { Duration::from_millis(100) };
+        match self.transform_due {
+            Some(due) => due.saturating_duration_since(Instant::now()).min(idle),
+            None => idle,
+        }
+    }
+
+    /// Desplaza `pane` `lines` líneas (hacia arriba si es negativo) y lo deja
+    /// como último panel usado.
+    pub fn scroll(&mut self, pane: Pane, lines: i16) {
+        self.focus = pane;
+        let scroll = match pane {
+            Pane::Source => &mut self.source_scroll,
+            Pane::Output => &mut self.output_scroll,
+        };
+        *scroll = scroll.saturating_add_signed(lines);
+    }
+
+    /// Calcula en segundo plano el recorrido del patrón actual sobre el texto
+    /// fuente; al llegar, `debug_traced` lo abre en el primer paso.
+    pub fn start_debugger(&mut self) {
+        if self.regex_input.is_empty() {
+            self.notify(tr(self.language, Text::NothingToDebug));
+            return;
+        }
+        let re = match regex_wysiwyg::compile(&self.regex_input, &self.engine_options()) {
+            Ok(re) => re,
+            Err(e) => {
+                self.notify_error(i18n::notice(self.language, Notice::RegexError(&e)));
+                return;
+            }
+        };
+        let group_names = re.capture_names().map(|name| name.map(str::to_string)).collect();
+        let pattern = self.regex_input.clone();
+        let source = self.source_text.clone();
+        let unit = self.processing_unit;
+        let tasks = self.tasks.clone();
+        tokio::task::spawn_blocking(move || {
+            let steps = debugger::trace(&re, &source.to_string(), unit, DEBUG_STEPS);
+            let _ = tasks.send(Background::DebugTraced { pattern, source, group_names, steps });
+        });
+    }
+
+    /// Abre el depurador con el recorrido calculado en segundo plano, salvo que
+    /// el patrón o el texto hayan cambiado mientras tanto.
+    pub fn debug_traced(&mut self, pattern: &str, source: &Rope, group_names: Vec<Option<String>>, steps: Vec<Step>) {
+        if pattern != self.regex_input || *source != self.source_text {
+            return;
+        }
+        if steps.is_empty() {
+            self.notify(tr(self.language, Text::NoTextToStep));
+            return;
+        }
+        if steps.len() == DEBUG_STEPS {
+            self.notify(i18n::notice(self.language, Notice::DebugTruncated(DEBUG_STEPS)));
+        }
+        self.zoomed = None;
+        self.focus = Pane::Source;
+        self.debugger = Some(Debugger { steps, current: 0, group_names, playing: false, last_step: Instant::now() });
+        self.input_mode = InputMode::Debugging;
+    }
+
+    /// Avanza (o retrocede, si `delta` es negativo) en el recorrido.
+    pub fn debug_step(&mut self, delta: isize) {
+        if let Some(debugger) = &mut self.debugger {
+            let last = debugger.steps.len().saturating_sub(1);
+            debugger.current = debugger.current.saturating_add_signed(delta).min(last);
+            debugger.last_step = Instant::now();
+            if debugger.current == last {
+                debugger.playing = false;
+            }
+        }
+    }
+
+    pub fn close_debugger(&mut self) {
+        self.debugger = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Avanza un paso si el depurador se está reproduciendo y ya pasó la pausa.
+    pub fn debug_tick(&mut self) {
+        let due = |debugger: &Debugger| debugger.playing && debugger.last_step.elapsed() >= DEBUG_INTERVAL;
+        if self.debugger.as_ref().is_some_and(due) {
+            self.debug_step(1);
+            self.dirty = true;
+        }
+    }
+
+    /// Abre el recorrido de bienvenida desde el primer paso.
+    pub fn start_tour(&mut self) {
+        self.tour_step = 0;
+        self.input_mode = InputMode::Tour;
+    }
+
+    /// Avanza un paso del recorrido; tras el último, lo cierra.
+    pub fn tour_next(&mut self) {
+        if self.tour_step + 1 < TOUR.len() {
+            self.tour_step += 1;
+        } else {
+            self.input_mode = InputMode::Normal;
+        }
+    }
+
+    /// Muestra `text` como aviso pasajero.
+    pub fn notify(&mut self, text: impl Into<String>) {
+        self.push_toast(text.into(), false);
+    }
+
+    /// Como `notify`, pero resaltado como error y visible más tiempo.
+    pub fn notify_error(&mut self, text: impl Into<String>) {
+        self.push_toast(text.into(), true);
+    }
+
+    fn push_toast(&mut self, text: String, error: bool) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast { text, error, shown: Instant::now() });
+        self.dirty = true;
+    }
+
+    /// Descarta los avisos que ya cumplieron su tiempo.
+    pub fn toast_tick(&mut self) {
+        let before = self.toasts.len();
+        self.toasts.retain(|toast| toast.shown.elapsed() < if toast.error { ERROR_TOAST_TTL } else { TOAST_TTL });
+        if self.toasts.len() != before {
+            self.dirty = true;
+        }
+    }
+
+    /// Guarda el patrón actual en el historial persistente.
+    pub fn record_history(&mut self) {
+        if let Err(e) = self.history.record(&self.regex_input, self.flags) {
+            self.notify_error(i18n::notice(self.language, Notice::HistoryNotSaved(&e)));
+        }
+    }
+
+    fn open_history(&mut self) {
+        self.history_query.clear();
+        self.history_selected = 0;
+        self.input_mode = InputMode::History;
+    }
+
+    /// Carga en el editor la entrada seleccionada del historial filtrado.
+    pub fn load_history_selection(&mut self) {
+        let selected = self
+            .history
+            .search(&self.history_query)
+            .get(self.history_selected)
+            .map(|entry| (entry.pattern.clone(), entry.flags));
+        if let Some((pattern, flags)) = selected {
+            self.regex_input = pattern;
+            self.flags = flags;
+            self.notify(tr(self.language, Text::LoadedFromHistory));
+        }
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Tras elegir el nombre, pasa a pedir la descripción (precargando la de una
+    /// entrada existente con ese nombre).
+    pub fn confirm_pattern_name(&mut self) {
+        let name = self.name_input.trim();
+        if name.is_empty() {
+            self.input_mode = InputMode::Normal;
+            self.notify(tr(self.language, Text::EmptyName));
+            return;
+        }
+        let existing = self.library.find(name);
+        self.description_input = existing.map(|entry| entry.description.clone()).unwrap_or_default();
+        self.example_input = existing.map(|entry| entry.example.clone()).unwrap_or_default();
+        self.tags_input = existing.map(|entry| entry.tags.join(", ")).unwrap_or_default();
+        self.input_mode = InputMode::DescribingPattern;
+    }
+
+    /// Guarda el patrón y reemplazo actuales en la biblioteca bajo `name_input`,
+    /// con la descripción y el ejemplo ingresados. Si la entrada ya existe y
+    /// tiene casos de prueba, antes se evalúan con el patrón guardado y con el
+    /// nuevo: si alguno que pasaba ahora falla, se pregunta antes de sobrescribir.
+    pub fn save_to_library(&mut self) {
+        let name = self.name_input.trim().to_string();
+        self.input_mode = InputMode::Normal;
+
+        let existing = self.library.find(&name).cloned();
+        // Sin casos propios se conservan los que ya tenía la entrada.
+        let tests = match &existing {
+            Some(entry) if self.test_cases.trim().is_empty() => entry.tests.clone(),
+            _ => self.test_cases.clone(),
+        };
+        let entry = LibraryEntry {
+            name,
+            pattern: self.regex_input.clone(),
+            replacement: self.replace_input.clone(),
+            flags: self.flags,
+            description: self.description_input.trim().to_string(),
+            example: self.example_input.clone(),
+            tags: self
+                .tags_input
+                .split(',')
+                .map(|tag| tag.trim().to_lowercase())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            tests,
+            ..LibraryEntry::default()
+        };
+        let regressions = existing.map(|old| self.library_regressions(&old, &entry)).unwrap_or_default();
+        if regressions.is_empty() {
+            self.store_library_entry(entry);
+            return;
+        }
+        let message = i18n::regressions_prompt(self.language, &entry.name, &regressions);
+        self.pending_entry = Some(entry);
+        self.ask(Question::LibraryRegressions, tr(self.language, Text::Regressions), message);
+    }
+
+    /// Casos guardados con `old` que su patrón pasa y el de `new` ya no, como
+    /// (texto del caso, motivo). Vacío si alguno de los dos no compila.
+    fn library_regressions(&self, old: &LibraryEntry, new: &LibraryEntry) -> Vec<(String, String)> {
+        if old.tests.trim().is_empty() || (old.pattern == new.pattern && old.flags == new.flags) {
+            return Vec::new();
+        }
+        let options = |flags| EngineOptions { flags, ..self.engine_options() };
+        let (Ok(before), Ok(after)) = (
+            regex_wysiwyg::compile(&old.pattern, &options(old.flags)),
+            regex_wysiwyg::compile(&new.pattern, &options(new.flags)),
+        ) else {
+            return Vec::new();
+        };
+        let lines: Vec<&str> = old.tests.lines().collect();
+        cases::regressions(&before, &after, &old.tests)
+            .into_iter()
+            .map(|(line, reason)| (lines[line].to_string(), reason))
+            .collect()
+    }
+
+    /// Responde al aviso de casos que dejan de pasar: guarda la entrada pendiente
+    /// o la descarta.
+    pub fn resolve_regressions(&mut self, overwrite: bool) {
+        self.input_mode = InputMode::Normal;
+        let Some(entry) = self.pending_entry.take() else {
+            return;
+        };
+        if overwrite {
+            self.store_library_entry(entry);
+        } else {
+            self.notify(i18n::notice(self.language, Notice::NotOverwritten(&entry.name)));
+        }
+    }
+
+    fn store_library_entry(&mut self, entry: LibraryEntry) {
+        let name = entry.name.clone();
+        let overwritten = self.library.upsert(entry);
+        match self.save_library() {
+            Ok(()) if overwritten => self.notify(i18n::notice(self.language, Notice::Overwritten(&name))),
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::SavedToLibrary(&name))),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::LibraryNotSaved(&e))),
+        }
+    }
+
+    pub fn handle_background(&mut self, finished: Background) {
+        self.dirty = true;
+        match finished {
+            Background::LoadProgress { read, total } => {
+                if let Some(loading) = &mut self.loading {
+                    loading.read = read;
+                    loading.total = total;
+                }
+            }
+            Background::FileLoaded(path, text) => self.file_loaded(path, text),
+            Background::LoadCancelled(path, partial) => self.load_cancelled(path, partial),
+            Background::LibraryFetched(entries) => self.library_fetched(entries),
+            Background::AiAnswered(provider, output) => self.ai_answered(&provider, output),
+            Background::DebugTraced { pattern, source, group_names, steps } => {
+                self.debug_traced(&pattern, &source, group_names, steps)
+            }
+            Background::StressMeasured(pattern, cases) => self.stress_measured(pattern, cases),
+        }
+    }
+
+    /// Persiste la biblioteca: en el archivo de proyecto si hay uno abierto, o en
+    /// la biblioteca del usuario.
+    pub fn save_library(&mut self) -> Result<()> {
+        match &mut self.project {
+            Some(open) => {
+                open.project.patterns = self.library.entries().to_vec();
+                open.project.save(&open.path)
+            }
+            None => self.library.save(),
+        }
+    }
+
+    /// Abre un proyecto: su primera muestra pasa a ser el texto fuente y sus
+    /// patrones reemplazan a la biblioteca del usuario mientras esté abierto.
+    pub fn open_project(&mut self, open: OpenProject) {
+        if let Some(sample) = open.current_sample() {
+            self.source_text = Rope::from_str(&sample.text);
+        }
+        self.library = Library::in_memory(open.project.patterns.clone());
+        self.library_selected = 0;
+        let opened = Notice::ProjectOpened {
+            name: &open.project.name,
+            samples: open.project.samples.len(),
+            patterns: open.project.patterns.len(),
+            tests: open.project.tests.len(),
+        };
+        self.notify(i18n::notice(self.language, opened));
+        self.project = Some(open);
+    }
+
+    fn next_sample(&mut self) {
+        let Some(open) = &mut self.project else {
+            self.notify(tr(self.language, Text::NoProject));
+            return;
+        };
+        match open.next_sample() {
+            Some(sample) => {
+                let name = sample.name.clone();
+                self.source_text = Rope::from_str(&sample.text);
+                self.notify(i18n::notice(self.language, Notice::Sample(&name)));
+            }
+            None => self.notify(tr(self.language, Text::NoSamples)),
+        }
+    }
+
+    /// Usa la entrada seleccionada del catálogo: reemplaza el patrón o, con
+    /// `append`, la agrega al final como grupo no capturante.
+    pub fn insert_catalog_selection(&mut self, append: bool) {
+        self.input_mode = InputMode::Normal;
+        let Some(entry) = CATALOG.get(self.catalog_selected) else {
+            return;
+        };
+        if append {
+            self.regex_input.push_str(&format!("(?:{})", entry.pattern));
+            self.notify(i18n::notice(self.language, Notice::AppendedToPattern(entry.name)));
+        } else {
+            self.regex_input = entry.pattern.to_string();
+            self.notify(i18n::notice(self.language, Notice::LoadedFromCatalog(entry.name)));
+        }
+        self.record_history();
+    }
+
+    /// Ejercicios de todas las lecciones en orden, como (lección, ejercicio).
+    pub fn exercise_entries(&self) -> Vec<(usize, usize)> {
+        self.lessons
+            .iter()
+            .enumerate()
+            .flat_map(|(lesson, content)| (0..content.exercises.len()).map(move |index| (lesson, index)))
+            .collect()
+    }
+
+    /// Empieza el ejercicio seleccionado en una pestaña nueva, con su texto
+    /// como fuente y el patrón vacío.
+    pub fn start_exercise(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(&(lesson, index)) = self.exercise_entries().get(self.exercise_selected) else {
+            return;
+        };
+        let exercise = &self.lessons[lesson].exercises[index];
+        let (text, prompt) = (exercise.text.clone(), exercise.prompt.clone());
+        self.new_workspace();
+        self.source_text = Rope::from_str(&text);
+        self.exercise = Some((lesson, index));
+        self.notify(i18n::notice(self.language, Notice::Exercise(&prompt)));
+    }
+
+    /// Selecciona el primer ejercicio de la lección anterior o siguiente
+    /// (`delta` lecciones más allá de la del seleccionado).
+    pub fn jump_lesson(&mut self, delta: isize) {
+        let entries = self.exercise_entries();
+        let Some(&(lesson, _)) = entries.get(self.exercise_selected) else {
+            return;
+        };
+        let target = lesson.saturating_add_signed(delta).min(self.lessons.len().saturating_sub(1));
+        if let Some(index) = entries.iter().position(|&(other, _)| other == target) {
+            self.exercise_selected = index;
+        }
+    }
+
+    /// Muestra la pista del ejercicio seleccionado.
+    pub fn show_exercise_hint(&mut self) {
+        let Some(&(lesson, index)) = self.exercise_entries().get(self.exercise_selected) else {
+            return;
+        };
+        let hint = &self.lessons[lesson].exercises[index].hint;
+        let hint = if hint.is_empty() { tr(self.language, Text::NoHint).to_string() } else { hint.clone() };
+        self.notify(hint);
+    }
+
+    /// Deja el ejercicio en curso; la pestaña y el patrón quedan como están.
+    pub fn leave_exercise(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if self.exercise.take().is_some() {
+            self.notify(tr(self.language, Text::ExerciseLeft));
+        }
+    }
+
+    /// Estado actual para los exportadores, con `source` como texto fuente (el
+    /// rope convertido por quien llama).
+    pub fn export_input<'a>(&'a self, source: &'a str) -> ExportInput<'a> {
+        ExportInput {
+            pattern: &self.regex_input,
+            replacement: &self.replace_input,
+            options: self.engine_options(),
+            source,
+            source_path: self.source_path.as_deref(),
+            language: self.language,
+        }
+    }
+
+    /// Elige el destino `index` del exportador y arma su fragmento.
+    pub fn select_export(&mut self, index: usize) {
+        self.export_selected = index;
+        let source = self.source_text.to_string();
+        self.export_preview =
+            Target::ALL.get(index).map(|target| target.render(&self.export_input(&source))).unwrap_or_default();
+    }
+
+    /// Copia al portapapeles el fragmento del destino elegido en el exportador.
+    pub fn copy_export_selection(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(&target) = Target::ALL.get(self.export_selected) else {
+            return;
+        };
+        match crate::io::copy_to_clipboard(&self.export_preview) {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::Copied(i18n::target_name(self.language, target)))),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::CopyFailed(&e))),
+        }
+    }
+
+    /// Pasa el filtro de la biblioteca a la siguiente etiqueta (y al final, a ninguna).
+    pub fn cycle_library_tag(&mut self) {
+        let tags = self.library.tags();
+        self.library_tag = match &self.library_tag {
+            None => tags.first().cloned(),
+            Some(current) => tags
+                .iter()
+                .position(|tag| tag == current)
+                .and_then(|index| tags.get(index + 1))
+                .cloned(),
+        };
+        self.library_selected = 0;
+    }
+
+    /// Índice (en la biblioteca) de la entrada seleccionada en la vista ordenada.
+    fn selected_library_index(&self) -> Option<usize> {
+        self.library
+            .view(self.library_sort, self.library_tag.as_deref())
+            .get(self.library_selected)
+            .copied()
+    }
+
+    pub fn load_library_selection(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(index) = self.selected_library_index() {
+            self.load_library_entry(index);
+        }
+    }
+
+    /// Entradas de la biblioteca que coinciden difusamente con la consulta de la
+    /// paleta (por nombre, etiquetas y descripción), de mejor a peor.
+    pub fn command_matches(&self) -> Vec<usize> {
+        let mut scored: Vec<(i64, usize)> = self
+            .library
+            .entries()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let haystack = format!("{} {} {}", entry.name, entry.tags.join(" "), entry.description);
+                fuzzy::score(&self.command_query, &haystack).map(|score| (score, index))
+            })
+            .collect();
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        scored.into_iter().map(|(_, index)| index).collect()
+    }
+
+    pub fn load_command_selection(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(index) = self.command_matches().get(self.command_selected).copied() {
+            self.load_library_entry(index);
+        }
+    }
+
+    fn load_library_entry(&mut self, index: usize) {
+        let entry = self.library.entries()[index].clone();
+        self.regex_input = entry.pattern;
+        self.replace_input = entry.replacement;
+        self.flags = entry.flags;
+        if !entry.tests.is_empty() {
+            self.test_cases = entry.tests;
+        }
+        self.library.record_use(index);
+        match self.save_library() {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::LoadedFromLibrary(&entry.name))),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::LibraryNotSaved(&e))),
+        }
+    }
+
+    pub fn refresh_library_preview(&mut self) {
+        self.library_preview = LibraryPreview::build(
+            self.library.entries(),
+            &self.source_text.to_string(),
+            self.processing_unit,
+            self.config.limits.regex_size_limit,
+        );
+    }
+
+    pub fn delete_library_selection(&mut self) {
+        let Some(index) = self.selected_library_index() else {
+            return;
+        };
+        if let Some(entry) = self.library.remove(index) {
+            self.library_selected = self.library_selected.min(self.library.entries().len().saturating_sub(1));
+            self.refresh_library_preview();
+            match self.save_library() {
+                Ok(()) => self.notify(i18n::notice(self.language, Notice::RemovedFromLibrary(&entry.name))),
+                Err(e) => self.notify_error(i18n::notice(self.language, Notice::LibraryNotSaved(&e))),
+            }
+        }
+    }
+
+    pub fn to_session(&self) -> Session {
+        // Los popups no se restauran: la sesión vuelve al modo de edición o a EXPLORAR.
+        let input_mode = match self.input_mode {
+            InputMode::EditingSource => InputMode::EditingSource,
+            InputMode::EditingRegex => InputMode::EditingRegex,
+            InputMode::EditingReplace => InputMode::EditingReplace,
+            InputMode::EditingTests => InputMode::EditingTests,
+            _ => InputMode::Normal,
+        };
+        Session {
+            source_text: self.source_text.to_string(),
+            regex_input: self.regex_input.clone(),
+            replace_input: self.replace_input.clone(),
+            flags: self.flags,
+            processing_unit: self.processing_unit,
+            input_mode,
+            source_scroll: self.source_scroll,
+            output_scroll: self.output_scroll,
+            test_cases: self.test_cases.clone(),
+            golden: self.golden.clone(),
+        }
+    }
+
+    pub fn restore_session(&mut self, session: Session) {
+        self.source_text = Rope::from(session.source_text);
+        self.regex_input = session.regex_input;
+        self.regex_cursor = self.regex_input.len();
+        self.replace_input = session.replace_input;
+        self.flags = session.flags;
+        self.processing_unit = session.processing_unit;
+        self.input_mode = session.input_mode;
+        self.source_scroll = session.source_scroll;
+        self.output_scroll = session.output_scroll;
+        self.test_cases = session.test_cases;
+        self.golden = session.golden;
+    }
+
+    /// Guarda una instantánea de recuperación si pasó el intervalo configurado y el
+    /// estado cambió desde la última.
+    pub fn autosave_tick(&mut self) {
+        let interval = self.config.session.autosave_secs;
+        // Mientras se ofrece la recuperación no hay que pisar el autoguardado previo.
+        if interval == 0
+            || self.asking(Question::RestoreAutosave)
+            || self.last_autosave.elapsed() < Duration::from_secs(interval)
+        {
+            return;
+        }
+        self.last_autosave = Instant::now();
+
+        let Some(path) = Session::autosave_path() else {
+            return;
+        };
+        let session = self.to_session();
+        if self.autosaved.as_ref() == Some(&session) {
+            return;
+        }
+        match session.save(&path) {
+            Ok(()) => self.autosaved = Some(session),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::AutosaveFailed(&e))),
+        }
+    }
+
+    /// Recarga la configuración si el archivo cambió desde la última lectura y
+    /// aplica colores, atajos y límites sin reiniciar. Una configuración inválida
+    /// se informa y se conserva la anterior.
+    pub fn config_reload_tick(&mut self) {
+        if self.last_config_check.elapsed() < Duration::from_secs(1) {
+            return;
+        }
+        self.last_config_check = Instant::now();
+
+        let modified = Config::modified();
+        if modified == self.config_modified {
+            return;
+        }
+        self.config_modified = modified;
+        self.dirty = true;
+
+        let reloaded = Config::load().and_then(|config| {
+            let palette = theme::palette(&config.colors)?;
+            let keymap = Keymap::from_config(&config.keys)?;
+            Ok((config, palette, keymap))
+        });
+        match reloaded {
+            Ok((config, palette, keymap)) => {
+                self.config = config;
+                self.palette = palette;
+                self.keymap = keymap;
+                self.language = i18n::detect(self.config.language);
+                self.screen_reader = self.config.accessibility.screen_reader;
+                self.apply_transform();
+                self.notify(tr(self.language, Text::ConfigReloaded));
+            }
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::ConfigNotReloaded(&e))),
+        }
+    }
+
+    /// Muestra un error en una ventana modal, para mensajes que no caben en la
+    /// línea de estado.
+    pub fn show_error(&mut self, title: &str, message: String) {
+        self.modal = Some(Modal { title: title.to_string(), message, kind: ModalKind::Error });
+        self.input_mode = InputMode::Modal;
+    }
+
+    /// Hace una pregunta en una ventana modal; la respuesta llega a `answer_modal`.
+    pub fn ask(&mut self, question: Question, title: &str, message: String) {
+        self.modal = Some(Modal { title: title.to_string(), message, kind: ModalKind::Question(question) });
+        self.input_mode = InputMode::Modal;
+    }
+
+    /// La ventana modal abierta hace esta pregunta.
+    pub fn asking(&self, question: Question) -> bool {
+        matches!(&self.modal, Some(Modal { kind: ModalKind::Question(asked), .. }) if *asked == question)
+    }
+
+    /// Responde a la ventana modal con una tecla (`'\n'` para Enter) o la cierra
+    /// con `None` (Esc). Una tecla que la pregunta no acepta se ignora.
+    pub fn answer_modal(&mut self, key: Option<char>) {
+        let question = match &self.modal {
+            Some(Modal { kind: ModalKind::Question(question), .. }) => *question,
+            _ => {
+                if key.is_none() || key == Some('\n') {
+                    self.modal = None;
+                    self.input_mode = InputMode::Normal;
+                }
+                return;
+            }
+        };
+        let key = match key {
+            Some('\n') => question.keys()[0],
+            Some(c) if question.keys().contains(&c) => c,
+            Some(_) => return,
+            None => 'n',
+        };
+        self.modal = None;
+        self.input_mode = InputMode::Normal;
+        let yes = matches!(key, 's' | 'y');
+        match question {
+            Question::RestoreAutosave => self.resolve_autosave(yes),
+            Question::KeepPartial => self.resolve_partial(yes),
+            Question::LibraryRegressions => self.resolve_regressions(yes),
+            Question::ImportConflicts => {
+                let policy = match key {
+                    's' => ConflictPolicy::Overwrite,
+                    'c' => ConflictPolicy::KeepExisting,
+                    'r' => ConflictPolicy::Rename,
+                    _ => {
+                        self.pending_import = None;
+                        self.notify(tr(self.language, Text::ImportCancelled));
+                        return;
+                    }
+                };
+                if let Some(incoming) = self.pending_import.take() {
+                    self.finish_import(incoming, policy);
+                }
+            }
+            Question::OverwriteLibrary if yes => self.write_library_export(),
+            Question::OverwriteReport if yes => self.write_report(),
+            Question::OverwriteLibrary | Question::OverwriteReport => {
+                self.notify(tr(self.language, Text::ExportCancelled));
+            }
+        }
+    }
+
+    /// Responde a la oferta de recuperar el autoguardado de una ejecución interrumpida.
+    pub fn resolve_autosave(&mut self, restore: bool) {
+        self.input_mode = InputMode::Normal;
+        let Some(path) = Session::autosave_path() else {
+            return;
+        };
+        if restore {
+            match Session::load(&path) {
+                Ok(session) => {
+                    self.restore_session(session);
+                    self.notify(tr(self.language, Text::SessionRecovered));
+                }
+                Err(e) => self.notify_error(i18n::notice(self.language, Notice::RecoveryFailed(&e))),
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,46 @@
+//! Comparación entre los tramos del texto que el patrón debería encontrar,
+//! marcados a mano, y los que encuentra: correctos, faltantes y de más. Un
+//! tramo cuenta como correcto solo si coincide exactamente, bordes incluidos.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpanScore {
+    pub correct: Vec<Range<usize>>,
+    /// Marcados que el patrón no encuentra.
+    pub missed: Vec<Range<usize>>,
+    /// Encontrados que no están marcados.
+    pub extra: Vec<Range<usize>>,
+}
+
+impl SpanScore {
+    /// Proporción de lo encontrado que estaba marcado; `None` si no se encontró nada.
+    pub fn precision(&self) -> Option<f64> {
+        ratio(self.correct.len(), self.correct.len() + self.extra.len())
+    }
+
+    /// Proporción de lo marcado que se encontró; `None` si no hay nada marcado.
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.correct.len(), self.correct.len() + self.missed.len())
+    }
+}
+
+fn ratio(part: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| part as f64 / total as f64)
+}
+
+/// Compara los tramos marcados en `expected` con los encontrados en `actual`.
+pub fn score(expected: &[Range<usize>], actual: &[Range<usize>]) -> SpanScore {
+    let expected: BTreeSet<(usize, usize)> = expected.iter().map(|range| (range.start, range.end)).collect();
+    let actual: BTreeSet<(usize, usize)> = actual.iter().map(|range| (range.start, range.end)).collect();
+    SpanScore {
+        correct: ranges(expected.intersection(&actual)),
+        missed: ranges(expected.difference(&actual)),
+        extra: ranges(actual.difference(&expected)),
+    }
+}
+
+fn ranges<'a>(spans: impl Iterator<Item = &'a (usize, usize)>) -> Vec<Range<usize>> {
+    spans.map(|&(start, end)| start..end).collect()
+}
@@ -0,0 +1,89 @@
+//! Qué alternativa de primer nivel produjo cada coincidencia, para depurar
+//! patrones armados como lista de reglas (`ERROR|WARN|FATAL.*`). Cada
+//! alternativa se envuelve en un grupo con nombre propio y se mira cuál
+//! participó en la coincidencia.
+
+use regex::Regex;
+use regex_syntax::ast::{self, Ast};
+
+use crate::engine::MatchLocation;
+use crate::{EngineOptions, ProcessingUnit};
+
+/// Prefijo de los grupos que marcan las alternativas, poco probable en un patrón real.
+const BRANCH_GROUP: &str = "__rama";
+
+/// Coincidencias mostradas con su alternativa.
+const SAMPLES: usize = 5;
+
+/// Resumen por alternativa de las coincidencias de un patrón.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchSummary {
+    /// Texto de cada alternativa, en orden.
+    pub branches: Vec<String>,
+    /// Coincidencias producidas por cada alternativa (entre las primeras `limit`).
+    pub counts: Vec<usize>,
+    /// Las primeras coincidencias con el índice (desde 0) de su alternativa.
+    pub samples: Vec<(MatchLocation, usize)>,
+}
+
+/// Las alternativas de primer nivel de `pattern` y el patrón con cada una en
+/// su grupo marcado, o `None` si no hay alternancia de primer nivel.
+///
+/// Un `(?i)` suelto dentro de una alternativa deja de afectar a las
+/// siguientes, ya que ahora termina con su grupo.
+pub fn tag_branches(pattern: &str, options: &EngineOptions) -> Option<(Vec<String>, String)> {
+    let ignore_whitespace = options.flags.ignore_whitespace;
+    let ast = ast::parse::ParserBuilder::new().ignore_whitespace(ignore_whitespace).build().parse(pattern).ok()?;
+    let Ast::Alternation(alternation) = &ast else {
+        return None;
+    };
+    let branches: Vec<String> = alternation
+        .asts
+        .iter()
+        .map(|branch| pattern[branch.span().start.offset..branch.span().end.offset].to_string())
+        .collect();
+    // Con el flag x un comentario al final de la alternativa se comería el `)`.
+    let close = if ignore_whitespace { "\n)" } else { ")" };
+    let tagged = branches
+        .iter()
+        .enumerate()
+        .map(|(index, branch)| format!("(?P<{}{}>{}{}", BRANCH_GROUP, index, branch, close))
+        .collect::<Vec<_>>()
+        .join("|");
+    Some((branches, tagged))
+}
+
+/// Índice de la alternativa de cada una de las primeras `limit` coincidencias
+/// de `re`, un patrón devuelto por `tag_branches` ya compilado.
+pub fn branch_of_matches(re: &Regex, source: &str, unit: ProcessingUnit, limit: usize) -> Vec<usize> {
+    let groups: Vec<usize> = re
+        .capture_names()
+        .enumerate()
+        .filter(|(_, name)| name.is_some_and(|name| name.starts_with(BRANCH_GROUP)))
+        .map(|(index, _)| index)
+        .collect();
+    let segments: Vec<&str> = match unit {
+        ProcessingUnit::WholeText => vec![source],
+        ProcessingUnit::PerLine => crate::split_lines(source).map(|(line, _)| line).collect(),
+    };
+    segments
+        .into_iter()
+        .flat_map(|segment| re.captures_iter(segment))
+        .take(limit)
+        .map(|caps| groups.iter().position(|&group| caps.get(group).is_some()).unwrap_or(0))
+        .collect()
+}
+
+/// Resumen por alternativa de las primeras `limit` coincidencias de `pattern`
+/// sobre `source`; `None` si no hay alternancia de primer nivel o no compila.
+pub fn summarize(pattern: &str, source: &str, options: &EngineOptions, limit: usize) -> Option<BranchSummary> {
+    let (branches, tagged) = tag_branches(pattern, options)?;
+    let re = crate::compile(&tagged, options).ok()?;
+    let tags = branch_of_matches(&re, source, options.unit, limit);
+    let mut counts = vec![0; branches.len()];
+    for &branch in &tags {
+        counts[branch] += 1;
+    }
+    let samples = crate::locate_matches(&re, source, options.unit, SAMPLES).into_iter().zip(tags).collect();
+    Some(BranchSummary { branches, counts, samples })
+}
@@ -0,0 +1,183 @@
+//! Casos de prueba de un patrón, escritos uno por línea y evaluados en vivo:
+//!
+//! ```text
+//! + 2024-01-15            debe coincidir
+//! + 2024-01-15 -> 2024 | 01 | 15
+//!                         debe coincidir con esas capturas (grupos 1, 2, 3)
+//! - 15/01/2024            no debe coincidir
+//! # comentario
+//! ```
+//!
+//! Un corpus de casos se puede importar desde un archivo con ese mismo formato
+//! (las líneas sin `+ ` ni `- ` son textos que deben coincidir) o en TOML:
+//!
+//! ```toml
+//! [[case]]
+//! input = "2024-01-15"
+//! captures = ["2024", "01", "15"]
+//!
+//! [[case]]
+//! input = "15/01/2024"
+//! matches = false
+//! ```
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Separa el texto de un caso de sus capturas esperadas.
+const CAPTURES_ARROW: &str = " -> ";
+/// Separa las capturas esperadas entre sí, como en la salida del modo filtro.
+const CAPTURES_SEPARATOR: &str = " | ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expectation {
+    Match,
+    NoMatch,
+}
+
+/// Caso de prueba: un texto, si el patrón debe encontrar algo en él y, si se
+/// indican, las capturas esperadas de la primera coincidencia.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub input: String,
+    pub expectation: Expectation,
+    pub captures: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    /// El patrón no cumple lo esperado, con el motivo.
+    Fail(String),
+    /// La línea no es un caso válido.
+    Invalid(String),
+}
+
+/// Resultado de la línea `line` (desde 0) del texto de casos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseResult {
+    pub line: usize,
+    pub verdict: Verdict,
+}
+
+/// Interpreta una línea. Las vacías y los comentarios (`#`) dan `None`.
+pub fn parse_line(line: &str) -> Option<Result<TestCase, String>> {
+    let line = line.trim_end_matches('\r');
+    if line.trim().is_empty() || line.trim_start().starts_with('#') {
+        return None;
+    }
+    let (expectation, rest) = if let Some(rest) = line.strip_prefix("+ ") {
+        (Expectation::Match, rest)
+    } else if let Some(rest) = line.strip_prefix("- ") {
+        (Expectation::NoMatch, rest)
+    } else {
+        return Some(Err("empezar con '+ ' (debe coincidir) o '- ' (no debe coincidir)".to_string()));
+    };
+    let (input, captures) = match (expectation, rest.split_once(CAPTURES_ARROW)) {
+        (Expectation::Match, Some((input, captures))) => {
+            (input, captures.split(CAPTURES_SEPARATOR).map(str::to_string).collect())
+        }
+        _ => (rest, Vec::new()),
+    };
+    Some(Ok(TestCase { input: input.to_string(), expectation, captures }))
+}
+
+/// Evalúa un caso contra `re`.
+pub fn check(re: &Regex, case: &TestCase) -> Verdict {
+    let found = re.captures(&case.input);
+    match (case.expectation, found) {
+        (Expectation::NoMatch, None) => Verdict::Pass,
+        (Expectation::NoMatch, Some(caps)) => {
+            Verdict::Fail(format!("coincide con '{}'", caps.get(0).map_or("", |m| m.as_str())))
+        }
+        (Expectation::Match, None) => Verdict::Fail("no coincide".to_string()),
+        (Expectation::Match, Some(_)) if case.captures.is_empty() => Verdict::Pass,
+        (Expectation::Match, Some(caps)) => {
+            let actual: Vec<&str> = caps.iter().skip(1).map(|group| group.map_or("", |m| m.as_str())).collect();
+            if actual == case.captures {
+                Verdict::Pass
+            } else {
+                Verdict::Fail(format!("captura {}", actual.join(CAPTURES_SEPARATOR)))
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Corpus {
+    #[serde(default, rename = "case")]
+    cases: Vec<CorpusCase>,
+}
+
+#[derive(Deserialize)]
+struct CorpusCase {
+    input: String,
+    #[serde(default = "must_match")]
+    matches: bool,
+    #[serde(default)]
+    captures: Vec<String>,
+}
+
+fn must_match() -> bool {
+    true
+}
+
+/// Lee un corpus de casos y lo devuelve en el formato del panel de casos,
+/// una línea por caso. Los `.toml` se leen como TOML; el resto, por líneas.
+pub fn load(path: &Path) -> Result<String> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("No se pudo leer {}", path.display()))?;
+    if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("toml")) {
+        let corpus: Corpus =
+            toml::from_str(&text).with_context(|| format!("Corpus de casos inválido en {}", path.display()))?;
+        return corpus.cases.iter().map(format_case).collect::<Result<Vec<_>>>().map(|lines| lines.join("\n"));
+    }
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| match parse_line(line) {
+            Some(Err(_)) => format!("+ {}", line.trim_end_matches('\r')),
+            _ => line.trim_end_matches('\r').to_string(),
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Un caso del corpus TOML como línea del panel.
+fn format_case(case: &CorpusCase) -> Result<String> {
+    if case.input.contains('\n') {
+        bail!("El caso {:?} tiene saltos de línea: cada caso ocupa una línea", case.input);
+    }
+    Ok(match (case.matches, case.captures.is_empty()) {
+        (false, _) => format!("- {}", case.input),
+        (true, true) => format!("+ {}", case.input),
+        (true, false) => format!("+ {}{}{}", case.input, CAPTURES_ARROW, case.captures.join(CAPTURES_SEPARATOR)),
+    })
+}
+
+/// Resultado de cada línea de `text` que no sea vacía ni comentario.
+pub fn evaluate(re: &Regex, text: &str) -> Vec<CaseResult> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, content)| {
+            let verdict = match parse_line(content)? {
+                Ok(case) => check(re, &case),
+                Err(reason) => Verdict::Invalid(reason),
+            };
+            Some(CaseResult { line, verdict })
+        })
+        .collect()
+}
+
+/// Casos de `text` que `before` pasa y `after` ya no, con la línea (desde 0) y
+/// el motivo del fallo. Sirve para avisar antes de reemplazar un patrón.
+pub fn regressions(before: &Regex, after: &Regex, text: &str) -> Vec<(usize, String)> {
+    evaluate(before, text)
+        .into_iter()
+        .zip(evaluate(after, text))
+        .filter_map(|(old, new)| match (old.verdict, new.verdict) {
+            (Verdict::Pass, Verdict::Fail(reason)) => Some((new.line, reason)),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+//! Catálogo incorporado de patrones comunes, listos para usar tal cual o como
+//! bloques dentro de un patrón más grande.
+
+pub struct CatalogEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub pattern: &'static str,
+}
+
+pub const CATALOG: &[CatalogEntry] = &[
+    CatalogEntry {
+        name: "Email",
+        description: "Dirección de correo (forma práctica, no RFC 5322 completo)",
+        pattern: r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+    },
+    CatalogEntry {
+        name: "IPv4",
+        description: "Dirección IPv4 con octetos 0-255",
+        pattern: r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b",
+    },
+    CatalogEntry {
+        name: "IPv6",
+        description: "Dirección IPv6 completa o comprimida con '::' (sin IPv4 embebida)",
+        pattern: concat!(
+            r"(?:[0-9A-Fa-f]{1,4}:){7}[0-9A-Fa-f]{1,4}",
+            r"|[0-9A-Fa-f]{1,4}:(?::[0-9A-Fa-f]{1,4}){1,6}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,2}(?::[0-9A-Fa-f]{1,4}){1,5}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,3}(?::[0-9A-Fa-f]{1,4}){1,4}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,4}(?::[0-9A-Fa-f]{1,4}){1,3}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,5}(?::[0-9A-Fa-f]{1,4}){1,2}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,6}:[0-9A-Fa-f]{1,4}",
+            r"|:(?::[0-9A-Fa-f]{1,4}){1,7}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,7}:",
+            r"|::",
+        ),
+    },
+    CatalogEntry {
+        name: "URL",
+        description: "URL http/https hasta el siguiente espacio",
+        pattern: r"https?://[^\s/$.?#][^\s]*",
+    },
+    CatalogEntry {
+        name: "UUID",
+        description: "UUID en formato canónico 8-4-4-4-12",
+        pattern: r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+    },
+    CatalogEntry {
+        name: "Fecha ISO",
+        description: "Fecha ISO 8601 AAAA-MM-DD",
+        pattern: r"\b\d{4}-(?:0[1-9]|1[0-2])-(?:0[1-9]|[12]\d|3[01])\b",
+    },
+    CatalogEntry {
+        name: "Fecha y hora ISO",
+        description: "Marca de tiempo ISO 8601 con fracción y zona opcionales",
+        pattern: r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})?",
+    },
+    CatalogEntry {
+        name: "Hora",
+        description: "Hora HH:MM o HH:MM:SS (24 h)",
+        pattern: r"\b(?:[01]\d|2[0-3]):[0-5]\d(?::[0-5]\d)?\b",
+    },
+    CatalogEntry {
+        name: "SemVer",
+        description: "Versión semántica 2.0.0 con pre-release y build opcionales",
+        pattern: concat!(
+            r"\b(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)",
+            r"(?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?",
+            r"(?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?\b",
+        ),
+    },
+    CatalogEntry {
+        name: "MAC",
+        description: "Dirección MAC con ':' o '-' como separador",
+        pattern: r"\b[0-9A-Fa-f]{2}(?:[:-][0-9A-Fa-f]{2}){5}\b",
+    },
+    CatalogEntry {
+        name: "Color hex",
+        description: "Color CSS #rgb o #rrggbb",
+        pattern: r"#(?:[0-9a-fA-F]{6}|[0-9a-fA-F]{3})\b",
+    },
+    CatalogEntry {
+        name: "Número",
+        description: "Entero o decimal con signo opcional",
+        pattern: r"[+-]?\d+(?:[.,]\d+)?",
+    },
+];
@@ -0,0 +1,199 @@
+//! Expansión de una clase de caracteres: qué caracteres incluye y cuáles deja
+//! afuera, con los operadores de conjuntos (`&&`, `--`, `~~`) y las negaciones
+//! ya resueltos. Como las clases pueden tener miles de caracteres, se muestra
+//! una muestra repartida entre sus rangos.
+
+use regex_syntax::ast::{self, Ast, ClassSet, ClassSetItem};
+use regex_syntax::hir::{Class, ClassUnicode, ClassUnicodeRange, HirKind};
+
+use crate::RegexFlags;
+
+/// Caracteres de cada muestra.
+const SAMPLE: usize = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassExpansion {
+    /// La clase tal como está escrita en el patrón.
+    pub source: String,
+    /// Rangos de caracteres que forman la clase, en orden.
+    pub ranges: Vec<(char, char)>,
+    /// Muestra de caracteres incluidos, repartida entre los rangos.
+    pub included: Vec<char>,
+    /// Muestra de caracteres excluidos: primero los que nombra la clase pero
+    /// quitan sus operadores o negaciones, y después los vecinos de sus bordes.
+    pub excluded: Vec<char>,
+}
+
+impl ClassExpansion {
+    /// Cantidad de caracteres que incluye la clase.
+    pub fn count(&self) -> u32 {
+        self.ranges.iter().map(|&(start, end)| range_len(start, end)).sum()
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        contains(&self.ranges, c)
+    }
+}
+
+/// Expansión de la clase de `pattern` que contiene el byte `offset`, o `None`
+/// si ahí no hay una clase. Se toma el nodo más interno en `offset`
+/// (`explain::innermost`); una clase anidada entre corchetes (`[^_]` en
+/// `[\w&&[^_]]`) no es un nodo aparte, así que se expande la que la contiene.
+///
+/// Los flags dentro del patrón (`(?i)`) no se aplican: solo cuentan los de `flags`.
+pub fn class_at(pattern: &str, flags: &RegexFlags, offset: usize) -> Option<ClassExpansion> {
+    let ast = ast::parse::ParserBuilder::new().ignore_whitespace(flags.ignore_whitespace).build().parse(pattern).ok()?;
+    let node = crate::explain::innermost(&ast, offset)?;
+    let (span, set) = match node {
+        Ast::ClassBracketed(class) => (&class.span, Some(&class.kind)),
+        Ast::ClassUnicode(class) => (&class.span, None),
+        Ast::ClassPerl(class) => (&class.span, None),
+        _ => return None,
+    };
+    let source = &pattern[span.start.offset..span.end.offset];
+    let class = resolve(source, flags)?;
+    let ranges: Vec<(char, char)> = class.ranges().iter().map(|range| (range.start(), range.end())).collect();
+
+    // Lo que nombran las piezas de la clase y el resultado no incluye.
+    let mut removed = ClassUnicode::empty();
+    if let Some(set) = set {
+        let mut items = Vec::new();
+        leaves(set, &mut items);
+        for item in items {
+            if let Some(leaf) = leaf_class(pattern, item, flags) {
+                removed.union(&leaf);
+            }
+        }
+        removed.difference(&class);
+    }
+    let removed: Vec<(char, char)> = removed.ranges().iter().map(|range| (range.start(), range.end())).collect();
+
+    let neighbours = ranges
+        .iter()
+        .flat_map(|&(start, end)| [char::from_u32((start as u32).wrapping_sub(1)), char::from_u32(end as u32 + 1)])
+        .flatten()
+        .filter(|&c| !contains(&ranges, c));
+    let ascii = (' '..='~').filter(|&c| !contains(&ranges, c));
+    let mut excluded: Vec<char> = Vec::new();
+    for c in sample(&removed, SAMPLE).into_iter().chain(neighbours).chain(ascii) {
+        if excluded.len() == SAMPLE {
+            break;
+        }
+        if visible(c) && !excluded.contains(&c) {
+            excluded.push(c);
+        }
+    }
+    Some(ClassExpansion { source: source.to_string(), included: sample(&ranges, SAMPLE), ranges, excluded })
+}
+
+/// La clase escrita en `source` como conjunto de caracteres.
+fn resolve(source: &str, flags: &RegexFlags) -> Option<ClassUnicode> {
+    let hir = regex_syntax::ParserBuilder::new()
+        .case_insensitive(flags.case_insensitive)
+        .ignore_whitespace(flags.ignore_whitespace)
+        .build()
+        .parse(source)
+        .ok()?;
+    match hir.kind() {
+        HirKind::Class(Class::Unicode(class)) => Some(class.clone()),
+        HirKind::Class(Class::Bytes(class)) => Some(ClassUnicode::new(
+            class.ranges().iter().map(|range| ClassUnicodeRange::new(range.start() as char, range.end() as char)),
+        )),
+        // Una clase de un solo carácter se simplifica a un literal.
+        HirKind::Literal(literal) => {
+            let text = std::str::from_utf8(&literal.0).ok()?;
+            let mut chars = text.chars();
+            let c = chars.next()?;
+            chars.next().is_none().then(|| ClassUnicode::new([ClassUnicodeRange::new(c, c)]))
+        }
+        _ => None,
+    }
+}
+
+/// Las piezas sueltas de una clase entre corchetes, entrando en las anidadas
+/// y en los dos lados de cada operador.
+fn leaves<'a>(set: &'a ClassSet, items: &mut Vec<&'a ClassSetItem>) {
+    match set {
+        ClassSet::BinaryOp(op) => {
+            leaves(&op.lhs, items);
+            leaves(&op.rhs, items);
+        }
+        ClassSet::Item(item) => leaf_items(item, items),
+    }
+}
+
+fn leaf_items<'a>(item: &'a ClassSetItem, items: &mut Vec<&'a ClassSetItem>) {
+    match item {
+        ClassSetItem::Bracketed(class) => leaves(&class.kind, items),
+        ClassSetItem::Union(union) => union.items.iter().for_each(|item| leaf_items(item, items)),
+        ClassSetItem::Empty(_) => {}
+        _ => items.push(item),
+    }
+}
+
+/// Los caracteres que nombra una pieza por sí sola.
+fn leaf_class(pattern: &str, item: &ClassSetItem, flags: &RegexFlags) -> Option<ClassUnicode> {
+    let mut class = match item {
+        ClassSetItem::Literal(literal) => ClassUnicode::new([ClassUnicodeRange::new(literal.c, literal.c)]),
+        ClassSetItem::Range(range) => ClassUnicode::new([ClassUnicodeRange::new(range.start.c, range.end.c)]),
+        _ => {
+            let span = item.span();
+            return resolve(&format!("[{}]", &pattern[span.start.offset..span.end.offset]), flags);
+        }
+    };
+    if flags.case_insensitive {
+        class.case_fold_simple();
+    }
+    Some(class)
+}
+
+/// Hasta `limit` caracteres visibles de `ranges`, tomando por turnos el
+/// siguiente de cada rango para que todos estén representados.
+fn sample(ranges: &[(char, char)], limit: usize) -> Vec<char> {
+    let mut chosen = Vec::new();
+    let mut step = 0;
+    while chosen.len() < limit {
+        let mut progressed = false;
+        for &(start, end) in ranges {
+            let Some(c) = char::from_u32(start as u32 + step).filter(|&c| c <= end) else {
+                continue;
+            };
+            progressed = true;
+            if visible(c) {
+                chosen.push(c);
+                if chosen.len() == limit {
+                    break;
+                }
+            }
+        }
+        // Un tope de vueltas evita recorrer rangos enormes de caracteres invisibles.
+        if !progressed || step == 256 {
+            break;
+        }
+        step += 1;
+    }
+    chosen
+}
+
+fn visible(c: char) -> bool {
+    !c.is_control()
+}
+
+fn contains(ranges: &[(char, char)], c: char) -> bool {
+    ranges.binary_search_by(|&(start, end)| {
+        if end < c {
+            std::cmp::Ordering::Less
+        } else if start > c {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    })
+    .is_ok()
+}
+
+/// Caracteres de un rango, sin contar los sustitutos UTF-16 que no son `char`.
+fn range_len(start: char, end: char) -> u32 {
+    let len = end as u32 - start as u32 + 1;
+    if start <= '\u{D7FF}' && end >= '\u{E000}' { len - 0x800 } else { len }
+}
@@ -0,0 +1,336 @@
+//! Configuración del usuario, leída al inicio desde `config.toml` en el
+//! directorio de configuración de la plataforma (p. ej. `~/.config/regex-wysiwyg/`
+//! en Linux) y recargada en caliente cuando el archivo cambia. Cualquier clave
+//! ausente conserva su valor por defecto.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::RegexFlags;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub colors: ColorsConfig,
+    pub accessibility: AccessibilityConfig,
+    pub flags: RegexFlags,
+    pub layout: LayoutConfig,
+    pub ai: AiConfig,
+    pub limits: LimitsConfig,
+    pub session: SessionConfig,
+    pub startup: StartupConfig,
+    pub sync: SyncConfig,
+    /// Redefiniciones de atajos: nombre de acción → tecla (ver `keymap`).
+    pub keys: BTreeMap<String, String>,
+    /// Idioma de la interfaz; sin definir, se toma de `LANG`.
+    pub language: Option<Language>,
+}
+
+/// Idiomas de la interfaz.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Es,
+    En,
+}
+
+/// Tema de colores: un preset y, por elemento, colores que lo redefinen, por
+/// nombre (`"yellow"`, `"lightblue"`) o `"#rrggbb"`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ColorsConfig {
+    pub theme: Theme,
+    pub title: Option<String>,
+    pub source: Option<String>,
+    pub regex: Option<String>,
+    pub replace: Option<String>,
+    pub output: Option<String>,
+    /// Coincidencias en modo filtro.
+    pub matches: Option<String>,
+    /// Separadores ` | ` entre coincidencias.
+    pub separator: Option<String>,
+    pub error: Option<String>,
+    pub border: Option<String>,
+    pub help: Option<String>,
+}
+
+/// Presets de colores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    #[default]
+    Dark,
+    /// Para terminales de fondo claro, donde amarillo y verde no se leen.
+    Light,
+    Solarized,
+    /// Paleta de Okabe–Ito sin pares rojo/verde: coincidencias en azul y
+    /// errores en naranja, distinguibles con deuteranopía.
+    Deuteranopia,
+    /// Como `Deuteranopia`, pero sin rojos (que con protanopía se ven oscuros):
+    /// los errores van en amarillo.
+    Protanopia,
+    /// Blanco y negro; las coincidencias se subrayan y van en negrita, así que
+    /// no dependen del color.
+    HighContrast,
+}
+
+/// Comando externo que genera sugerencias de regex. El prompt se agrega como
+/// último argumento.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AiConfig {
+    pub provider: String,
+    pub command: String,
+    pub args: Vec<String>,
+    /// Variable de entorno por la que el comando recibe la API key guardada en
+    /// el llavero del sistema (p. ej. `"GEMINI_API_KEY"`). Sin ella no se usa key.
+    pub api_key_env: Option<String>,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        if cfg!(windows) {
+            AiConfig {
+                provider: "Gemini".to_string(),
+                command: "cmd".to_string(),
+                args: vec!["/C".to_string(), "gemini".to_string(), "-p".to_string()],
+                api_key_env: None,
+            }
+        } else {
+            AiConfig {
+                provider: "Gemini".to_string(),
+                command: "gemini".to_string(),
+                args: vec!["-p".to_string()],
+                api_key_env: None,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LimitsConfig {
+    /// Tamaño máximo (en bytes) del programa compilado para una regex.
+    pub regex_size_limit: usize,
+    /// Tamaño máximo (en bytes) de la salida que se muestra; el resultado completo
+    /// solo se genera al registrarlo.
+    pub output_size_limit: usize,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        LimitsConfig { regex_size_limit: 10 * (1 << 20), output_size_limit: 8 * (1 << 20) }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Restaurar al inicio la última sesión guardada automáticamente al salir.
+    pub restore_last: bool,
+    /// Segundos entre autoguardados de recuperación ante fallos (0 = desactivado).
+    pub autosave_secs: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig { restore_last: false, autosave_secs: 30 }
+    }
+}
+
+/// Ajustes para usar la interfaz con un lector de pantalla.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityConfig {
+    /// Sin bordes decorativos ni señales que dependan solo del color: cada
+    /// coincidencia se describe en una línea de texto con su posición.
+    pub screen_reader: bool,
+}
+
+/// Distribución de los paneles.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    pub preset: LayoutPreset,
+    /// Ocultar el panel de reemplazo mientras esté vacío y no se esté editando.
+    pub collapse_replace: bool,
+    pub split: Split,
+    /// Margen con números de línea y marcadores de coincidencias en el texto fuente.
+    pub line_numbers: bool,
+    /// Paneles visibles, de arriba abajo. Los que faltan no se muestran (salvo
+    /// mientras se editan); con `split = "horizontal"` el texto fuente y la
+    /// salida van juntos en la fila del primero de los dos.
+    pub panes: Vec<PaneKind>,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            preset: LayoutPreset::Balanced,
+            collapse_replace: false,
+            split: Split::Vertical,
+            line_numbers: true,
+            panes: PaneKind::ALL.to_vec(),
+        }
+    }
+}
+
+/// Paneles de la distribución normal, para ordenarlos u ocultarlos en `layout.panes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaneKind {
+    Title,
+    Source,
+    Regex,
+    Replace,
+    Output,
+    /// Casos de prueba; solo se ve si hay alguno o se están editando.
+    Tests,
+    Status,
+}
+
+impl PaneKind {
+    pub const ALL: [PaneKind; 7] = [
+        PaneKind::Title,
+        PaneKind::Source,
+        PaneKind::Regex,
+        PaneKind::Replace,
+        PaneKind::Output,
+        PaneKind::Tests,
+        PaneKind::Status,
+    ];
+}
+
+/// Cómo se reparten el texto fuente y la salida.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Split {
+    /// Uno encima del otro, con la regex y el reemplazo entre ambos.
+    #[default]
+    Vertical,
+    /// Lado a lado, con la regex y el reemplazo abajo: aprovecha las
+    /// terminales anchas.
+    Horizontal,
+}
+
+impl Split {
+    pub fn toggle(self) -> Split {
+        match self {
+            Split::Vertical => Split::Horizontal,
+            Split::Horizontal => Split::Vertical,
+        }
+    }
+}
+
+/// Reparto del alto disponible entre el texto fuente y la salida.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutPreset {
+    #[default]
+    Balanced,
+    /// Más espacio para el texto fuente.
+    Editing,
+    /// Más espacio para la salida.
+    Results,
+}
+
+impl LayoutPreset {
+    /// Porcentaje del alto compartido que ocupa la salida.
+    pub fn output_share(self) -> u16 {
+        match self {
+            LayoutPreset::Balanced => 50,
+            LayoutPreset::Editing => 30,
+            LayoutPreset::Results => 75,
+        }
+    }
+
+    pub fn next(self) -> LayoutPreset {
+        match self {
+            LayoutPreset::Balanced => LayoutPreset::Editing,
+            LayoutPreset::Editing => LayoutPreset::Results,
+            LayoutPreset::Results => LayoutPreset::Balanced,
+        }
+    }
+}
+
+/// Fuente compartida de patrones que se sincroniza al iniciar: una URL HTTPS a
+/// un JSON, o la ruta de un repositorio git que contiene `file`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub source: String,
+    pub file: String,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        SyncConfig { source: String::new(), file: "library.json".to_string() }
+    }
+}
+
+impl SyncConfig {
+    pub fn enabled(&self) -> bool {
+        !self.source.trim().is_empty()
+    }
+}
+
+/// Contenido inicial del panel fuente. `source_file` tiene prioridad sobre
+/// `source_text`; sin ninguno se usa la frase de demostración.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    pub source_text: Option<String>,
+    pub source_file: Option<PathBuf>,
+}
+
+impl StartupConfig {
+    /// Texto inicial configurado, si lo hay. Admite `~/` al comienzo de la ruta.
+    pub fn initial_source(&self) -> Result<Option<String>> {
+        if let Some(path) = &self.source_file {
+            let path = expand_home(path);
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("No se pudo leer startup.source_file ({})", path.display()))?;
+            return Ok(Some(text));
+        }
+        Ok(self.source_text.clone())
+    }
+}
+
+fn expand_home(path: &std::path::Path) -> PathBuf {
+    match (path.strip_prefix("~"), directories::BaseDirs::new()) {
+        (Ok(rest), Some(dirs)) => dirs.home_dir().join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        crate::paths::config_file("config.toml")
+    }
+
+    /// Carga la configuración; si el archivo no existe se usan los valores por defecto.
+    pub fn load() -> Result<Config> {
+        let Some(path) = Config::path() else {
+            return Ok(Config::default());
+        };
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("No se pudo leer {}", path.display()))?;
+        let config: Config = toml::from_str(&text)
+            .with_context(|| format!("Configuración inválida en {}", path.display()))?;
+        Ok(config)
+    }
+
+    /// Fecha de modificación del archivo de configuración, para detectar cambios.
+    pub fn modified() -> Option<SystemTime> {
+        let path = Config::path()?;
+        std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+    }
+}
@@ -0,0 +1,137 @@
+//! Qué partes de un patrón participaron en alguna coincidencia sobre un texto,
+//! para encontrar alternativas muertas en patrones grandes. Solo se miden las
+//! partes que pueden no participar en una coincidencia: cada alternativa y lo
+//! que se repite con mínimo cero (`?`, `*`, `{0,n}`). Cada una se envuelve en
+//! un grupo con nombre propio y se mira si alguna coincidencia lo usó.
+//!
+//! Dentro de una repetición el motor informa solo la última vuelta, así que
+//! una parte usada en una vuelta anterior puede figurar como no usada.
+
+use regex_syntax::ast::{self, Ast, RepetitionKind, RepetitionRange};
+use std::ops::Range;
+
+use crate::{EngineOptions, ProcessingUnit};
+
+/// Prefijo de los grupos que marcan las partes, poco probable en un patrón real.
+const COVERAGE_GROUP: &str = "__cob";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage {
+    /// Partes medidas, como rangos de bytes del patrón, en orden de aparición.
+    pub parts: Vec<Range<usize>>,
+    /// Si cada parte participó en alguna de las coincidencias examinadas.
+    pub used: Vec<bool>,
+}
+
+impl Coverage {
+    /// Partes que nunca participaron, sin las que están dentro de otra que
+    /// tampoco lo hizo.
+    pub fn unused(&self) -> Vec<Range<usize>> {
+        let mut unused: Vec<Range<usize>> = Vec::new();
+        for (part, _) in self.parts.iter().zip(&self.used).filter(|(_, used)| !**used) {
+            if !unused.iter().any(|outer| outer.start <= part.start && part.end <= outer.end) {
+                unused.push(part.clone());
+            }
+        }
+        unused
+    }
+}
+
+/// Las partes medibles de `pattern` y el patrón con cada una en su grupo
+/// marcado, o `None` si no compila o no tiene partes medibles.
+///
+/// Como en las alternativas de `branches`, un `(?i)` suelto dentro de una parte
+/// deja de afectar a lo que sigue, ya que ahora termina con su grupo.
+pub fn tag_parts(pattern: &str, options: &EngineOptions) -> Option<(Vec<Range<usize>>, String)> {
+    let ignore_whitespace = options.flags.ignore_whitespace;
+    let ast = ast::parse::ParserBuilder::new().ignore_whitespace(ignore_whitespace).build().parse(pattern).ok()?;
+    let mut parts = Vec::new();
+    collect(&ast, &mut parts);
+    if parts.is_empty() {
+        return None;
+    }
+
+    // Inserciones (posición, orden, desempate, texto): en una misma posición
+    // primero se cierran las partes, de la más interna a la más externa, y
+    // después se abren, de la más externa a la más interna.
+    // Con el flag x un comentario al final de la parte se comería el `)`.
+    let close = if ignore_whitespace { "\n)" } else { ")" };
+    let mut inserts: Vec<(usize, u8, isize, String)> = Vec::new();
+    for (index, part) in parts.iter().enumerate() {
+        let open = format!("(?P<{}{}>", COVERAGE_GROUP, index);
+        if part.is_empty() {
+            inserts.push((part.start, 1, -(part.start as isize), format!("{}{}", open, close)));
+        } else {
+            inserts.push((part.start, 1, -(part.end as isize), open));
+            inserts.push((part.end, 0, -(part.start as isize), close.to_string()));
+        }
+    }
+    inserts.sort_by_key(|&(offset, order, tiebreak, _)| (offset, order, tiebreak));
+
+    let mut tagged = String::with_capacity(pattern.len() + inserts.len() * 12);
+    let mut copied = 0;
+    for (offset, _, _, text) in inserts {
+        tagged.push_str(&pattern[copied..offset]);
+        tagged.push_str(&text);
+        copied = offset;
+    }
+    tagged.push_str(&pattern[copied..]);
+    Some((parts, tagged))
+}
+
+fn collect(ast: &Ast, parts: &mut Vec<Range<usize>>) {
+    let span = |ast: &Ast| ast.span().start.offset..ast.span().end.offset;
+    match ast {
+        Ast::Alternation(alternation) => {
+            for branch in &alternation.asts {
+                parts.push(span(branch));
+                collect(branch, parts);
+            }
+        }
+        Ast::Concat(concat) => concat.asts.iter().for_each(|item| collect(item, parts)),
+        Ast::Group(group) => collect(&group.ast, parts),
+        Ast::Repetition(repetition) => {
+            let optional = matches!(
+                repetition.op.kind,
+                RepetitionKind::ZeroOrOne
+                    | RepetitionKind::ZeroOrMore
+                    | RepetitionKind::Range(
+                        RepetitionRange::Exactly(0) | RepetitionRange::AtLeast(0) | RepetitionRange::Bounded(0, _)
+                    )
+            );
+            if optional {
+                parts.push(span(&repetition.ast));
+            }
+            collect(&repetition.ast, parts);
+        }
+        _ => {}
+    }
+}
+
+/// Cobertura de `pattern` en las primeras `limit` coincidencias sobre
+/// `source`; `None` si no compila o no tiene partes medibles.
+pub fn coverage(pattern: &str, source: &str, options: &EngineOptions, limit: usize) -> Option<Coverage> {
+    let (parts, tagged) = tag_parts(pattern, options)?;
+    let re = crate::compile(&tagged, options).ok()?;
+    let groups: Vec<(usize, usize)> = re
+        .capture_names()
+        .enumerate()
+        .filter_map(|(group, name)| Some((group, name?.strip_prefix(COVERAGE_GROUP)?.parse().ok()?)))
+        .collect();
+    let segments: Vec<&str> = match options.unit {
+        ProcessingUnit::WholeText => vec![source],
+        ProcessingUnit::PerLine => crate::split_lines(source).map(|(line, _)| line).collect(),
+    };
+    let mut used = vec![false; parts.len()];
+    for caps in segments.into_iter().flat_map(|segment| re.captures_iter(segment)).take(limit) {
+        for &(group, part) in &groups {
+            if caps.get(group).is_some() {
+                used[part] = true;
+            }
+        }
+        if used.iter().all(|&used| used) {
+            break;
+        }
+    }
+    Some(Coverage { parts, used })
+}
@@ -0,0 +1,108 @@
+//! Recorrido paso a paso de cómo el patrón avanza sobre el texto: cada
+//! posición donde se intenta una coincidencia, y en las que prosperan, la
+//! entrada y salida de cada grupo hasta la coincidencia completa.
+//!
+//! El motor de `regex` no expone sus estados internos, así que los pasos se
+//! reconstruyen desde las coincidencias: entre una y la siguiente, cada
+//! posición es un intento fallido.
+
+use regex::Regex;
+use std::ops::Range;
+
+use crate::ProcessingUnit;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepKind {
+    /// Ninguna coincidencia empieza en esta posición.
+    NoMatch,
+    /// Empieza una coincidencia.
+    Start,
+    /// Entra al grupo de captura con ese índice.
+    Enter(usize),
+    /// Sale del grupo de captura con ese índice.
+    Exit(usize),
+    /// Coincidencia completa en ese rango.
+    Match(Range<usize>),
+}
+
+/// Paso del recorrido: qué pasa en la posición `at` (en bytes del texto
+/// completo) y, dentro de una coincidencia, dónde empezó.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub at: usize,
+    pub kind: StepKind,
+    pub match_start: Option<usize>,
+}
+
+/// Los primeros `limit` pasos de `re` sobre `source`, por unidad de proceso.
+/// Por línea, las líneas se recorren a medida que hacen falta: al llegar a
+/// `limit` no se mira el resto del texto.
+pub fn trace(re: &Regex, source: &str, unit: ProcessingUnit, limit: usize) -> Vec<Step> {
+    let mut steps = Vec::new();
+    match unit {
+        ProcessingUnit::WholeText => trace_segment(re, source, 0, limit, &mut steps),
+        ProcessingUnit::PerLine => {
+            for (line, _) in crate::split_lines(source) {
+                if steps.len() >= limit {
+                    break;
+                }
+                let offset = line.as_ptr() as usize - source.as_ptr() as usize;
+                trace_segment(re, line, offset, limit, &mut steps);
+            }
+        }
+    }
+    steps.truncate(limit);
+    steps
+}
+
+fn trace_segment(re: &Regex, text: &str, offset: usize, limit: usize, steps: &mut Vec<Step>) {
+    let mut cursor = 0;
+    for caps in re.captures_iter(text) {
+        if steps.len() >= limit {
+            return;
+        }
+        let Some(m) = caps.get(0) else {
+            continue;
+        };
+        fail_between(text, cursor, m.start(), offset, limit, steps);
+        let start = Some(offset + m.start());
+        steps.push(Step { at: offset + m.start(), kind: StepKind::Start, match_start: start });
+
+        // Entradas y salidas por posición. En una misma posición salen primero
+        // los grupos no vacíos que terminan ahí (los internos antes), después
+        // entran los que empiezan (los externos antes) y al final salen los vacíos.
+        let mut events: Vec<(usize, u8, isize, StepKind)> = Vec::new();
+        for (group, span) in caps.iter().enumerate().skip(1) {
+            let Some(span) = span else {
+                continue;
+            };
+            events.push((span.start(), 1, group as isize, StepKind::Enter(group)));
+            let order = if span.is_empty() { 2 } else { 0 };
+            events.push((span.end(), order, -(group as isize), StepKind::Exit(group)));
+        }
+        events.sort_by_key(|(at, order, group, _)| (*at, *order, *group));
+        for (at, _, _, kind) in events {
+            steps.push(Step { at: offset + at, kind, match_start: start });
+        }
+
+        let range = offset + m.start()..offset + m.end();
+        steps.push(Step { at: offset + m.end(), kind: StepKind::Match(range), match_start: start });
+        // Tras una coincidencia vacía el siguiente intento es en el carácter siguiente.
+        cursor = if m.is_empty() { next_boundary(text, m.end()) } else { m.end() };
+    }
+    fail_between(text, cursor, text.len() + 1, offset, limit, steps);
+}
+
+/// Intentos fallidos en cada límite de carácter de `from..to` (hasta el final
+/// del texto, inclusive), sin pasar de `limit` pasos.
+fn fail_between(text: &str, from: usize, to: usize, offset: usize, limit: usize, steps: &mut Vec<Step>) {
+    let mut at = from;
+    while at < to && at <= text.len() && steps.len() < limit {
+        steps.push(Step { at: offset + at, kind: StepKind::NoMatch, match_start: None });
+        at = next_boundary(text, at);
+    }
+}
+
+fn next_boundary(text: &str, at: usize) -> usize {
+    text[at..].chars().next().map_or(at + 1, |c| at + c.len_utf8())
+}
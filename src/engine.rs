@@ -0,0 +1,401 @@
+//! Motor de coincidencias y reemplazos: flags, unidad de procesamiento y la
+//! transformación que muestra el panel de salida (modo filtro o reemplazo).
+
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Unidad de procesamiento: el buffer completo (motor multilínea) o cada línea
+/// por separado (como sed/grep), lo que cambia el sentido de `^`/`$`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProcessingUnit {
+    WholeText,
+    PerLine,
+}
+
+impl ProcessingUnit {
+    pub fn toggle(self) -> ProcessingUnit {
+        match self {
+            ProcessingUnit::WholeText => ProcessingUnit::PerLine,
+            ProcessingUnit::PerLine => ProcessingUnit::WholeText,
+        }
+    }
+}
+
+/// Opciones del motor de regex, activables desde la interfaz o `[flags]` en la configuración.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegexFlags {
+    pub case_insensitive: bool,
+    pub multi_line: bool,
+    pub dot_matches_new_line: bool,
+    pub ignore_whitespace: bool,
+}
+
+impl RegexFlags {
+    /// Resumen compacto al estilo `/patrón/imsx`.
+    pub fn summary(&self) -> String {
+        let mut letters = String::new();
+        if self.case_insensitive {
+            letters.push('i');
+        }
+        if self.multi_line {
+            letters.push('m');
+        }
+        if self.dot_matches_new_line {
+            letters.push('s');
+        }
+        if self.ignore_whitespace {
+            letters.push('x');
+        }
+        if letters.is_empty() {
+            letters.push('-');
+        }
+        letters
+    }
+}
+
+/// Todo lo que influye en cómo se compila y aplica un patrón.
+#[derive(Debug, Clone, Copy)]
+pub struct EngineOptions {
+    pub unit: ProcessingUnit,
+    pub flags: RegexFlags,
+    /// Tamaño máximo (en bytes) del programa compilado.
+    pub size_limit: usize,
+}
+
+/// Resultado de aplicar el patrón sobre el texto fuente.
+#[derive(Clone)]
+pub struct Transform {
+    pub output: String,
+    pub match_count: usize,
+    /// La salida se cortó al llegar al límite de tamaño (`transform_capped`).
+    pub truncated: bool,
+}
+
+/// Compila `pattern` con los flags y el límite de tamaño de `options`.
+pub fn compile(pattern: &str, options: &EngineOptions) -> Result<regex::Regex, regex::Error> {
+    regex::RegexBuilder::new(pattern)
+        .case_insensitive(options.flags.case_insensitive)
+        .multi_line(options.flags.multi_line)
+        .dot_matches_new_line(options.flags.dot_matches_new_line)
+        .ignore_whitespace(options.flags.ignore_whitespace)
+        .size_limit(options.size_limit)
+        .build()
+}
+
+/// Aplica `pattern` sobre `source`: sin reemplazo lista las coincidencias (grep),
+/// con reemplazo devuelve el texto completo modificado (sed).
+pub fn transform(source: &str, pattern: &str, replacement: &str, options: &EngineOptions) -> Result<Transform, regex::Error> {
+    if pattern.is_empty() {
+        return Ok(Transform { output: source.to_string(), match_count: 0, truncated: false });
+    }
+
+    let re = compile(pattern, options)?;
+    Ok(transform_with(&re, source, replacement, options.unit))
+}
+
+/// Como `transform`, con una regex ya compilada.
+pub fn transform_with(re: &regex::Regex, source: &str, replacement: &str, unit: ProcessingUnit) -> Transform {
+    let mut output = String::new();
+    let match_count = transform_into(re, source, replacement, unit, &mut output);
+    Transform { output, match_count, truncated: false }
+}
+
+/// Como `transform_with` en modo reemplazo aunque `replacement` esté vacío, que
+/// entonces borra las coincidencias.
+pub fn replace_with(re: &regex::Regex, source: &str, replacement: &str, unit: ProcessingUnit) -> Transform {
+    let mut output = String::new();
+    let match_count = apply(re, source, Some(replacement), unit, &mut output);
+    Transform { output, match_count, truncated: false }
+}
+
+/// Como `transform_with`, pero la salida deja de crecer al superar `cap` bytes,
+/// para que un reemplazo que multiplica el texto no agote la memoria. Las
+/// coincidencias se cuentan igual hasta el final.
+pub fn transform_capped(
+    re: &regex::Regex,
+    source: &str,
+    replacement: &str,
+    unit: ProcessingUnit,
+    cap: usize,
+) -> Transform {
+    let mut output = CappedString { text: String::new(), cap, truncated: false };
+    let match_count = transform_into(re, source, replacement, unit, &mut output);
+    Transform { output: output.text, match_count, truncated: output.truncated }
+}
+
+/// Destino de la salida de una transformación.
+pub trait OutputSink {
+    fn push(&mut self, text: &str);
+
+    /// Ya no acepta más texto: lo que queda solo se cuenta.
+    fn full(&self) -> bool {
+        false
+    }
+}
+
+impl OutputSink for String {
+    fn push(&mut self, text: &str) {
+        self.push_str(text);
+    }
+}
+
+struct CappedString {
+    text: String,
+    cap: usize,
+    truncated: bool,
+}
+
+impl OutputSink for CappedString {
+    fn push(&mut self, text: &str) {
+        if self.truncated {
+            return;
+        }
+        self.text.push_str(text);
+        if self.text.len() > self.cap {
+            let mut end = self.cap;
+            while !self.text.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.text.truncate(end);
+            self.truncated = true;
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// Escribe la salida en un archivo (u otro `io::Write`) sin tenerla entera en
+/// memoria; tras el primer error deja de escribir y lo conserva en `error`.
+pub struct WriteSink<W: std::io::Write> {
+    pub out: W,
+    pub error: Option<std::io::Error>,
+}
+
+impl<W: std::io::Write> OutputSink for WriteSink<W> {
+    fn push(&mut self, text: &str) {
+        if self.error.is_none()
+            && let Err(e) = self.out.write_all(text.as_bytes())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// Escribe en `sink` la salida de aplicar `re` sobre `source` y devuelve el
+/// número de coincidencias.
+pub fn transform_into(
+    re: &regex::Regex,
+    source: &str,
+    replacement: &str,
+    unit: ProcessingUnit,
+    sink: &mut impl OutputSink,
+) -> usize {
+    let replacement = (!replacement.is_empty()).then_some(replacement);
+    apply(re, source, replacement, unit, sink)
+}
+
+/// Sin `replacement` lista las coincidencias; con él, reemplaza.
+fn apply(
+    re: &regex::Regex,
+    source: &str,
+    replacement: Option<&str>,
+    unit: ProcessingUnit,
+    sink: &mut impl OutputSink,
+) -> usize {
+    match unit {
+        ProcessingUnit::WholeText => transform_whole_text(re, source, replacement, sink),
+        ProcessingUnit::PerLine => transform_per_line(re, source, replacement, sink),
+    }
+}
+
+/// Las líneas de `source` en el modo por línea, cada una con su fin de línea
+/// (`"\n"`, `"\r\n"` o `""` si la última no tiene). Como en `str::lines`, el
+/// texto de la línea no incluye el fin y un salto final no agrega una línea vacía.
+pub fn split_lines(source: &str) -> impl Iterator<Item = (&str, &str)> {
+    source.split_inclusive('\n').map(|line| {
+        let text = line.strip_suffix('\n').map_or(line, |line| line.strip_suffix('\r').unwrap_or(line));
+        (text, &line[text.len()..])
+    })
+}
+
+/// Número de coincidencias de `re` en `source`, sin construir la salida.
+pub fn count_matches(re: &regex::Regex, source: &str, unit: ProcessingUnit) -> usize {
+    match unit {
+        ProcessingUnit::WholeText => re.find_iter(source).count(),
+        ProcessingUnit::PerLine => split_lines(source).map(|(line, _)| re.find_iter(line).count()).sum(),
+    }
+}
+
+/// Dónde está una coincidencia en el texto fuente.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchLocation {
+    /// Línea y columna (en caracteres), desde 1.
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+/// Las primeras `limit` coincidencias de `re` con su posición.
+pub fn locate_matches(re: &regex::Regex, source: &str, unit: ProcessingUnit, limit: usize) -> Vec<MatchLocation> {
+    match unit {
+        ProcessingUnit::WholeText => {
+            let mut locations = Vec::new();
+            let (mut line, mut line_start, mut scanned) = (1, 0, 0);
+            for m in re.find_iter(source).take(limit) {
+                for (offset, _) in source[scanned..m.start()].match_indices('\n') {
+                    line += 1;
+                    line_start = scanned + offset + 1;
+                }
+                scanned = m.start();
+                let column = source[line_start..m.start()].chars().count() + 1;
+                locations.push(MatchLocation { line, column, text: m.as_str().to_string() });
+            }
+            locations
+        }
+        ProcessingUnit::PerLine => split_lines(source)
+            .enumerate()
+            .flat_map(|(n, (text, _))| {
+                re.find_iter(text).map(move |m| MatchLocation {
+                    line: n + 1,
+                    column: text[..m.start()].chars().count() + 1,
+                    text: m.as_str().to_string(),
+                })
+            })
+            .take(limit)
+            .collect(),
+    }
+}
+
+/// Rango de bytes en `source` de las primeras `limit` coincidencias de `re`.
+pub fn match_spans(re: &regex::Regex, source: &str, unit: ProcessingUnit, limit: usize) -> Vec<Range<usize>> {
+    match unit {
+        ProcessingUnit::WholeText => re.find_iter(source).take(limit).map(|m| m.range()).collect(),
+        ProcessingUnit::PerLine => split_lines(source)
+            .flat_map(|(line, _)| {
+                // `split_lines` devuelve porciones de `source`: su desplazamiento sale de los punteros.
+                let offset = line.as_ptr() as usize - source.as_ptr() as usize;
+                re.find_iter(line).map(move |m| offset + m.start()..offset + m.end())
+            })
+            .take(limit)
+            .collect(),
+    }
+}
+
+/// Hay al menos una coincidencia, sin recorrer el resto del texto.
+pub fn has_match(re: &regex::Regex, source: &str, unit: ProcessingUnit) -> bool {
+    match unit {
+        ProcessingUnit::WholeText => re.is_match(source),
+        ProcessingUnit::PerLine => split_lines(source).any(|(line, _)| re.is_match(line)),
+    }
+}
+
+/// Última regex compilada, reutilizada mientras no cambien el patrón, los flags
+/// ni el límite de tamaño (editar solo el texto fuente no recompila).
+#[derive(Default)]
+pub struct RegexCache {
+    pattern: String,
+    flags: RegexFlags,
+    size_limit: usize,
+    compiled: Option<Result<regex::Regex, regex::Error>>,
+}
+
+impl RegexCache {
+    /// La regex de `pattern` con `options` ya está compilada.
+    pub fn is_current(&self, pattern: &str, options: &EngineOptions) -> bool {
+        self.compiled.is_some()
+            && self.pattern == pattern
+            && self.flags == options.flags
+            && self.size_limit == options.size_limit
+    }
+
+    pub fn get(&mut self, pattern: &str, options: &EngineOptions) -> Result<&regex::Regex, regex::Error> {
+        if !self.is_current(pattern, options) {
+            self.pattern = pattern.to_string();
+            self.flags = options.flags;
+            self.size_limit = options.size_limit;
+            self.compiled = Some(compile(pattern, options));
+        }
+        match self.compiled.as_ref().expect("se acaba de compilar") {
+            Ok(re) => Ok(re),
+            Err(e) => Err(e.clone()),
+        }
+    }
+}
+
+fn transform_whole_text(re: &regex::Regex, source: &str, replacement: Option<&str>, sink: &mut impl OutputSink) -> usize {
+    match replacement {
+        // MODO FILTRO (Grep): Mostrar solo coincidencias
+        None => filter_into(re, source, sink),
+        // MODO REEMPLAZO (Sed): Mostrar texto completo con cambios
+        Some(replacement) => replace_into(re, source, replacement, sink),
+    }
+}
+
+fn transform_per_line(re: &regex::Regex, source: &str, replacement: Option<&str>, sink: &mut impl OutputSink) -> usize {
+    let mut match_count = 0;
+    if let Some(replacement) = replacement {
+        // MODO REEMPLAZO (Sed): reemplazar línea por línea, conservando los saltos
+        for (line, ending) in split_lines(source) {
+            match_count += replace_into(re, line, replacement, sink);
+            sink.push(ending);
+        }
+    } else {
+        // MODO FILTRO (Grep): una fila por línea con coincidencias
+        let mut rows = 0;
+        for (idx, (line, _)) in split_lines(source).enumerate() {
+            if !re.is_match(line) {
+                continue;
+            }
+            if rows > 0 {
+                sink.push("\n");
+            }
+            rows += 1;
+            sink.push(&format!("{}: ", idx + 1));
+            match_count += filter_into(re, line, sink);
+        }
+    }
+    match_count
+}
+
+/// Coincidencias separadas por " | ".
+fn filter_into(re: &regex::Regex, text: &str, sink: &mut impl OutputSink) -> usize {
+    let mut count = 0;
+    for m in re.find_iter(text) {
+        if !sink.full() {
+            if count > 0 {
+                sink.push(" | ");
+            }
+            sink.push(m.as_str());
+        }
+        count += 1;
+    }
+    count
+}
+
+/// Equivalente a `replace_all`, escribiendo a medida que avanza.
+fn replace_into(re: &regex::Regex, text: &str, replacement: &str, sink: &mut impl OutputSink) -> usize {
+    let mut count = 0;
+    let mut last = 0;
+    let mut expanded = String::new();
+    for caps in re.captures_iter(text) {
+        count += 1;
+        if sink.full() {
+            continue;
+        }
+        let whole = caps.get(0).expect("el grupo 0 siempre existe");
+        sink.push(&text[last..whole.start()]);
+        expanded.clear();
+        caps.expand(replacement, &mut expanded);
+        sink.push(&expanded);
+        last = whole.end();
+    }
+    sink.push(&text[last..]);
+    count
+}
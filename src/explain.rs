@@ -0,0 +1,151 @@
+//! Explicación en lenguaje natural de un patrón, recorriendo el AST de
+//! `regex-syntax`: una línea por construcción, indentada según el anidamiento.
+
+use regex_syntax::ast::{
+    self, Ast, AssertionKind, ClassPerlKind, ClassUnicodeKind, GroupKind, RepetitionKind, RepetitionRange,
+};
+
+use crate::RegexFlags;
+
+/// Líneas de explicación de `pattern`, o el error de sintaxis.
+pub fn explain(pattern: &str, flags: &RegexFlags) -> Result<Vec<String>, String> {
+    let ast = ast::parse::ParserBuilder::new()
+        .ignore_whitespace(flags.ignore_whitespace)
+        .build()
+        .parse(pattern)
+        .map_err(|e| e.to_string())?;
+    let mut lines = Vec::new();
+    describe(pattern, &ast, 0, &mut lines);
+    Ok(lines)
+}
+
+fn source<'a>(pattern: &'a str, span: &ast::Span) -> &'a str {
+    &pattern[span.start.offset..span.end.offset]
+}
+
+/// Explicación de una línea de la construcción más interna de `pattern` que
+/// contiene el byte `offset`, o `None` si el patrón no es válido.
+pub fn explain_at(pattern: &str, flags: &RegexFlags, offset: usize) -> Option<String> {
+    let ast = ast::parse::ParserBuilder::new()
+        .ignore_whitespace(flags.ignore_whitespace)
+        .build()
+        .parse(pattern)
+        .ok()?;
+    summary(pattern, innermost(&ast, offset)?)
+}
+
+fn contains(span: &ast::Span, offset: usize) -> bool {
+    span.start.offset <= offset && offset < span.end.offset
+}
+
+/// El nodo más interno que contiene `offset`. Los paréntesis de un grupo, el
+/// operador de una repetición y los `|` de una alternancia son del nodo mismo.
+pub(crate) fn innermost(ast: &Ast, offset: usize) -> Option<&Ast> {
+    if !contains(ast.span(), offset) {
+        return None;
+    }
+    let child = match ast {
+        Ast::Repetition(repetition) => innermost(&repetition.ast, offset),
+        Ast::Group(group) => innermost(&group.ast, offset),
+        Ast::Alternation(alternation) => alternation.asts.iter().find_map(|branch| innermost(branch, offset)),
+        Ast::Concat(concat) => concat.asts.iter().find_map(|item| innermost(item, offset)),
+        _ => None,
+    };
+    Some(child.unwrap_or(ast))
+}
+
+/// La línea que describe el nodo en sí, sin sus hijos (`None` en una concatenación).
+fn summary(pattern: &str, ast: &Ast) -> Option<String> {
+    let text = match ast {
+        Ast::Empty(_) => "(vacío: coincide en cualquier posición)".to_string(),
+        Ast::Flags(set) => format!("{}: cambia los flags desde aquí", source(pattern, &set.span)),
+        Ast::Literal(literal) => format!("el carácter '{}'", literal.c),
+        Ast::Dot(_) => "cualquier carácter (salvo salto de línea sin el flag s)".to_string(),
+        Ast::Assertion(assertion) => match assertion.kind {
+            AssertionKind::StartLine => "^: inicio del texto (o de línea con el flag m)",
+            AssertionKind::EndLine => "$: fin del texto (o de línea con el flag m)",
+            AssertionKind::StartText => "\\A: inicio del texto",
+            AssertionKind::EndText => "\\z: fin del texto",
+            AssertionKind::WordBoundary => "\\b: límite de palabra",
+            AssertionKind::NotWordBoundary => "\\B: no es límite de palabra",
+            _ => "límite de palabra especial",
+        }
+        .to_string(),
+        Ast::ClassPerl(class) => {
+            let what = match class.kind {
+                ClassPerlKind::Digit => "un dígito",
+                ClassPerlKind::Space => "un espacio en blanco",
+                ClassPerlKind::Word => "un carácter de palabra (letra, dígito o _)",
+            };
+            let negated = if class.negated { "cualquier cosa salvo " } else { "" };
+            format!("{}: {}{}", source(pattern, &class.span), negated, what)
+        }
+        Ast::ClassUnicode(class) => {
+            let name = match &class.kind {
+                ClassUnicodeKind::OneLetter(c) => c.to_string(),
+                ClassUnicodeKind::Named(name) => name.clone(),
+                ClassUnicodeKind::NamedValue { name, value, .. } => format!("{}={}", name, value),
+            };
+            let negated = if class.negated { "fuera de " } else { "" };
+            format!("{}: un carácter {}la categoría Unicode {}", source(pattern, &class.span), negated, name)
+        }
+        Ast::ClassBracketed(class) => {
+            let negated = if class.negated { "ninguno de" } else { "uno de" };
+            format!("{}: {} los caracteres del conjunto", source(pattern, &class.span), negated)
+        }
+        Ast::Repetition(repetition) => {
+            let times = match &repetition.op.kind {
+                RepetitionKind::ZeroOrOne => "opcional (0 o 1 vez)".to_string(),
+                RepetitionKind::ZeroOrMore => "0 o más veces".to_string(),
+                RepetitionKind::OneOrMore => "1 o más veces".to_string(),
+                RepetitionKind::Range(RepetitionRange::Exactly(n)) => format!("exactamente {} veces", n),
+                RepetitionKind::Range(RepetitionRange::AtLeast(n)) => format!("{} o más veces", n),
+                RepetitionKind::Range(RepetitionRange::Bounded(min, max)) => format!("entre {} y {} veces", min, max),
+            };
+            let greedy = if repetition.greedy { "" } else { ", lo menos posible (perezoso)" };
+            format!("repetir {}{}:", times, greedy)
+        }
+        Ast::Group(group) => match &group.kind {
+            GroupKind::CaptureIndex(index) => format!("grupo de captura {}:", index),
+            GroupKind::CaptureName { name, .. } => format!("grupo de captura {} '{}':", name.index, name.name),
+            GroupKind::NonCapturing(_) => "grupo sin captura:".to_string(),
+        },
+        Ast::Alternation(alternation) => format!("una de {} alternativas:", alternation.asts.len()),
+        Ast::Concat(_) => return None,
+    };
+    Some(text)
+}
+
+fn describe(pattern: &str, ast: &Ast, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    if let Some(text) = summary(pattern, ast) {
+        lines.push(format!("{}{}", indent, text));
+    }
+    match ast {
+        Ast::Repetition(repetition) => describe(pattern, &repetition.ast, depth + 1, lines),
+        Ast::Group(group) => describe(pattern, &group.ast, depth + 1, lines),
+        Ast::Alternation(alternation) => {
+            for branch in &alternation.asts {
+                describe(pattern, branch, depth + 1, lines);
+            }
+        }
+        Ast::Concat(concat) => {
+            // Los literales consecutivos se agrupan en un solo texto.
+            let mut text = String::new();
+            for item in &concat.asts {
+                if let Ast::Literal(literal) = item {
+                    text.push(literal.c);
+                    continue;
+                }
+                if !text.is_empty() {
+                    lines.push(format!("{}el texto '{}'", indent, std::mem::take(&mut text)));
+                }
+                describe(pattern, item, depth, lines);
+            }
+            if !text.is_empty() {
+                lines.push(format!("{}el texto '{}'", indent, text));
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,1323 @@
+//! Exportación a código: traduce el patrón, el reemplazo y los flags actuales a
+//! un fragmento listo para pegar en otro lenguaje o herramienta.
+
+use std::cell::RefCell;
+use std::path::Path;
+
+use crate::{EngineOptions, ProcessingUnit, RegexFlags};
+
+/// Estado que se exporta.
+pub struct ExportInput<'a> {
+    pub pattern: &'a str,
+    pub replacement: &'a str,
+    pub options: EngineOptions,
+    /// Texto de prueba (el panel fuente).
+    pub source: &'a str,
+    /// Archivo del que se cargó el texto, si lo hay.
+    pub source_path: Option<&'a Path>,
+    /// Idioma de los textos para leer (el informe HTML).
+    pub language: crate::config::Language,
+}
+
+/// Lenguaje para "copiar como literal".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Rust,
+    C,
+    Java,
+    Python,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Rust,
+    Python,
+    JavaScript,
+    Sed,
+    Grep,
+    RgReplace,
+    Regex101,
+    Awk,
+    MarkdownTable,
+    RustTest,
+    VsCode,
+    Go,
+    Java,
+    PowerShell,
+    Sql,
+    /// Solo el patrón, escapado como literal de cadena del lenguaje.
+    Literal(Language),
+}
+
+impl Target {
+    pub const ALL: &'static [Target] = &[
+        Target::Rust,
+        Target::Python,
+        Target::JavaScript,
+        Target::Sed,
+        Target::Grep,
+        Target::RgReplace,
+        Target::Regex101,
+        Target::Awk,
+        Target::MarkdownTable,
+        Target::RustTest,
+        Target::VsCode,
+        Target::Go,
+        Target::Java,
+        Target::PowerShell,
+        Target::Sql,
+        Target::Literal(Language::Rust),
+        Target::Literal(Language::C),
+        Target::Literal(Language::Java),
+        Target::Literal(Language::Python),
+        Target::Literal(Language::Json),
+    ];
+
+    pub fn render(self, input: &ExportInput) -> String {
+        match self {
+            Target::Rust => rust_snippet(input),
+            Target::Python => python_snippet(input),
+            Target::JavaScript => javascript_snippet(input),
+            Target::Sed => sed_command(input),
+            Target::Grep => grep_commands(input),
+            Target::RgReplace => rg_replace(input),
+            Target::Regex101 => regex101_share(input),
+            Target::Awk => awk_program(input),
+            Target::MarkdownTable => markdown_table(input),
+            Target::RustTest => rust_test(input),
+            Target::VsCode => vscode_search(input),
+            Target::Go => go_snippet(input),
+            Target::Java => java_snippet(input),
+            Target::PowerShell => powershell_snippet(input),
+            Target::Sql => sql_snippet(input),
+            Target::Literal(language) => string_literal(language, input.pattern),
+        }
+    }
+}
+
+/// Trozo de un reemplazo con la sintaxis de `regex`: texto literal o referencia
+/// a un grupo (`$1`, `${1}`, `$nombre`, `${nombre}`).
+#[derive(Debug, PartialEq)]
+enum ReplacementPart {
+    Literal(String),
+    Group(String),
+}
+
+fn parse_replacement(replacement: &str) -> Vec<ReplacementPart> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut rest = replacement;
+    while let Some(pos) = rest.find('$') {
+        literal.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            literal.push('$');
+            rest = after;
+            continue;
+        }
+        let (name, after) = match rest.strip_prefix('{').and_then(|inner| inner.split_once('}')) {
+            Some((name, after)) => (name, after),
+            None => {
+                let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+                (&rest[..end], &rest[end..])
+            }
+        };
+        if name.is_empty() {
+            literal.push('$');
+            continue;
+        }
+        if !literal.is_empty() {
+            parts.push(ReplacementPart::Literal(std::mem::take(&mut literal)));
+        }
+        parts.push(ReplacementPart::Group(name.to_string()));
+        rest = after;
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        parts.push(ReplacementPart::Literal(literal));
+    }
+    parts
+}
+
+/// Reescribe secuencias de escape (`\z`) y aperturas de grupo con nombre (`(?<`,
+/// `(?P<`) del patrón según `rewrite`, que recibe el token y devuelve su reemplazo si
+/// corresponde.
+fn translate_pattern(pattern: &str, rewrite: impl Fn(&str) -> Option<&'static str>) -> String {
+    translate_tokens(pattern, |token, _| rewrite(token))
+}
+
+/// Como `translate_pattern`, pero `rewrite` también recibe si el token está
+/// dentro de una clase entre corchetes, donde los escapes significan otra cosa
+/// en algunos motores. Las clases POSIX (`[:alpha:]`) llegan como un solo token.
+fn translate_tokens<'a>(pattern: &'a str, rewrite: impl Fn(&'a str, bool) -> Option<&'a str>) -> String {
+    let mut out = String::new();
+    let mut chars = pattern.char_indices();
+    // Clases abiertas, y si el token anterior abrió una (`[` o `[^`): ahí `]` es literal.
+    let (mut depth, mut opened, mut negated) = (0usize, false, false);
+    while let Some((start, c)) = chars.next() {
+        let posix_class = (depth > 0 && c == '[')
+            .then(|| pattern[start..].strip_prefix("[:")?.find(":]").map(|end| start + end + 4))
+            .flatten();
+        let token = match c {
+            '\\' => match chars.next() {
+                Some((end, next)) => &pattern[start..end + next.len_utf8()],
+                None => &pattern[start..],
+            },
+            '[' if posix_class.is_some() => {
+                let end = posix_class.expect("se acaba de comprobar");
+                while chars.next().is_some_and(|(offset, c)| offset + c.len_utf8() < end) {}
+                &pattern[start..end]
+            }
+            '(' if depth == 0 && pattern[start..].starts_with("(?P<") => {
+                chars.nth(2);
+                &pattern[start..start + 4]
+            }
+            '(' if depth == 0
+                && pattern[start..]
+                    .strip_prefix("(?<")
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_alphabetic() || c == '_')) =>
+            {
+                chars.nth(1);
+                &pattern[start..start + 3]
+            }
+            _ => &pattern[start..start + c.len_utf8()],
+        };
+        out.push_str(rewrite(token, depth > 0).unwrap_or(token));
+        let literal_bracket = opened;
+        opened = false;
+        match token {
+            "[" if posix_class.is_none() => {
+                depth += 1;
+                opened = true;
+                negated = false;
+            }
+            "^" if literal_bracket && !negated => {
+                opened = true;
+                negated = true;
+            }
+            "]" if depth > 0 && !literal_bracket => depth -= 1,
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Construcciones de `regex` que el motor destino no entiende, como avisos.
+fn unsupported(pattern: &str, constructs: &[(&str, &str)]) -> Vec<String> {
+    constructs
+        .iter()
+        .filter(|(needle, _)| pattern.contains(needle))
+        .map(|(needle, why)| format!("'{}': {}", needle, why))
+        .collect()
+}
+
+/// Flags como grupo en línea (`(?im)`), o vacío si no hay ninguno.
+fn inline_flags(flags: &RegexFlags) -> String {
+    let letters = [
+        (flags.case_insensitive, 'i'),
+        (flags.multi_line, 'm'),
+        (flags.dot_matches_new_line, 's'),
+        (flags.ignore_whitespace, 'x'),
+    ]
+    .iter()
+    .filter(|(on, _)| *on)
+    .map(|(_, letter)| *letter)
+    .collect::<String>();
+    if letters.is_empty() { String::new() } else { format!("(?{})", letters) }
+}
+
+/// Literal crudo de Rust (`r"…"`, `r#"…"#`, ...) con los `#` justos para que el
+/// texto no lo cierre antes de tiempo. Rust no admite `\r` en un literal crudo,
+/// así que ese texto va como literal con escapes.
+fn rust_raw(text: &str) -> String {
+    if text.contains('\r') {
+        return format!("{:?}", text);
+    }
+    let hashes = (0..)
+        .map(|n| "#".repeat(n))
+        .find(|hashes| !text.contains(&format!("\"{}", hashes)))
+        .expect("siempre hay una cantidad de # suficiente");
+    format!("r{0}\"{1}\"{0}", hashes, text)
+}
+
+fn rust_snippet(input: &ExportInput) -> String {
+    let pattern = format!("{}{}", inline_flags(&input.options.flags), input.pattern);
+    let mut code = format!(
+        "use regex::Regex;\nuse std::sync::LazyLock;\n\nstatic RE: LazyLock<Regex> = LazyLock::new(|| Regex::new({}).unwrap());\n\n",
+        rust_raw(&pattern)
+    );
+    if input.replacement.is_empty() {
+        code.push_str("let matches: Vec<&str> = RE.find_iter(text).map(|m| m.as_str()).collect();\n");
+    } else {
+        code.push_str(&format!("let result = RE.replace_all(text, {});\n", rust_raw(input.replacement)));
+    }
+    code
+}
+
+/// Test de regresión que fija las coincidencias (o el texto reemplazado) que se
+/// observan ahora sobre el texto fuente.
+fn rust_test(input: &ExportInput) -> String {
+    if input.pattern.is_empty() {
+        return "(sin patrón)".to_string();
+    }
+    let re = match crate::compile(input.pattern, &input.options) {
+        Ok(re) => re,
+        Err(e) => return format!("Regex Error: {}", e),
+    };
+    let per_line = input.options.unit == ProcessingUnit::PerLine;
+    let pattern = format!("{}{}", inline_flags(&input.options.flags), input.pattern);
+
+    let mut code = format!(
+        "#[test]\nfn pattern_matches_fixture() {{\n    let re = regex::Regex::new({}).unwrap();\n    let text = {};\n",
+        rust_raw(&pattern),
+        rust_raw(input.source)
+    );
+    if input.replacement.is_empty() {
+        let matches: Vec<String> = if per_line {
+            crate::split_lines(input.source).flat_map(|(line, _)| re.find_iter(line)).map(|m| rust_raw(m.as_str())).collect()
+        } else {
+            re.find_iter(input.source).map(|m| rust_raw(m.as_str())).collect()
+        };
+        let source = if per_line { "text.lines().flat_map(|line| re.find_iter(line))" } else { "re.find_iter(text)" };
+        code.push_str(&format!(
+            "    let matches: Vec<&str> = {}.map(|m| m.as_str()).collect();\n    let expected: [&str; {}] = [{}];\n    assert_eq!(matches, expected);\n",
+            source,
+            matches.len(),
+            matches.join(", ")
+        ));
+    } else {
+        let expected = match crate::transform(input.source, input.pattern, input.replacement, &input.options) {
+            Ok(result) => result.output,
+            Err(e) => return format!("Regex Error: {}", e),
+        };
+        // Por línea, como `split_lines`: el fin de línea no se busca y se conserva.
+        let replaced = if per_line {
+            "text.split_inclusive('\\n').map(|line| {\n        \
+             let body = line.strip_suffix('\\n').map_or(line, |line| line.strip_suffix('\\r').unwrap_or(line));\n        \
+             re.replace_all(body, replacement) + &line[body.len()..]\n    }).collect::<String>()"
+        } else {
+            "re.replace_all(text, replacement).into_owned()"
+        };
+        code.push_str(&format!(
+            "    let replacement = {};\n    assert_eq!({}, {});\n",
+            rust_raw(input.replacement),
+            replaced,
+            rust_raw(&expected)
+        ));
+    }
+    code.push_str("}\n");
+    code
+}
+
+/// Literal de Python, crudo siempre que se pueda.
+fn python_str(text: &str) -> String {
+    if !text.ends_with('\\') && !text.contains('\n') {
+        if !text.contains('"') {
+            return format!("r\"{}\"", text);
+        }
+        if !text.contains('\'') {
+            return format!("r'{}'", text);
+        }
+    }
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+fn python_snippet(input: &ExportInput) -> String {
+    let pattern = translate_pattern(input.pattern, |token| match token {
+        "\\z" => Some("\\Z"),
+        "(?<" => Some("(?P<"),
+        _ => None,
+    });
+    let flags: Vec<&str> = [
+        (input.options.flags.case_insensitive, "re.I"),
+        (input.options.flags.multi_line, "re.M"),
+        (input.options.flags.dot_matches_new_line, "re.S"),
+        (input.options.flags.ignore_whitespace, "re.X"),
+    ]
+    .iter()
+    .filter(|(on, _)| *on)
+    .map(|(_, flag)| *flag)
+    .collect();
+    let flags = if flags.is_empty() { String::new() } else { format!(", {}", flags.join(" | ")) };
+
+    let mut code = String::new();
+    for warning in unsupported(input.pattern, &[
+        ("\\p{", "re no admite clases Unicode (usar el paquete regex)"),
+        ("\\P{", "re no admite clases Unicode (usar el paquete regex)"),
+        ("[[:", "re no admite clases POSIX"),
+    ]) {
+        code.push_str(&format!("# Aviso: {}\n", warning));
+    }
+    code.push_str(&format!("import re\n\nPATTERN = re.compile({}{})\n\n", python_str(&pattern), flags));
+    if input.replacement.is_empty() {
+        code.push_str("matches = [m.group(0) for m in PATTERN.finditer(text)]\n");
+    } else {
+        let replacement: String = parse_replacement(input.replacement)
+            .into_iter()
+            .map(|part| match part {
+                ReplacementPart::Literal(text) => text.replace('\\', "\\\\"),
+                ReplacementPart::Group(name) => format!("\\g<{}>", name),
+            })
+            .collect();
+        code.push_str(&format!("result = PATTERN.sub({}, text)\n", python_str(&replacement)));
+    }
+    code
+}
+
+fn javascript_snippet(input: &ExportInput) -> String {
+    let pattern = translate_pattern(input.pattern, |token| match token {
+        "/" => Some("\\/"),
+        "(?P<" => Some("(?<"),
+        _ => None,
+    });
+    let mut flags = "g".to_string();
+    if input.options.flags.case_insensitive {
+        flags.push('i');
+    }
+    if input.options.flags.multi_line {
+        flags.push('m');
+    }
+    if input.options.flags.dot_matches_new_line {
+        flags.push('s');
+    }
+    if input.pattern.contains("\\p{") || input.pattern.contains("\\P{") {
+        flags.push('u');
+    }
+
+    let mut warnings = unsupported(input.pattern, &[
+        ("(?<", "grupos con nombre y lookbehind requieren ES2018"),
+        ("\\p{", "clases Unicode requieren ES2018 y el flag u"),
+        ("(?i", "JS no admite flags en línea; usar los flags del literal"),
+        ("(?m", "JS no admite flags en línea; usar los flags del literal"),
+        ("(?s", "JS no admite flags en línea; usar los flags del literal"),
+        ("(?x", "JS no admite flags en línea; usar los flags del literal"),
+        ("\\A", "JS no tiene \\A; usar ^ sin el flag m"),
+        ("\\z", "JS no tiene \\z; usar $ sin el flag m"),
+        ("[[:", "JS no admite clases POSIX"),
+    ]);
+    if input.options.flags.dot_matches_new_line {
+        warnings.push("flag s: requiere ES2018".to_string());
+    }
+    if input.options.flags.ignore_whitespace {
+        warnings.push("flag x: JS no lo admite; quitar espacios y comentarios del patrón".to_string());
+    }
+
+    let mut code = String::new();
+    for warning in warnings {
+        code.push_str(&format!("// Aviso: {}\n", warning));
+    }
+    code.push_str(&format!("const re = /{}/{};\n\n", pattern, flags));
+    if input.replacement.is_empty() {
+        code.push_str("const matches = text.match(re) || [];\n");
+    } else {
+        let replacement: String = parse_replacement(input.replacement)
+            .into_iter()
+            .map(|part| match part {
+                ReplacementPart::Literal(text) => text.replace('$', "$$"),
+                ReplacementPart::Group(name) if name.chars().all(|c| c.is_ascii_digit()) => format!("${}", name),
+                ReplacementPart::Group(name) => format!("$<{}>", name),
+            })
+            .collect();
+        let literal = serde_json::to_string(&replacement).expect("un String siempre se serializa");
+        code.push_str(&format!("const result = text.replace(re, {});\n", literal));
+    }
+    code
+}
+
+/// Patrón en sintaxis POSIX ERE (sed -E, grep -E, awk), opcionalmente con `/`
+/// escapada para usarlo entre delimitadores, y avisos sobre lo que no tiene
+/// traducción. Dentro de corchetes ERE no tiene escapes: `\d` pasa a `0-9` y
+/// `\.` al carácter solo.
+fn ere(pattern: &str, escape_slash: bool) -> (String, Vec<String>) {
+    let warnings = RefCell::new(Vec::new());
+    let translated = translate_tokens(pattern, |token, in_class| match token {
+        "/" if escape_slash => Some("\\/"),
+        "\\d" if in_class => Some("0-9"),
+        "\\w" if in_class => Some("[:alnum:]_"),
+        "\\s" if in_class => Some("[:space:]"),
+        _ if in_class && token.starts_with('\\') => {
+            let escaped = &token[1..];
+            if escaped.chars().all(|c| c.is_ascii_punctuation() && !"[]^-".contains(c)) {
+                return Some(escaped);
+            }
+            warnings.borrow_mut().push(format!("'{}' dentro de [...]: ERE no admite escapes en corchetes", token));
+            None
+        }
+        "\\d" => Some("[0-9]"),
+        "\\D" => Some("[^0-9]"),
+        _ => None,
+    });
+    let mut warnings = warnings.into_inner();
+    warnings.dedup();
+    (translated, warnings)
+}
+
+/// Campos para el buscar/reemplazar de VS Code (regex de JS, siempre con `^`/`$`
+/// por línea y búsqueda multilínea solo si el patrón contiene `\n`). Los grupos
+/// con nombre se reemplazan por su número, porque VS Code no los admite en el
+/// reemplazo.
+fn vscode_search(input: &ExportInput) -> String {
+    let flags = &input.options.flags;
+    let pattern = translate_pattern(input.pattern, |token| match token {
+        "(?P<" => Some("(?<"),
+        _ => None,
+    });
+    let names: Vec<Option<String>> = crate::compile(input.pattern, &input.options)
+        .map(|re| re.capture_names().map(|name| name.map(str::to_string)).collect())
+        .unwrap_or_default();
+    let replacement: String = parse_replacement(input.replacement)
+        .into_iter()
+        .map(|part| match part {
+            ReplacementPart::Literal(text) => text.replace('$', "$$"),
+            ReplacementPart::Group(name) => {
+                let index = name
+                    .parse::<usize>()
+                    .ok()
+                    .or_else(|| names.iter().position(|own| own.as_deref() == Some(name.as_str())))
+                    .unwrap_or(0);
+                format!("${}", index)
+            }
+        })
+        .collect();
+
+    let mut warnings = unsupported(input.pattern, &[
+        ("(?i", "VS Code no admite flags en línea; usar el botón de mayúsculas"),
+        ("(?m", "VS Code no admite flags en línea"),
+        ("(?s", "VS Code no admite flags en línea"),
+        ("(?x", "VS Code no admite flags en línea"),
+        ("\\A", "VS Code no tiene \\A"),
+        ("\\z", "VS Code no tiene \\z"),
+        ("[[:", "VS Code no admite clases POSIX"),
+    ]);
+    if !flags.multi_line && (input.pattern.contains('^') || input.pattern.contains('$')) {
+        warnings.push("en VS Code ^ y $ siempre anclan a cada línea (como con el flag m)".to_string());
+    }
+    if flags.dot_matches_new_line {
+        warnings.push("flag s: el punto de VS Code no cruza líneas; usar [\\s\\S]".to_string());
+    }
+    if flags.ignore_whitespace {
+        warnings.push("flag x: VS Code no lo admite; quitar espacios y comentarios del patrón".to_string());
+    }
+
+    let mut text = String::new();
+    for warning in warnings {
+        text.push_str(&format!("Aviso: {}\n", warning));
+    }
+    text.push_str(&format!("Buscar:     {}\n", pattern));
+    if !input.replacement.is_empty() {
+        text.push_str(&format!("Reemplazar: {}\n", replacement));
+    }
+    text.push_str(&format!(
+        "Opciones:   expresión regular (Alt+R) activada, coincidir mayúsculas (Alt+C) {}\n",
+        if flags.case_insensitive { "desactivado" } else { "activado" }
+    ));
+    text
+}
+
+/// Literal de Go: crudo entre acentos graves salvo que el texto los contenga.
+fn go_str(text: &str) -> String {
+    if text.contains('`') { c_like_str(text, true) } else { format!("`{}`", text) }
+}
+
+/// `regexp` de Go usa RE2, muy cercano a `regex`: los flags van en línea y solo
+/// cambian algunas extensiones propias de Rust.
+fn go_snippet(input: &ExportInput) -> String {
+    let flags = RegexFlags { ignore_whitespace: false, ..input.options.flags };
+    let pattern = translate_pattern(input.pattern, |token| match token {
+        "(?<" => Some("(?P<"),
+        _ => None,
+    });
+    let pattern = format!("{}{}", inline_flags(&flags), pattern);
+
+    let mut warnings = unsupported(input.pattern, &[
+        ("&&", "RE2 no admite intersección de clases"),
+        ("--", "RE2 no admite diferencia de clases (revisar si está dentro de [...])"),
+        ("~~", "RE2 no admite diferencia simétrica de clases"),
+        ("\\b{", "RE2 no admite límites de palabra \\b{start}/\\b{end}"),
+        ("(?x", "RE2 no admite el flag x"),
+    ]);
+    if input.options.flags.ignore_whitespace {
+        warnings.push("flag x: RE2 no lo admite; quitar espacios y comentarios del patrón".to_string());
+    }
+
+    let mut code = String::new();
+    for warning in warnings {
+        code.push_str(&format!("// Aviso: {}\n", warning));
+    }
+    code.push_str(&format!("var re = regexp.MustCompile({})\n\n", go_str(&pattern)));
+    if input.replacement.is_empty() {
+        code.push_str("matches := re.FindAllStringSubmatch(text, -1) // [i][0]: coincidencia, [i][n]: grupo n\n");
+    } else {
+        let replacement: String = parse_replacement(input.replacement)
+            .into_iter()
+            .map(|part| match part {
+                ReplacementPart::Literal(text) => text.replace('$', "$$"),
+                ReplacementPart::Group(name) => format!("${{{}}}", name),
+            })
+            .collect();
+        code.push_str(&format!("result := re.ReplaceAllString(text, {})\n", go_str(&replacement)));
+    }
+    code
+}
+
+fn java_snippet(input: &ExportInput) -> String {
+    let flags = &input.options.flags;
+    let pattern = translate_pattern(input.pattern, |token| match token {
+        "(?P<" => Some("(?<"),
+        _ => None,
+    });
+    // `regex` es Unicode por defecto; en Java hay que pedirlo explícitamente, o
+    // `\d`, `\w` y `\b` solo reconocen ASCII. Este flag también implica `UNICODE_CASE`.
+    let constants: Vec<&str> = [
+        (true, "Pattern.UNICODE_CHARACTER_CLASS"),
+        (flags.case_insensitive, "Pattern.CASE_INSENSITIVE"),
+        (flags.multi_line, "Pattern.MULTILINE"),
+        (flags.dot_matches_new_line, "Pattern.DOTALL"),
+        (flags.ignore_whitespace, "Pattern.COMMENTS"),
+    ]
+    .iter()
+    .filter(|(on, _)| *on)
+    .map(|(_, constant)| *constant)
+    .collect();
+    let constants = constants.join(" | ");
+
+    let mut code = String::new();
+    let names: Vec<String> = crate::compile(input.pattern, &input.options)
+        .map(|re| re.capture_names().flatten().map(str::to_string).collect())
+        .unwrap_or_default();
+    for name in names.iter().filter(|name| !name.chars().all(|c| c.is_ascii_alphanumeric())) {
+        code.push_str(&format!("// Aviso: el grupo '{}' no es un nombre válido en Java (solo letras y dígitos)\n", name));
+    }
+    if input.replacement.is_empty() {
+        code.push_str("import java.util.ArrayList;\nimport java.util.List;\n");
+    }
+    code.push_str("import java.util.regex.Matcher;\nimport java.util.regex.Pattern;\n\n");
+    code.push_str(&format!(
+        "private static final Pattern PATTERN = Pattern.compile({}, {});\n\n",
+        c_like_str(&pattern, true),
+        constants
+    ));
+    if input.replacement.is_empty() {
+        code.push_str(
+            "List<String> matches = new ArrayList<>();\nMatcher matcher = PATTERN.matcher(text);\nwhile (matcher.find()) {\n    matches.add(matcher.group());\n}\n",
+        );
+    } else {
+        let replacement: String = parse_replacement(input.replacement)
+            .into_iter()
+            .map(|part| match part {
+                ReplacementPart::Literal(text) => text.replace('\\', "\\\\").replace('$', "\\$"),
+                ReplacementPart::Group(name) if name.chars().all(|c| c.is_ascii_digit()) => format!("${}", name),
+                ReplacementPart::Group(name) => format!("${{{}}}", name),
+            })
+            .collect();
+        code.push_str(&format!(
+            "String result = PATTERN.matcher(text).replaceAll({});\n",
+            c_like_str(&replacement, true)
+        ));
+    }
+    code
+}
+
+/// Argumento entre comillas simples para sh (`'` se escribe `'\''`).
+fn sh_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+fn sed_command(input: &ExportInput) -> String {
+    let (pattern, mut warnings) = ere(input.pattern, true);
+    warnings.extend(unsupported(input.pattern, &[
+        ("(?", "ERE no admite grupos especiales (sin captura, con nombre ni flags en línea)"),
+        ("\\p{", "ERE no admite clases Unicode"),
+        ("*?", "ERE no admite cuantificadores perezosos"),
+        ("+?", "ERE no admite cuantificadores perezosos"),
+    ]));
+    if input.options.flags.multi_line || input.options.flags.dot_matches_new_line || input.options.flags.ignore_whitespace {
+        warnings.push("sed procesa línea por línea; los flags m, s y x no tienen equivalente".to_string());
+    }
+
+    let mut groups = Vec::new();
+    let replacement: String = parse_replacement(input.replacement)
+        .into_iter()
+        .map(|part| match part {
+            ReplacementPart::Literal(text) => text.replace('\\', "\\\\").replace('&', "\\&").replace('/', "\\/"),
+            ReplacementPart::Group(name) => match name.parse::<u8>() {
+                Ok(0) => "&".to_string(),
+                Ok(n) if n <= 9 => format!("\\{}", n),
+                _ => {
+                    groups.push(name);
+                    String::new()
+                }
+            },
+        })
+        .collect();
+    for name in groups {
+        warnings.push(format!("sed solo referencia grupos \\1..\\9; se omitió ${}", name));
+    }
+
+    let mut script = format!("s/{}/{}/g", pattern, replacement);
+    if input.options.flags.case_insensitive {
+        script.push('I');
+    }
+    if input.replacement.is_empty() {
+        // Sin reemplazo: como el modo filtro, imprimir solo las líneas que coinciden.
+        script = format!("/{}/{}p", pattern, if input.options.flags.case_insensitive { "I" } else { "" });
+    }
+
+    let mut code = String::new();
+    for warning in warnings {
+        code.push_str(&format!("# Aviso: {}\n", warning));
+    }
+    let quiet = if input.replacement.is_empty() { "-n " } else { "" };
+    code.push_str(&format!("sed -E {}{} archivo.txt\n", quiet, sh_quote(&script)));
+    code
+}
+
+/// PowerShell usa el motor de .NET: `-replace` ignora mayúsculas salvo que se use
+/// `-creplace`, y el resto de los flags va en línea. El resultado no puede ir en
+/// `$matches`, que PowerShell sobrescribe en cada `-match`.
+fn powershell_snippet(input: &ExportInput) -> String {
+    let flags = &input.options.flags;
+    let pattern = translate_pattern(input.pattern, |token| match token {
+        "(?P<" => Some("(?<"),
+        _ => None,
+    });
+    let pattern = format!("{}{}", inline_flags(&RegexFlags { case_insensitive: false, ..*flags }), pattern);
+
+    let mut code = String::new();
+    for warning in unsupported(input.pattern, &[
+        ("[[:", ".NET no admite clases POSIX"),
+        ("&&", ".NET no admite intersección de clases"),
+    ]) {
+        code.push_str(&format!("# Aviso: {}\n", warning));
+    }
+    if input.replacement.is_empty() {
+        let options = if flags.case_insensitive { ", 'IgnoreCase'" } else { "" };
+        code.push_str(&format!(
+            "$found = [regex]::Matches($text, {}{}) | ForEach-Object {{ $_.Value }}\n",
+            powershell_quote(&pattern),
+            options
+        ));
+    } else {
+        let replacement: String = parse_replacement(input.replacement)
+            .into_iter()
+            .map(|part| match part {
+                ReplacementPart::Literal(text) => text.replace('$', "$$"),
+                ReplacementPart::Group(name) => format!("${{{}}}", name),
+            })
+            .collect();
+        let operator = if flags.case_insensitive { "-replace" } else { "-creplace" };
+        code.push_str(&format!(
+            "$result = $text {} {}, {}\n",
+            operator,
+            powershell_quote(&pattern),
+            powershell_quote(&replacement)
+        ));
+    }
+    code
+}
+
+/// Literal de cadena SQL estándar (`'` se duplica); MySQL además necesita las
+/// barras invertidas duplicadas.
+fn sql_quote(text: &str, escape_backslash: bool) -> String {
+    let text = if escape_backslash { text.replace('\\', "\\\\") } else { text.to_string() };
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Aproximaciones para PostgreSQL (ARE) y MySQL 8 (ICU), con avisos sobre lo que
+/// cambia entre motores.
+fn sql_snippet(input: &ExportInput) -> String {
+    let flags = &input.options.flags;
+
+    // PostgreSQL: sus flags combinan el sentido de `.` y de `^`/`$` en uno solo.
+    let mut pg_flags = match (flags.multi_line, flags.dot_matches_new_line) {
+        (false, false) => "p",
+        (true, false) => "n",
+        (false, true) => "s",
+        (true, true) => "w",
+    }
+    .to_string();
+    if flags.case_insensitive {
+        pg_flags.push('i');
+    }
+    if flags.ignore_whitespace {
+        pg_flags.push('x');
+    }
+    // En ARE `\b` es un retroceso; el límite de palabra es `\y`.
+    let pg_pattern = sql_quote(
+        &translate_pattern(input.pattern, |token| match token {
+            "\\z" => Some("\\Z"),
+            "\\b" => Some("\\y"),
+            "\\B" => Some("\\Y"),
+            _ => None,
+        }),
+        false,
+    );
+
+    // MySQL: c/i mayúsculas, m multilínea, n punto con saltos de línea.
+    let mut my_flags = if flags.case_insensitive { "i" } else { "c" }.to_string();
+    if flags.multi_line {
+        my_flags.push('m');
+    }
+    if flags.dot_matches_new_line {
+        my_flags.push('n');
+    }
+    if flags.ignore_whitespace {
+        my_flags.push('x');
+    }
+    let my_pattern = sql_quote(
+        &translate_pattern(input.pattern, |token| match token {
+            "(?P<" => Some("(?<"),
+            _ => None,
+        }),
+        true,
+    );
+
+    let mut code = String::new();
+    for warning in unsupported(input.pattern, &[
+        ("(?<", "PostgreSQL no admite grupos con nombre"),
+        ("(?P<", "PostgreSQL no admite grupos con nombre"),
+        ("\\p{", "PostgreSQL no admite clases Unicode \\p{...}"),
+    ]) {
+        code.push_str(&format!("-- Aviso: {}\n", warning));
+    }
+    code.push_str("-- Aviso: MySQL anterior a 8.0.4 no usa ICU (sin \\d, lookaround ni flags en línea)\n\n");
+
+    code.push_str("-- PostgreSQL\n");
+    if input.replacement.is_empty() {
+        code.push_str(&format!(
+            "SELECT regexp_matches(columna, {}, {}) FROM tabla;\n",
+            pg_pattern,
+            sql_quote(&format!("g{}", pg_flags), false)
+        ));
+    } else {
+        let pg_replacement: String = parse_replacement(input.replacement)
+            .into_iter()
+            .map(|part| match part {
+                // `&` es literal; `\&` sería la coincidencia completa.
+                ReplacementPart::Literal(text) => text.replace('\\', "\\\\"),
+                ReplacementPart::Group(name) if name == "0" => "\\&".to_string(),
+                ReplacementPart::Group(name) if name.len() == 1 && name.chars().all(|c| c.is_ascii_digit()) => {
+                    format!("\\{}", name)
+                }
+                ReplacementPart::Group(name) => {
+                    code.push_str(&format!("-- Aviso: PostgreSQL solo referencia \\1..\\9; se omitió ${}\n", name));
+                    String::new()
+                }
+            })
+            .collect();
+        code.push_str(&format!(
+            "SELECT regexp_replace(columna, {}, {}, {}) FROM tabla;\n",
+            pg_pattern,
+            sql_quote(&pg_replacement, false),
+            sql_quote(&format!("g{}", pg_flags), false)
+        ));
+    }
+
+    code.push_str("\n-- MySQL 8\n");
+    if input.replacement.is_empty() {
+        code.push_str(&format!(
+            "SELECT REGEXP_SUBSTR(columna, {}, 1, 1, {}) FROM tabla; -- una coincidencia por fila\n",
+            my_pattern,
+            sql_quote(&my_flags, false)
+        ));
+    } else {
+        let my_replacement: String = parse_replacement(input.replacement)
+            .into_iter()
+            .map(|part| match part {
+                ReplacementPart::Literal(text) => text.replace('\\', "\\\\").replace('$', "\\$"),
+                ReplacementPart::Group(name) if name.chars().all(|c| c.is_ascii_digit()) => format!("${}", name),
+                ReplacementPart::Group(name) => format!("${{{}}}", name),
+            })
+            .collect();
+        code.push_str(&format!(
+            "SELECT REGEXP_REPLACE(columna, {}, {}, 1, 0, {}) FROM tabla;\n",
+            my_pattern,
+            sql_quote(&my_replacement, true),
+            sql_quote(&my_flags, false)
+        ));
+    }
+    code
+}
+
+/// Invocación de rg con `--replace` sobre todos los archivos del mismo tipo que
+/// el abierto. rg usa el mismo motor y la misma sintaxis de reemplazo que la
+/// aplicación, así que el patrón va sin traducir.
+fn rg_replace(input: &ExportInput) -> String {
+    let glob = input
+        .source_path
+        .and_then(|path| path.extension())
+        .map(|ext| format!("*.{}", ext.to_string_lossy()))
+        .unwrap_or_else(|| "*".to_string());
+    let pattern = format!("{}{}", inline_flags(&input.options.flags), input.pattern);
+    let multiline = if input.options.flags.dot_matches_new_line || input.pattern.contains("\\n") { "-U " } else { "" };
+
+    let mut code = String::from(
+        "# rg solo muestra el resultado: no modifica los archivos.\n\
+         # Para aplicarlo, revisar la salida y luego usar --passthru por archivo\n\
+         # (rg --passthru -r ... archivo > tmp && mv tmp archivo).\n",
+    );
+    code.push_str(&format!(
+        "rg {}{} --replace {} -g {} .\n",
+        multiline,
+        sh_quote(&pattern),
+        sh_quote(input.replacement),
+        sh_quote(&glob)
+    ));
+    code
+}
+
+/// Argumento entre comillas simples para PowerShell (`'` se escribe `''`).
+fn powershell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn grep_commands(input: &ExportInput) -> String {
+    let flags = &input.options.flags;
+    let case = if flags.case_insensitive { "-i " } else { "" };
+    let (ere, mut warnings) = ere(input.pattern, false);
+    // PCRE y rg entienden los flags en línea; i va como opción por legibilidad.
+    let inline = inline_flags(&RegexFlags { case_insensitive: false, ..*flags });
+    let pcre = format!("{}{}", inline, input.pattern);
+    let multiline = if flags.dot_matches_new_line || input.pattern.contains("\\n") {
+        "-U --multiline-dotall "
+    } else {
+        ""
+    };
+
+    warnings.extend(unsupported(input.pattern, &[
+        ("(?", "grep -E no admite grupos especiales"),
+        ("\\p{", "grep -E no admite clases Unicode"),
+    ]));
+    let mut code = String::new();
+    for warning in warnings {
+        code.push_str(&format!("# Aviso: {}\n", warning));
+    }
+    if !multiline.is_empty() {
+        code.push_str("# Aviso: grep trabaja por líneas; solo rg -U puede cruzar saltos de línea\n");
+    }
+    code.push_str("# sh\n");
+    code.push_str(&format!("grep -P {}-o {} archivo.txt\n", case, sh_quote(&pcre)));
+    code.push_str(&format!("grep -E {}-o {} archivo.txt\n", case, sh_quote(&ere)));
+    code.push_str(&format!("rg {}{}-o {} archivo.txt\n", case, multiline, sh_quote(&pcre)));
+    code.push_str("\n# PowerShell\n");
+    code.push_str(&format!("rg {}{}-o {} archivo.txt\n", case, multiline, powershell_quote(&pcre)));
+    code.push_str(&format!("Select-String -Pattern {} -Path archivo.txt{}\n", powershell_quote(&pcre), if flags.case_insensitive { "" } else { " -CaseSensitive" }));
+    code
+}
+
+/// Programa awk que imprime cada coincidencia con sus grupos en columnas
+/// separadas por tabulador (gawk), más una variante POSIX que solo puede
+/// extraer la coincidencia completa.
+fn awk_program(input: &ExportInput) -> String {
+    let (pattern, mut warnings) = ere(input.pattern, true);
+    let groups = crate::compile(input.pattern, &input.options).map(|re| re.captures_len() - 1).unwrap_or(0);
+    let columns = if groups == 0 {
+        "g[0]".to_string()
+    } else {
+        (1..=groups).map(|n| format!("g[{}]", n)).collect::<Vec<_>>().join(", ")
+    };
+    let ignore_case = if input.options.flags.case_insensitive { " IGNORECASE = 1;" } else { "" };
+    let gawk = format!(
+        "BEGIN {{ OFS = \"\\t\";{} }} {{ s = $0; while (match(s, /{}/, g) && RLENGTH > 0) {{ print {}; s = substr(s, RSTART + RLENGTH) }} }}",
+        ignore_case, pattern, columns
+    );
+    let posix = format!(
+        "{{ s = $0; while (match(s, /{}/) && RLENGTH > 0) {{ print substr(s, RSTART, RLENGTH); s = substr(s, RSTART + RLENGTH) }} }}",
+        pattern
+    );
+
+    warnings.extend(unsupported(input.pattern, &[
+        ("(?", "awk no admite grupos especiales"),
+        ("\\p{", "awk no admite clases Unicode"),
+        ("\\b", "en awk es un retroceso; gawk usa \\y para el límite de palabra"),
+        ("\\B", "awk POSIX no lo admite; gawk usa \\B"),
+        ("\\w", "awk POSIX no lo admite (gawk sí); usar [[:alnum:]_]"),
+        ("\\s", "awk POSIX no lo admite (gawk sí); usar [[:space:]]"),
+    ]));
+    let mut code = String::new();
+    for warning in warnings {
+        code.push_str(&format!("# Aviso: {}\n", warning));
+    }
+    code.push_str(&format!("# gawk: un grupo por columna\ngawk {} archivo.txt\n\n", sh_quote(&gawk)));
+    if input.options.flags.case_insensitive {
+        code.push_str("# Aviso: awk POSIX no tiene IGNORECASE; la variante distingue mayúsculas\n");
+    }
+    code.push_str(&format!("# awk POSIX: solo la coincidencia completa\nawk {} archivo.txt\n", sh_quote(&posix)));
+    code
+}
+
+/// Celda de tabla Markdown: sin `|` ni saltos de línea que rompan la fila.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// Tabla de coincidencias al estilo GitHub: una fila por coincidencia y una
+/// columna por grupo (con su nombre si lo tiene). En modo por línea se agrega el
+/// número de línea.
+fn markdown_table(input: &ExportInput) -> String {
+    if input.pattern.is_empty() {
+        return "(sin patrón)".to_string();
+    }
+    let re = match crate::compile(input.pattern, &input.options) {
+        Ok(re) => re,
+        Err(e) => return format!("Regex Error: {}", e),
+    };
+    let per_line = input.options.unit == ProcessingUnit::PerLine;
+
+    let mut header = vec!["#".to_string()];
+    if per_line {
+        header.push("Línea".to_string());
+    }
+    header.push("Coincidencia".to_string());
+    for (index, name) in re.capture_names().enumerate().skip(1) {
+        header.push(name.map(str::to_string).unwrap_or_else(|| index.to_string()));
+    }
+
+    let lines: Vec<(usize, &str)> = match input.options.unit {
+        ProcessingUnit::WholeText => vec![(0, input.source)],
+        ProcessingUnit::PerLine => crate::split_lines(input.source).enumerate().map(|(n, (line, _))| (n + 1, line)).collect(),
+    };
+    let mut rows = Vec::new();
+    for (line_number, text) in lines {
+        for caps in re.captures_iter(text) {
+            let mut row = vec![(rows.len() + 1).to_string()];
+            if per_line {
+                row.push(line_number.to_string());
+            }
+            row.extend(caps.iter().map(|group| markdown_cell(group.map_or("", |m| m.as_str()))));
+            rows.push(row);
+        }
+    }
+
+    let mut table = format!("| {} |\n|{}\n", header.join(" | "), " --- |".repeat(header.len()));
+    for row in rows {
+        table.push_str(&format!("| {} |\n", row.join(" | ")));
+    }
+    table
+}
+
+/// Codifica `text` para una query string (todo salvo los caracteres no reservados).
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Enlace que abre el estado actual en regex101 (sabor Rust), seguido del mismo
+/// contenido como JSON para quien prefiera pegarlo a mano.
+fn regex101_share(input: &ExportInput) -> String {
+    let flags = format!("g{}", inline_flags(&input.options.flags).trim_start_matches("(?").trim_end_matches(')'));
+    let mut url = format!(
+        "https://regex101.com/?flavor=rust&regex={}&flags={}&testString={}",
+        percent_encode(input.pattern),
+        flags,
+        percent_encode(input.source)
+    );
+    if !input.replacement.is_empty() {
+        url.push_str(&format!("&subst={}", percent_encode(input.replacement)));
+    }
+    let payload = serde_json::json!({
+        "regex": input.pattern,
+        "flags": flags,
+        "flavor": "rust",
+        "testString": input.source,
+        "substitution": input.replacement,
+    });
+    format!(
+        "{}\n\n{}\n",
+        url,
+        serde_json::to_string_pretty(&payload).expect("un valor JSON siempre se serializa")
+    )
+}
+
+/// Literal entre comillas dobles con las secuencias de escape comunes a C, Java y
+/// JSON (`\\`, `\"`, `\n`, `\t`, `\r`); el resto de los controles va como
+/// `\uXXXX` o, en C, en octal.
+fn c_like_str(text: &str, unicode_escape: bool) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() && unicode_escape => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c if c.is_control() => out.push_str(&format!("\\{:03o}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn string_literal(language: Language, text: &str) -> String {
+    match language {
+        Language::Rust => rust_raw(text),
+        Language::C => c_like_str(text, false),
+        Language::Java | Language::Json => c_like_str(text, true),
+        Language::Python => python_str(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>(pattern: &'a str, replacement: &'a str, flags: RegexFlags) -> ExportInput<'a> {
+        ExportInput {
+            pattern,
+            replacement,
+            options: EngineOptions { unit: ProcessingUnit::WholeText, flags, size_limit: 1 << 20 },
+            source: "",
+            source_path: None,
+            language: crate::config::Language::Es,
+        }
+    }
+
+    fn case_insensitive() -> RegexFlags {
+        RegexFlags { case_insensitive: true, ..RegexFlags::default() }
+    }
+
+    #[test]
+    fn sed_escapes_the_replacement_and_maps_groups() {
+        let code = sed_command(&input(r"(\d+)/(\w+)", r"$2&$1\${name}", RegexFlags::default()));
+        assert_eq!(
+            code,
+            "# Aviso: sed solo referencia grupos \\1..\\9; se omitió $name\n\
+             sed -E 's/([0-9]+)\\/(\\w+)/\\2\\&\\1\\\\/g' archivo.txt\n"
+        );
+        assert_eq!(sed_command(&input("it's", "$0!", RegexFlags::default())), "sed -E 's/it'\\''s/&!/g' archivo.txt\n");
+    }
+
+    #[test]
+    fn sed_filters_lines_without_a_replacement() {
+        assert_eq!(sed_command(&input("error", "", case_insensitive())), "sed -E -n '/error/Ip' archivo.txt\n");
+        let flags = RegexFlags { multi_line: true, ..RegexFlags::default() };
+        assert!(sed_command(&input("a", "b", flags)).starts_with("# Aviso: sed procesa línea por línea"));
+    }
+
+    #[test]
+    fn grep_passes_inline_flags_to_pcre_and_rg() {
+        let flags = RegexFlags { case_insensitive: true, multi_line: true, ..RegexFlags::default() };
+        assert_eq!(
+            grep_commands(&input(r"\d+", "", flags)),
+            "# sh\n\
+             grep -P -i -o '(?m)\\d+' archivo.txt\n\
+             grep -E -i -o '[0-9]+' archivo.txt\n\
+             rg -i -o '(?m)\\d+' archivo.txt\n\
+             \n\
+             # PowerShell\n\
+             rg -i -o '(?m)\\d+' archivo.txt\n\
+             Select-String -Pattern '(?m)\\d+' -Path archivo.txt\n"
+        );
+    }
+
+    #[test]
+    fn grep_quotes_for_each_shell_and_warns_about_multiline() {
+        let code = grep_commands(&input(r"(?:it's)\n", "", RegexFlags::default()));
+        assert!(code.starts_with("# Aviso: '(?': grep -E no admite grupos especiales\n"));
+        assert!(code.contains("# Aviso: grep trabaja por líneas"));
+        assert!(code.contains("grep -P -o '(?:it'\\''s)\\n' archivo.txt\n"));
+        assert!(code.contains("rg -U --multiline-dotall -o '(?:it''s)\\n' archivo.txt\n"));
+        assert!(code.ends_with("Select-String -Pattern '(?:it''s)\\n' -Path archivo.txt -CaseSensitive\n"));
+    }
+
+    #[test]
+    fn regex101_link_encodes_every_field() {
+        let flags = RegexFlags { case_insensitive: true, multi_line: true, ..RegexFlags::default() };
+        let export = ExportInput { source: "x y\nñ", ..input("(a b)&", "$1", flags) };
+        let shared = regex101_share(&export);
+        let (url, json) = shared.split_once("\n\n").unwrap();
+        assert_eq!(
+            url,
+            "https://regex101.com/?flavor=rust&regex=%28a%20b%29%26&flags=gim&testString=x%20y%0A%C3%B1&subst=%241"
+        );
+        let payload: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(payload["regex"], "(a b)&");
+        assert_eq!(payload["flags"], "gim");
+        assert_eq!(payload["testString"], "x y\nñ");
+        assert_eq!(payload["substitution"], "$1");
+    }
+
+    #[test]
+    fn string_literals_escape_per_language() {
+        assert_eq!(string_literal(Language::Rust, r"\d+"), r#"r"\d+""#);
+        assert_eq!(string_literal(Language::Rust, "\"#"), "r##\"\"#\"##");
+        assert_eq!(string_literal(Language::C, "a\"\\\n\u{1}"), r#""a\"\\\n\001""#);
+        assert_eq!(string_literal(Language::Java, "\\d\t\u{1}"), r#""\\d\t\u0001""#);
+        assert_eq!(string_literal(Language::Json, "\r"), r#""\r""#);
+        assert_eq!(string_literal(Language::Python, r"\d+"), r#"r"\d+""#);
+        assert_eq!(string_literal(Language::Python, r#"say "hi""#), r#"r'say "hi"'"#);
+        // Un literal crudo no puede terminar en barra invertida ni tener los dos tipos de comillas.
+        assert_eq!(string_literal(Language::Python, r"a\"), r#""a\\""#);
+        assert_eq!(string_literal(Language::Python, r#"'""#), r#""'\"""#);
+    }
+
+    #[test]
+    fn ere_translates_shorthands_inside_brackets() {
+        assert_eq!(ere(r"[\d.]+\d", false), ("[0-9.]+[0-9]".to_string(), Vec::new()));
+        assert_eq!(ere(r"[^\w\s-]\D", false), ("[^[:alnum:]_[:space:]-][^0-9]".to_string(), Vec::new()));
+        assert_eq!(ere(r"[]\.[:alpha:]/]/", true), (r"[].[:alpha:]\/]\/".to_string(), Vec::new()));
+        assert_eq!(ere(r"[^]\d]", false).0, "[^]0-9]");
+        let (pattern, warnings) = ere(r"[\D\]]", false);
+        assert_eq!(pattern, r"[\D\]]");
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn awk_prints_groups_and_warns_about_perl_escapes() {
+        let code = awk_program(&input(r"(\w+)=([\d.]+)", "", case_insensitive()));
+        assert!(code.starts_with("# Aviso: '\\w': awk POSIX no lo admite"));
+        assert!(code.contains(
+            r#"gawk 'BEGIN { OFS = "\t"; IGNORECASE = 1; } { s = $0; while (match(s, /(\w+)=([0-9.]+)/, g) && RLENGTH > 0) { print g[1], g[2]; s = substr(s, RSTART + RLENGTH) } }' archivo.txt"#
+        ));
+        assert!(code.contains("# Aviso: awk POSIX no tiene IGNORECASE"));
+        assert!(code.contains(r"match(s, /(\w+)=([0-9.]+)/) && RLENGTH > 0) { print substr(s, RSTART, RLENGTH)"));
+        assert!(awk_program(&input(r"\bid\b", "", RegexFlags::default())).contains("gawk usa \\y"));
+        assert!(awk_program(&input("a/b", "", RegexFlags::default())).contains(r"/a\/b/"));
+    }
+
+    #[test]
+    fn markdown_table_has_a_column_per_group() {
+        let mut export = ExportInput { source: "a|b=1 x\r\nc=2", ..input(r"(?P<key>[\w|]+)=(\d)", "", RegexFlags::default()) };
+        export.options.unit = ProcessingUnit::PerLine;
+        assert_eq!(
+            markdown_table(&export),
+            "| # | Línea | Coincidencia | key | 2 |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | 1 | 1 | a\\|b=1 | a\\|b | 1 |\n\
+             | 2 | 2 | c=2 | c | 2 |\n"
+        );
+        let export = ExportInput { source: "a\nb", ..input(r"a\s(b)?", "", RegexFlags::default()) };
+        assert_eq!(markdown_table(&export), "| # | Coincidencia | 1 |\n| --- | --- | --- |\n| 1 | a<br>b | b |\n");
+    }
+
+    #[test]
+    fn rust_test_pins_the_current_matches() {
+        let export = ExportInput { source: r#"a1 "b22"#, ..input(r"\d+", "", case_insensitive()) };
+        assert_eq!(
+            rust_test(&export),
+            "#[test]\n\
+             fn pattern_matches_fixture() {\n    \
+             let re = regex::Regex::new(r\"(?i)\\d+\").unwrap();\n    \
+             let text = r#\"a1 \"b22\"#;\n    \
+             let matches: Vec<&str> = re.find_iter(text).map(|m| m.as_str()).collect();\n    \
+             let expected: [&str; 2] = [r\"1\", r\"22\"];\n    \
+             assert_eq!(matches, expected);\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn rust_test_keeps_line_endings_per_line() {
+        let mut export = ExportInput { source: "a\r\n\r\nb\n", ..input("^$", "-", RegexFlags::default()) };
+        export.options.unit = ProcessingUnit::PerLine;
+        let code = rust_test(&export);
+        assert!(code.contains("    let text = \"a\\r\\n\\r\\nb\\n\";\n"));
+        assert!(code.contains("    let replacement = r\"-\";\n"));
+        assert!(code.contains("re.replace_all(body, replacement) + &line[body.len()..]"));
+        assert!(code.ends_with("}).collect::<String>(), \"a\\r\\n-\\r\\nb\\n\");\n}\n"));
+    }
+
+    #[test]
+    fn vscode_numbers_named_groups_in_the_replacement() {
+        assert_eq!(
+            vscode_search(&input(r"(?P<y>\d{4})-(\d+)$", "${y}$$/$2", case_insensitive())),
+            "Aviso: en VS Code ^ y $ siempre anclan a cada línea (como con el flag m)\n\
+             Buscar:     (?<y>\\d{4})-(\\d+)$\n\
+             Reemplazar: $1$$/$2\n\
+             Opciones:   expresión regular (Alt+R) activada, coincidir mayúsculas (Alt+C) desactivado\n"
+        );
+        let flags = RegexFlags { dot_matches_new_line: true, ..RegexFlags::default() };
+        let text = vscode_search(&input(r"\Aa.b", "", flags));
+        assert!(text.starts_with("Aviso: '\\A': VS Code no tiene \\A\nAviso: flag s:"));
+        assert!(!text.contains("Reemplazar:"));
+        assert!(text.ends_with("coincidir mayúsculas (Alt+C) activado\n"));
+    }
+
+    #[test]
+    fn go_keeps_flags_inline_and_braces_group_references() {
+        let flags = RegexFlags { case_insensitive: true, ignore_whitespace: true, ..RegexFlags::default() };
+        assert_eq!(
+            go_snippet(&input(r"(?<n>a)\b{start}", "$n-$$", flags)),
+            "// Aviso: '\\b{': RE2 no admite límites de palabra \\b{start}/\\b{end}\n\
+             // Aviso: flag x: RE2 no lo admite; quitar espacios y comentarios del patrón\n\
+             var re = regexp.MustCompile(`(?i)(?P<n>a)\\b{start}`)\n\
+             \n\
+             result := re.ReplaceAllString(text, `${n}-$$`)\n"
+        );
+        assert!(go_snippet(&input("a`b", "", RegexFlags::default())).contains("regexp.MustCompile(\"a`b\")"));
+    }
+
+    #[test]
+    fn java_imports_what_it_uses_and_asks_for_unicode() {
+        assert_eq!(
+            java_snippet(&input(r"(?P<w>\w+)", "", RegexFlags::default())),
+            "import java.util.ArrayList;\n\
+             import java.util.List;\n\
+             import java.util.regex.Matcher;\n\
+             import java.util.regex.Pattern;\n\
+             \n\
+             private static final Pattern PATTERN = Pattern.compile(\"(?<w>\\\\w+)\", Pattern.UNICODE_CHARACTER_CLASS);\n\
+             \n\
+             List<String> matches = new ArrayList<>();\n\
+             Matcher matcher = PATTERN.matcher(text);\n\
+             while (matcher.find()) {\n    matches.add(matcher.group());\n}\n"
+        );
+        let flags = RegexFlags { case_insensitive: true, multi_line: true, ..RegexFlags::default() };
+        let code = java_snippet(&input(r"(?P<año>\d+)", r"${año}$1\$$", flags));
+        assert!(code.starts_with("// Aviso: el grupo 'año' no es un nombre válido en Java"));
+        assert!(!code.contains("java.util.List"));
+        assert!(code.contains(
+            "Pattern.compile(\"(?<año>\\\\d+)\", Pattern.UNICODE_CHARACTER_CLASS | Pattern.CASE_INSENSITIVE | Pattern.MULTILINE)"
+        ));
+        assert!(code.ends_with("replaceAll(\"${año}$1\\\\\\\\\\\\$\");\n"));
+    }
+
+    #[test]
+    fn rg_replace_globs_the_open_file_type() {
+        let export = ExportInput { source_path: Some(Path::new("logs/app.log")), ..input(r"it's\n", "$1", case_insensitive()) };
+        let code = rg_replace(&export);
+        assert!(code.starts_with("# rg solo muestra el resultado: no modifica los archivos.\n"));
+        assert!(code.ends_with("rg -U '(?i)it'\\''s\\n' --replace '$1' -g '*.log' .\n"));
+        assert!(rg_replace(&input("a", "b", RegexFlags::default())).ends_with("rg 'a' --replace 'b' -g '*' .\n"));
+    }
+
+    #[test]
+    fn powershell_picks_the_operator_by_case() {
+        let flags = RegexFlags { multi_line: true, ..RegexFlags::default() };
+        assert_eq!(
+            powershell_snippet(&input(r"(?P<n>it's)", "${n}$$1", flags)),
+            "$result = $text -creplace '(?m)(?<n>it''s)', '${n}$$1'\n"
+        );
+        assert_eq!(
+            powershell_snippet(&input(r"\d+", "", case_insensitive())),
+            "$found = [regex]::Matches($text, '\\d+', 'IgnoreCase') | ForEach-Object { $_.Value }\n"
+        );
+        assert!(powershell_snippet(&input("[a&&b]", "", RegexFlags::default())).starts_with("# Aviso: '&&'"));
+    }
+
+    #[test]
+    fn sql_keeps_ampersands_and_maps_word_boundaries() {
+        let flags = RegexFlags { case_insensitive: true, multi_line: true, ..RegexFlags::default() };
+        assert_eq!(
+            sql_snippet(&input(r"\b(\w+)'s\B\z", r"$1 & $0\", flags)),
+            "-- Aviso: MySQL anterior a 8.0.4 no usa ICU (sin \\d, lookaround ni flags en línea)\n\
+             \n\
+             -- PostgreSQL\n\
+             SELECT regexp_replace(columna, '\\y(\\w+)''s\\Y\\Z', '\\1 & \\&\\\\', 'gni') FROM tabla;\n\
+             \n\
+             -- MySQL 8\n\
+             SELECT REGEXP_REPLACE(columna, '\\\\b(\\\\w+)''s\\\\B\\\\z', '$1 & $0\\\\\\\\', 1, 0, 'im') FROM tabla;\n"
+        );
+        let code = sql_snippet(&input(r"(?P<y>\d{4})", "${y}", RegexFlags::default()));
+        assert!(code.starts_with("-- Aviso: '(?P<': PostgreSQL no admite grupos con nombre\n"));
+        assert!(code.contains("-- Aviso: PostgreSQL solo referencia \\1..\\9; se omitió $y\n"));
+        assert!(code.contains("SELECT regexp_replace(columna, '(?P<y>\\d{4})', '', 'gp') FROM tabla;"));
+    }
+}
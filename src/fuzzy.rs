@@ -0,0 +1,44 @@
+//! Coincidencia difusa por subsecuencia, al estilo de los selectores tipo Ctrl+P:
+//! todas las letras de la consulta deben aparecer en orden, y se premian las
+//! rachas consecutivas y los comienzos de palabra.
+
+/// Puntaje de `query` sobre `candidate` (sin distinguir mayúsculas), o `None` si
+/// no todas las letras de la consulta aparecen en orden. Mayor es mejor.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous_matched = false;
+    let mut previous_char: Option<char> = None;
+    for (position, c) in candidate.chars().enumerate() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        if next < query.len() && lower == query[next] {
+            score += 1;
+            if previous_matched {
+                score += 5;
+            }
+            if previous_char.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 8;
+            }
+            if position == 0 {
+                score += 3;
+            }
+            next += 1;
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous_char = Some(c);
+    }
+
+    // A igual coincidencia, preferir los candidatos más cortos.
+    (next == query.len()).then(|| score * 100 - candidate.chars().count() as i64)
+}
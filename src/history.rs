@@ -0,0 +1,96 @@
+//! Historial persistente de patrones aplicados, guardado como JSON Lines
+//! (una entrada por línea) en el directorio de datos de la plataforma.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::RegexFlags;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub flags: RegexFlags,
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    /// `None` deja el historial solo en memoria (sin directorio de datos).
+    path: Option<PathBuf>,
+}
+
+impl History {
+    pub fn default_path() -> Option<PathBuf> {
+        crate::paths::data_file("history.jsonl")
+    }
+
+    /// Lee el historial existente; las líneas corruptas se ignoran.
+    pub fn load() -> Result<History> {
+        let Some(path) = History::default_path() else {
+            return Ok(History::default());
+        };
+        if !path.exists() {
+            return Ok(History { entries: Vec::new(), path: Some(path) });
+        }
+
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("No se pudo leer {}", path.display()))?;
+        let entries = text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Ok(History { entries, path: Some(path) })
+    }
+
+    /// Registra un patrón aplicado, salvo que repita exactamente la última entrada.
+    pub fn record(&mut self, pattern: &str, flags: RegexFlags) -> Result<()> {
+        if pattern.is_empty() {
+            return Ok(());
+        }
+        if let Some(last) = self.entries.last()
+            && last.pattern == pattern
+            && last.flags == flags
+        {
+            return Ok(());
+        }
+
+        let entry = HistoryEntry {
+            timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            pattern: pattern.to_string(),
+            flags,
+        };
+
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut line = serde_json::to_string(&entry)?;
+            line.push('\n');
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(line.as_bytes()))
+                .with_context(|| format!("No se pudo escribir {}", path.display()))?;
+        }
+
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Entradas que contienen `query` (sin distinguir mayúsculas), de la más reciente
+    /// a la más antigua.
+    pub fn search(&self, query: &str) -> Vec<&HistoryEntry> {
+        let query = query.to_lowercase();
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.pattern.to_lowercase().contains(&query))
+            .collect()
+    }
+}
@@ -0,0 +1,1055 @@
+//! Textos fijos de la interfaz en español e inglés: nombres de modo, ayudas,
+//! títulos de los popups, la barra de estado y los avisos de cada operación. El
+//! idioma sale de `language` en la configuración o, si no está, de `LC_ALL`,
+//! `LC_MESSAGES` o `LANG`. Los nombres de los valores del núcleo (unidad de
+//! procesamiento, distribuciones, destinos de exportación, resúmenes de
+//! importación) también se traducen aquí; el núcleo no tiene textos de interfaz.
+
+use regex_wysiwyg::config::{Language, LayoutPreset, Split};
+use regex_wysiwyg::engine::ProcessingUnit;
+use regex_wysiwyg::export::{self, Target};
+use regex_wysiwyg::library::{LibrarySort, MergeReport, SyncReport};
+use std::fmt::Display;
+use std::path::Path;
+use regex_wysiwyg::assertions::SpanScore;
+use regex_wysiwyg::coverage::Coverage;
+use regex_wysiwyg::debugger::{Step, StepKind};
+use regex_wysiwyg::engine::MatchLocation;
+
+use crate::app::{InputMode, ModalKind, Question};
+use crate::keymap::Action;
+use crate::session::Drift;
+
+/// El idioma configurado o, en su defecto, el del entorno (inglés si empieza con
+/// `en`, español en cualquier otro caso).
+pub fn detect(configured: Option<Language>) -> Language {
+    if let Some(language) = configured {
+        return language;
+    }
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    if locale.starts_with("en") { Language::En } else { Language::Es }
+}
+
+fn pick(language: Language, es: &'static str, en: &'static str) -> &'static str {
+    match language {
+        Language::Es => es,
+        Language::En => en,
+    }
+}
+
+/// Textos sueltos de la interfaz.
+#[derive(Debug, Clone, Copy)]
+pub enum Text {
+    Ready,
+    SourceHint,
+    ReplaceHint,
+    CommitHint,
+    UnitHint,
+    Help,
+    History,
+    Library,
+    Save,
+    Session,
+    Quit,
+    Calculating,
+    InvalidPattern,
+    Matches,
+    Unit,
+    Engine,
+    EngineTitle,
+    Ai,
+    Querying,
+    Project,
+    Profile,
+    TruncatedTo,
+    WritesFullResult,
+    Zoomed,
+    Restores,
+    Key,
+    PatternName,
+    Description,
+    Tags,
+    Example,
+    OpenFile,
+    ExportLibraryTo,
+    ExportReportTo,
+    ImportLibraryFrom,
+    ImportRegex101,
+    ImportTests,
+    Conflicts,
+    Regressions,
+    GoldenDrift,
+    LoadCancelled,
+    KeepPartialPrompt,
+    Recovery,
+    RecoveryPrompt,
+    Current,
+    Previous,
+    VsPrevious,
+    NoMeasurements,
+    Branches,
+    Class,
+    Includes,
+    Excludes,
+    Stress,
+    Timings,
+    Compile,
+    Search,
+    Replace,
+    MatchesPerSec,
+    Cached,
+    Uses,
+    ExampleShort,
+    DoesNotCompile,
+    MatchesAtLine,
+    Column,
+    NoMatchesInSource,
+    NoMatches,
+    PatternPane,
+    ReplacePane,
+    SedMode,
+    SourcePane,
+    TestsPane,
+    OutputPane,
+    Debugger,
+    Sort,
+    Tag,
+    AllTags,
+    Preview,
+    Loading,
+    RecentFiles,
+    ExportTo,
+    Catalog,
+    Exercises,
+    NearMisses,
+    SearchLibrary,
+    Mode,
+    OpenFailed,
+    ExportFailed,
+    ImportFailed,
+    Overwrite,
+    NothingToCover,
+    OnlyWorkspace,
+    PatternFirst,
+    NoSlowInput,
+    Stressing,
+    NoFullExample,
+    NothingToDebug,
+    NoTextToStep,
+    LoadedFromHistory,
+    EmptyName,
+    NoProject,
+    NoSamples,
+    NoHint,
+    ExerciseLeft,
+    ConfigReloaded,
+    ImportCancelled,
+    ExportCancelled,
+    SessionRecovered,
+    NoSessionPath,
+    GoldenMatches,
+    NoSessionToRestore,
+    NoLog,
+    EmptyApiKey,
+    SuggestionApplied,
+}
+
+pub fn tr(language: Language, text: Text) -> &'static str {
+    let (es, en) = match text {
+        Text::Ready => ("Listo.", "Ready."),
+        Text::SourceHint => ("Fuente", "Source"),
+        Text::ReplaceHint => ("Reemplazar", "Replace"),
+        Text::CommitHint => ("Registrar", "Commit"),
+        Text::UnitHint => ("Unidad", "Unit"),
+        Text::Help => ("Ayuda", "Help"),
+        Text::History => ("Historial", "History"),
+        Text::Library => ("Biblioteca", "Library"),
+        Text::Save => ("Guardar", "Save"),
+        Text::Session => ("Sesión", "Session"),
+        Text::Quit => ("Salir", "Quit"),
+        Text::Calculating => ("calculando…", "computing…"),
+        Text::InvalidPattern => ("patrón inválido", "invalid pattern"),
+        Text::Matches => ("coincidencias", "matches"),
+        Text::Unit => ("unidad", "unit"),
+        Text::Engine => ("motor", "engine"),
+        Text::EngineTitle => ("Motor", "Engine"),
+        Text::Ai => ("IA", "AI"),
+        Text::Querying => ("consultando…", "querying…"),
+        Text::Project => ("PROYECTO", "PROJECT"),
+        Text::Profile => ("PERFIL", "PROFILE"),
+        Text::TruncatedTo => ("recortada a", "truncated to"),
+        Text::WritesFullResult => ("registra el resultado completo", "writes the full result"),
+        Text::Zoomed => ("ampliado", "zoomed"),
+        Text::Restores => ("restaura", "restores"),
+        Text::Key => ("Tecla", "Key"),
+        Text::PatternName => ("Nombre del patrón", "Pattern name"),
+        Text::Description => ("Descripción (opcional)", "Description (optional)"),
+        Text::Tags => ("Etiquetas, separadas por coma", "Tags, comma separated"),
+        Text::Example => ("Texto de ejemplo (opcional)", "Example text (optional)"),
+        Text::OpenFile => ("Abrir archivo", "Open file"),
+        Text::ExportLibraryTo => ("Exportar biblioteca a", "Export library to"),
+        Text::ExportReportTo => ("Exportar informe HTML a", "Export HTML report to"),
+        Text::ImportLibraryFrom => ("Importar biblioteca de", "Import library from"),
+        Text::ImportRegex101 => ("Importar de regex101", "Import from regex101"),
+        Text::ImportTests => ("Importar casos de", "Import test cases from"),
+        Text::Conflicts => ("Conflictos", "Conflicts"),
+        Text::Regressions => ("Casos que dejan de pasar", "Cases that stop passing"),
+        Text::GoldenDrift => ("La salida cambió", "The output changed"),
+        Text::LoadCancelled => ("Carga cancelada", "Load cancelled"),
+        Text::KeepPartialPrompt => (
+            "¿Conservar como texto fuente lo leído hasta ahora?",
+            "Keep what was read so far as the source text?",
+        ),
+        Text::Recovery => ("Recuperación", "Recovery"),
+        Text::RecoveryPrompt => (
+            "La sesión anterior no terminó bien. ¿Recuperar el autoguardado?",
+            "The previous session did not exit cleanly. Restore the autosave?",
+        ),
+        Text::Current => ("Actual", "Current"),
+        Text::Previous => ("Anterior", "Previous"),
+        Text::VsPrevious => ("Δ frente al anterior", "Δ vs previous"),
+        Text::NoMeasurements => ("Sin mediciones todavía", "No measurements yet"),
+        Text::Branches => ("Alternativas", "Branches"),
+        Text::Class => ("Clase", "Class"),
+        Text::Includes => ("incluye", "includes"),
+        Text::Excludes => ("excluye", "excludes"),
+        Text::Stress => ("Estrés", "Stress"),
+        Text::Timings => ("Tiempos", "Timings"),
+        Text::Compile => ("compilar", "compile"),
+        Text::Search => ("buscar", "search"),
+        Text::Replace => ("reemplazar", "replace"),
+        Text::MatchesPerSec => ("coincid./s", "matches/s"),
+        Text::Cached => ("en caché", "cached"),
+        Text::Uses => ("usos", "uses"),
+        Text::ExampleShort => ("ej", "e.g."),
+        Text::DoesNotCompile => ("No compila", "Does not compile"),
+        Text::MatchesAtLine => ("Coincide en la línea", "Matches at line"),
+        Text::Column => ("columna", "column"),
+        Text::NoMatchesInSource => ("Sin coincidencias en el texto fuente", "No matches in the source text"),
+        Text::NoMatches => ("(No hay coincidencias)", "(No matches)"),
+        Text::PatternPane => ("Patrón regex", "Regex Pattern"),
+        Text::ReplacePane => ("Reemplazar con", "Replace With"),
+        Text::SedMode => ("modo sed", "sed mode"),
+        Text::SourcePane => ("Texto fuente", "Source Text"),
+        Text::TestsPane => ("Casos de prueba", "Test Cases"),
+        Text::OutputPane => ("Vista previa de salida", "Output Preview"),
+        Text::Debugger => ("Depurador", "Debugger"),
+        Text::Sort => ("orden", "sort"),
+        Text::Tag => ("etiqueta", "tag"),
+        Text::AllTags => ("todas", "all"),
+        Text::Preview => ("Vista previa", "Preview"),
+        Text::Loading => ("Cargando", "Loading"),
+        Text::RecentFiles => ("Archivos recientes", "Recent files"),
+        Text::ExportTo => ("Exportar a", "Export to"),
+        Text::Catalog => ("Catálogo de patrones", "Pattern catalog"),
+        Text::Exercises => ("Ejercicios", "Exercises"),
+        Text::NearMisses => ("Casi coincidencias de", "Near misses of"),
+        Text::SearchLibrary => ("Buscar en la biblioteca", "Search the library"),
+        Text::Mode => ("modo", "mode"),
+        Text::OpenFailed => ("No se pudo abrir", "Could not open"),
+        Text::ExportFailed => ("Error al exportar", "Export failed"),
+        Text::ImportFailed => ("Error al importar", "Import failed"),
+        Text::Overwrite => ("Sobrescribir", "Overwrite"),
+        Text::NothingToCover => (
+            "El patrón no tiene alternativas ni partes opcionales que medir",
+            "The pattern has no alternatives or optional parts to measure",
+        ),
+        Text::OnlyWorkspace => ("Es el único espacio de trabajo", "This is the only workspace"),
+        Text::PatternFirst => ("Escribí un patrón primero", "Type a pattern first"),
+        Text::NoSlowInput => ("Ninguna entrada de estrés resultó lenta", "No stress input was slow"),
+        Text::Stressing => ("Midiendo el patrón con entradas de estrés...", "Measuring the pattern with stress inputs..."),
+        Text::NoFullExample => (
+            "No se encontró un ejemplo que el patrón acepte completo",
+            "No example fully matched by the pattern was found",
+        ),
+        Text::NothingToDebug => ("No hay patrón que depurar", "No pattern to debug"),
+        Text::NoTextToStep => ("No hay texto que recorrer", "No text to step through"),
+        Text::LoadedFromHistory => ("Patrón cargado del historial", "Pattern loaded from history"),
+        Text::EmptyName => ("Nombre vacío: no se guardó", "Empty name: not saved"),
+        Text::NoProject => (
+            "No hay proyecto abierto (--project <archivo.rwproj>)",
+            "No project is open (--project <file.rwproj>)",
+        ),
+        Text::NoSamples => ("El proyecto no tiene muestras", "The project has no samples"),
+        Text::NoHint => ("Este ejercicio no tiene pista", "This exercise has no hint"),
+        Text::ExerciseLeft => ("Ejercicio abandonado", "Exercise left"),
+        Text::ConfigReloaded => ("Configuración recargada", "Configuration reloaded"),
+        Text::ImportCancelled => ("Importación cancelada", "Import cancelled"),
+        Text::ExportCancelled => ("Exportación cancelada", "Export cancelled"),
+        Text::SessionRecovered => ("Sesión recuperada del autoguardado", "Session restored from the autosave"),
+        Text::NoSessionPath => ("No hay ruta para guardar la sesión", "No path to save the session to"),
+        Text::GoldenMatches => ("La salida es idéntica a la de referencia", "The output matches the golden result"),
+        Text::NoSessionToRestore => ("No hay sesión para restaurar", "No session to restore"),
+        Text::NoLog => ("Sin archivo de log. Iniciar con --log <ruta>", "No log file. Start with --log <path>"),
+        Text::EmptyApiKey => ("API key vacía: no se guardó", "Empty API key: not saved"),
+        Text::SuggestionApplied => ("Sugerencia aplicada!", "Suggestion applied!"),
+    };
+    pick(language, es, en)
+}
+
+/// Avisos con datos de la operación que los produjo.
+pub enum Notice<'a> {
+    ProcessingUnit(&'a str),
+    Flags(&'a str),
+    Layout(&'a str),
+    Split(&'a str),
+    Workspace(usize, usize),
+    SlowInput(&'a str, f64),
+    InvalidPattern(&'a dyn Display),
+    CaseAdded(&'a str),
+    ExerciseSolved(usize, usize),
+    ProgressNotSaved(&'a dyn Display),
+    RegexError(&'a dyn Display),
+    DebugTruncated(usize),
+    HistoryNotSaved(&'a dyn Display),
+    NotOverwritten(&'a str),
+    Overwritten(&'a str),
+    SavedToLibrary(&'a str),
+    LibraryNotSaved(&'a dyn Display),
+    ProjectOpened { name: &'a str, samples: usize, patterns: usize, tests: usize },
+    Sample(&'a str),
+    AppendedToPattern(&'a str),
+    LoadedFromCatalog(&'a str),
+    Exercise(&'a str),
+    Copied(&'a str),
+    CopyFailed(&'a dyn Display),
+    LoadedFromLibrary(&'a str),
+    RemovedFromLibrary(&'a str),
+    AutosaveFailed(&'a dyn Display),
+    ConfigNotReloaded(&'a dyn Display),
+    RecoveryFailed(&'a dyn Display),
+    Opened(&'a Path),
+    RecentNotSaved(&'a dyn Display),
+    LoadStopped(&'a Path),
+    PartialDiscarded(&'a Path),
+    /// Ruta y KiB conservados.
+    PartialKept(&'a Path, usize),
+    LibraryExported(usize, &'a Path),
+    ReportSaved(&'a Path),
+    ImportedRegex101(&'a Path),
+    /// Avisos de la importación, ya unidos.
+    ImportedRegex101WithWarnings(&'a str),
+    CasesImported(usize, &'a Path),
+    Imported(&'a str),
+    SyncFailed(&'a dyn Display),
+    Synced(&'a str),
+    SessionSaved(&'a Path),
+    SessionNotSaved(&'a dyn Display),
+    GoldenRecorded(usize),
+    /// Tecla con la que se registra la salida de referencia.
+    NoGolden(&'a str),
+    SessionRestored(&'a Path),
+    SessionNotRestored(&'a dyn Display),
+    ResultLogged(&'a Path),
+    LogNotWritten(&'a dyn Display),
+    ApiKeyPrompt(&'a str),
+    ApiKeySaved(&'a str),
+    Error(&'a dyn Display),
+    QueryingAi(&'a str),
+    AiEmpty(&'a str),
+    AiRunFailed(&'a dyn Display),
+    /// Proveedor y comienzo de lo que escribió en stderr.
+    AiFailed(&'a str, &'a str),
+}
+
+pub fn notice(language: Language, notice: Notice) -> String {
+    let en = language == Language::En;
+    match notice {
+        Notice::ProcessingUnit(unit) if en => format!("Processing unit: {}", unit),
+        Notice::ProcessingUnit(unit) => format!("Unidad de procesamiento: {}", unit),
+        Notice::Flags(summary) => format!("Flags: {}", summary),
+        Notice::Layout(layout) if en => format!("Layout: {}", layout),
+        Notice::Layout(layout) => format!("Distribución: {}", layout),
+        Notice::Split(split) if en => format!("Source and output: {}", split),
+        Notice::Split(split) => format!("Fuente y salida: {}", split),
+        Notice::Workspace(index, total) if en => format!("Workspace {} of {}", index, total),
+        Notice::Workspace(index, total) => format!("Espacio de trabajo {} de {}", index, total),
+        Notice::SlowInput(name, speed) if en => format!("Slow input: {} ({:.1} MB/s)", name, speed),
+        Notice::SlowInput(name, speed) => format!("Entrada lenta: {} ({:.1} MB/s)", name, speed),
+        Notice::InvalidPattern(e) if en => format!("Invalid pattern: {}", e),
+        Notice::InvalidPattern(e) => format!("Patrón inválido: {}", e),
+        Notice::CaseAdded(case) if en => format!("Case added: {}", case),
+        Notice::CaseAdded(case) => format!("Caso agregado: {}", case),
+        Notice::ExerciseSolved(solved, total) if en => format!("Exercise solved! {} of {} done", solved, total),
+        Notice::ExerciseSolved(solved, total) => format!("¡Ejercicio resuelto! Llevás {} de {}", solved, total),
+        Notice::ProgressNotSaved(e) if en => format!("Could not save progress: {}", e),
+        Notice::ProgressNotSaved(e) => format!("No se pudo guardar el progreso: {}", e),
+        Notice::RegexError(e) if en => format!("Regex Error: {}", e),
+        Notice::RegexError(e) => format!("Error de regex: {}", e),
+        Notice::DebugTruncated(steps) if en => format!("Debugger: showing the first {} steps", steps),
+        Notice::DebugTruncated(steps) => format!("Depurador: se muestran los primeros {} pasos", steps),
+        Notice::HistoryNotSaved(e) if en => format!("Error saving history: {}", e),
+        Notice::HistoryNotSaved(e) => format!("Error al guardar historial: {}", e),
+        Notice::NotOverwritten(name) if en => format!("'{}' was not overwritten", name),
+        Notice::NotOverwritten(name) => format!("'{}' no se sobrescribió", name),
+        Notice::Overwritten(name) if en => format!("'{}' overwritten in the library", name),
+        Notice::Overwritten(name) => format!("'{}' sobrescrito en la biblioteca", name),
+        Notice::SavedToLibrary(name) if en => format!("'{}' saved to the library", name),
+        Notice::SavedToLibrary(name) => format!("'{}' guardado en la biblioteca", name),
+        Notice::LibraryNotSaved(e) if en => format!("Error saving library: {}", e),
+        Notice::LibraryNotSaved(e) => format!("Error al guardar biblioteca: {}", e),
+        Notice::ProjectOpened { name, samples, patterns, tests } if en => {
+            format!("Project '{}': {} samples, {} patterns, {} test cases", name, samples, patterns, tests)
+        }
+        Notice::ProjectOpened { name, samples, patterns, tests } => {
+            format!("Proyecto '{}': {} muestras, {} patrones, {} casos de prueba", name, samples, patterns, tests)
+        }
+        Notice::Sample(name) if en => format!("Sample: {}", name),
+        Notice::Sample(name) => format!("Muestra: {}", name),
+        Notice::AppendedToPattern(name) if en => format!("'{}' appended to the pattern", name),
+        Notice::AppendedToPattern(name) => format!("'{}' agregado al patrón", name),
+        Notice::LoadedFromCatalog(name) if en => format!("'{}' loaded from the catalog", name),
+        Notice::LoadedFromCatalog(name) => format!("'{}' cargado del catálogo", name),
+        Notice::Exercise(prompt) if en => format!("Exercise: {}", prompt),
+        Notice::Exercise(prompt) => format!("Ejercicio: {}", prompt),
+        Notice::Copied(target) if en => format!("{} snippet copied to the clipboard", target),
+        Notice::Copied(target) => format!("Fragmento {} copiado al portapapeles", target),
+        Notice::CopyFailed(e) if en => format!("Copy failed: {}", e),
+        Notice::CopyFailed(e) => format!("Error al copiar: {}", e),
+        Notice::LoadedFromLibrary(name) if en => format!("'{}' loaded from the library", name),
+        Notice::LoadedFromLibrary(name) => format!("'{}' cargado de la biblioteca", name),
+        Notice::RemovedFromLibrary(name) if en => format!("'{}' removed from the library", name),
+        Notice::RemovedFromLibrary(name) => format!("'{}' eliminado de la biblioteca", name),
+        Notice::AutosaveFailed(e) if en => format!("Autosave failed: {}", e),
+        Notice::AutosaveFailed(e) => format!("Error de autoguardado: {}", e),
+        Notice::ConfigNotReloaded(e) if en => format!("Configuration not reloaded: {:#}", e),
+        Notice::ConfigNotReloaded(e) => format!("Configuración no recargada: {:#}", e),
+        Notice::RecoveryFailed(e) if en => format!("Error restoring the autosave: {}", e),
+        Notice::RecoveryFailed(e) => format!("Error al recuperar autoguardado: {}", e),
+        Notice::Opened(path) if en => format!("Opened: {}", path.display()),
+        Notice::Opened(path) => format!("Abierto: {}", path.display()),
+        Notice::RecentNotSaved(e) if en => format!("Error saving recent files: {}", e),
+        Notice::RecentNotSaved(e) => format!("Error al guardar recientes: {}", e),
+        Notice::LoadStopped(path) if en => format!("Loading {} cancelled", path.display()),
+        Notice::LoadStopped(path) => format!("Carga de {} cancelada", path.display()),
+        Notice::PartialDiscarded(path) if en => format!("Partial load of {} discarded", path.display()),
+        Notice::PartialDiscarded(path) => format!("Carga de {} descartada", path.display()),
+        Notice::PartialKept(path, kib) if en => format!("Kept the start of {} ({} KiB)", path.display(), kib),
+        Notice::PartialKept(path, kib) => format!("Conservado el inicio de {} ({} KiB)", path.display(), kib),
+        Notice::LibraryExported(count, path) if en => format!("{} patterns exported to {}", count, path.display()),
+        Notice::LibraryExported(count, path) => format!("{} patrones exportados a {}", count, path.display()),
+        Notice::ReportSaved(path) if en => format!("Report saved to {}", path.display()),
+        Notice::ReportSaved(path) => format!("Informe guardado en {}", path.display()),
+        Notice::ImportedRegex101(path) if en => format!("Imported from regex101: {}", path.display()),
+        Notice::ImportedRegex101(path) => format!("Importado de regex101: {}", path.display()),
+        Notice::ImportedRegex101WithWarnings(warnings) if en => {
+            format!("Imported from regex101 with warnings: {}", warnings)
+        }
+        Notice::ImportedRegex101WithWarnings(warnings) => format!("Importado de regex101 con avisos: {}", warnings),
+        Notice::CasesImported(count, path) if en => format!("{} cases imported from {}", count, path.display()),
+        Notice::CasesImported(count, path) => format!("{} casos importados de {}", count, path.display()),
+        Notice::Imported(summary) if en => format!("Import: {}", summary),
+        Notice::Imported(summary) => format!("Importación: {}", summary),
+        Notice::SyncFailed(e) if en => format!("Sync failed: {:#}", e),
+        Notice::SyncFailed(e) => format!("Sincronización fallida: {:#}", e),
+        Notice::Synced(summary) if en => format!("Library synced: {}", summary),
+        Notice::Synced(summary) => format!("Biblioteca sincronizada: {}", summary),
+        Notice::SessionSaved(path) if en => format!("Session saved to {}", path.display()),
+        Notice::SessionSaved(path) => format!("Sesión guardada en {}", path.display()),
+        Notice::SessionNotSaved(e) if en => format!("Error saving the session: {}", e),
+        Notice::SessionNotSaved(e) => format!("Error al guardar sesión: {}", e),
+        Notice::GoldenRecorded(lines) if en => format!("Golden result recorded ({} lines)", lines),
+        Notice::GoldenRecorded(lines) => format!("Salida de referencia registrada ({} líneas)", lines),
+        Notice::NoGolden(key) if en => format!("No golden result: record it with '{}'", key),
+        Notice::NoGolden(key) => format!("No hay salida de referencia: registrala con '{}'", key),
+        Notice::SessionRestored(path) if en => format!("Session restored from {}", path.display()),
+        Notice::SessionRestored(path) => format!("Sesión restaurada de {}", path.display()),
+        Notice::SessionNotRestored(e) if en => format!("Error restoring the session: {}", e),
+        Notice::SessionNotRestored(e) => format!("Error al restaurar sesión: {}", e),
+        Notice::ResultLogged(path) if en => format!("Result logged to {}", path.display()),
+        Notice::ResultLogged(path) => format!("Resultado registrado en {}", path.display()),
+        Notice::LogNotWritten(e) if en => format!("Error writing the log: {}", e),
+        Notice::LogNotWritten(e) => format!("Error al escribir log: {}", e),
+        Notice::ApiKeyPrompt(provider) if en => format!("{} API key (stored in the system keyring)", provider),
+        Notice::ApiKeyPrompt(provider) => format!("API key de {} (se guarda en el llavero del sistema)", provider),
+        Notice::ApiKeySaved(provider) if en => format!("{} API key stored in the keyring", provider),
+        Notice::ApiKeySaved(provider) => format!("API key de {} guardada en el llavero", provider),
+        Notice::Error(e) => format!("Error: {:#}", e),
+        Notice::QueryingAi(provider) if en => format!("Asking {} AI...", provider),
+        Notice::QueryingAi(provider) => format!("Consultando a {} IA...", provider),
+        Notice::AiEmpty(provider) if en => format!("{} returned nothing.", provider),
+        Notice::AiEmpty(provider) => format!("{} devolvió vacío.", provider),
+        Notice::AiRunFailed(e) if en => format!("Could not run: {}", e),
+        Notice::AiRunFailed(e) => format!("Error de ejecución: {}", e),
+        Notice::AiFailed(provider, stderr) => format!("{} Error: {}", provider, stderr),
+    }
+}
+
+/// Una coincidencia descrita en una línea, para el modo de lector de pantalla.
+pub fn describe_match(language: Language, index: usize, total: usize, location: &MatchLocation) -> String {
+    match language {
+        Language::Es => format!(
+            "coincidencia {} de {} en la línea {}, columna {}: «{}»",
+            index, total, location.line, location.column, location.text
+        ),
+        Language::En => format!(
+            "match {} of {} at line {}, column {}: '{}'",
+            index, total, location.line, location.column, location.text
+        ),
+    }
+}
+
+/// Cierre de la lista cuando hay más coincidencias de las descritas.
+pub fn more_matches(language: Language, count: usize) -> String {
+    match language {
+        Language::Es => format!("… y {} coincidencias más", count),
+        Language::En => format!("… and {} more matches", count),
+    }
+}
+
+pub fn mode_name(language: Language, mode: &InputMode) -> &'static str {
+    let (es, en) = match mode {
+        InputMode::Normal => ("EXPLORAR", "BROWSE"),
+        InputMode::EditingSource => ("EDITANDO FUENTE", "EDITING SOURCE"),
+        InputMode::EditingRegex => ("EDITANDO REGEX", "EDITING REGEX"),
+        InputMode::EditingReplace => ("EDITANDO REEMPLAZO", "EDITING REPLACEMENT"),
+        InputMode::EditingTests => ("EDITANDO CASOS", "EDITING TEST CASES"),
+        InputMode::EditingCompare => ("EDITANDO PATRÓN B", "EDITING PATTERN B"),
+        InputMode::History => ("HISTORIAL", "HISTORY"),
+        InputMode::Library => ("BIBLIOTECA", "LIBRARY"),
+        InputMode::NamingPattern
+        | InputMode::DescribingPattern
+        | InputMode::TaggingPattern
+        | InputMode::ExemplifyingPattern => ("GUARDAR PATRÓN", "SAVE PATTERN"),
+        InputMode::OpeningFile => ("ABRIR ARCHIVO", "OPEN FILE"),
+        InputMode::Recent => ("RECIENTES", "RECENT"),
+        InputMode::ExportingLibrary => ("EXPORTAR BIBLIOTECA", "EXPORT LIBRARY"),
+        InputMode::ExportingReport => ("EXPORTAR INFORME", "EXPORT REPORT"),
+        InputMode::ImportingLibrary => ("IMPORTAR BIBLIOTECA", "IMPORT LIBRARY"),
+        InputMode::ImportingRegex101 => ("IMPORTAR REGEX101", "IMPORT REGEX101"),
+        InputMode::ImportingTests => ("IMPORTAR CASOS", "IMPORT TEST CASES"),
+        InputMode::Loading => ("CARGANDO", "LOADING"),
+        InputMode::Catalog => ("CATÁLOGO", "CATALOG"),
+        InputMode::Exercises => ("EJERCICIOS", "EXERCISES"),
+        InputMode::ExportingCode => ("EXPORTAR CÓDIGO", "EXPORT CODE"),
+        InputMode::EnteringApiKey => ("API KEY", "API KEY"),
+        InputMode::CommandPalette => ("PALETA", "PALETTE"),
+        InputMode::Help => ("AYUDA", "HELP"),
+        InputMode::Modal => ("DIÁLOGO", "DIALOG"),
+        InputMode::Tour => ("BIENVENIDA", "WELCOME"),
+        InputMode::Debugging => ("DEPURANDO", "DEBUGGING"),
+        InputMode::MarkingSpans => ("MARCANDO", "MARKING"),
+        InputMode::NearMisses => ("CASI COINCIDENCIAS", "NEAR MISSES"),
+    };
+    pick(language, es, en)
+}
+
+pub fn unit_name(language: Language, unit: ProcessingUnit) -> &'static str {
+    let (es, en) = match unit {
+        ProcessingUnit::WholeText => ("TEXTO COMPLETO", "WHOLE TEXT"),
+        ProcessingUnit::PerLine => ("POR LÍNEA", "PER LINE"),
+    };
+    pick(language, es, en)
+}
+
+pub fn split_name(language: Language, split: Split) -> &'static str {
+    let (es, en) = match split {
+        Split::Vertical => ("vertical", "stacked"),
+        Split::Horizontal => ("lado a lado", "side by side"),
+    };
+    pick(language, es, en)
+}
+
+pub fn layout_name(language: Language, layout: LayoutPreset) -> &'static str {
+    let (es, en) = match layout {
+        LayoutPreset::Balanced => ("equilibrada", "balanced"),
+        LayoutPreset::Editing => ("edición", "editing"),
+        LayoutPreset::Results => ("resultados", "results"),
+    };
+    pick(language, es, en)
+}
+
+pub fn sort_name(language: Language, sort: LibrarySort) -> &'static str {
+    let (es, en) = match sort {
+        LibrarySort::Recent => ("recientes", "recent"),
+        LibrarySort::Frequent => ("frecuentes", "frequent"),
+        LibrarySort::Name => ("nombre", "name"),
+    };
+    pick(language, es, en)
+}
+
+/// Nombre de un destino en el exportador de código.
+pub fn target_name(language: Language, target: Target) -> &'static str {
+    let (es, en) = match target {
+        Target::Rust => ("Rust (regex)", "Rust (regex)"),
+        Target::Python => ("Python (re)", "Python (re)"),
+        Target::JavaScript => ("JavaScript / TypeScript", "JavaScript / TypeScript"),
+        Target::Sed => ("sed -E", "sed -E"),
+        Target::Grep => ("grep / rg", "grep / rg"),
+        Target::RgReplace => ("rg --replace (varios archivos)", "rg --replace (several files)"),
+        Target::Regex101 => ("regex101", "regex101"),
+        Target::Awk => ("awk", "awk"),
+        Target::MarkdownTable => ("Tabla Markdown", "Markdown table"),
+        Target::RustTest => ("Test Rust (#[test])", "Rust test (#[test])"),
+        Target::VsCode => ("VS Code (buscar/reemplazar)", "VS Code (find/replace)"),
+        Target::Go => ("Go (regexp)", "Go (regexp)"),
+        Target::Java => ("Java (Pattern)", "Java (Pattern)"),
+        Target::PowerShell => ("PowerShell (-replace)", "PowerShell (-replace)"),
+        Target::Sql => ("SQL (PostgreSQL / MySQL)", "SQL (PostgreSQL / MySQL)"),
+        Target::Literal(export::Language::Rust) => ("Literal Rust", "Rust literal"),
+        Target::Literal(export::Language::C) => ("Literal C/C++", "C/C++ literal"),
+        Target::Literal(export::Language::Java) => ("Literal Java", "Java literal"),
+        Target::Literal(export::Language::Python) => ("Literal Python", "Python literal"),
+        Target::Literal(export::Language::Json) => ("Literal JSON", "JSON literal"),
+    };
+    pick(language, es, en)
+}
+
+/// Resumen de una importación a la biblioteca.
+pub fn merge_summary(language: Language, report: &MergeReport) -> String {
+    match language {
+        Language::Es => format!(
+            "{} nuevos, {} sobrescritos, {} renombrados, {} omitidos",
+            report.added, report.overwritten, report.renamed, report.skipped
+        ),
+        Language::En => format!(
+            "{} new, {} overwritten, {} renamed, {} skipped",
+            report.added, report.overwritten, report.renamed, report.skipped
+        ),
+    }
+}
+
+/// Resumen de una sincronización con la fuente compartida.
+pub fn sync_summary(language: Language, report: &SyncReport) -> String {
+    match language {
+        Language::Es => format!(
+            "{} nuevos, {} actualizados, {} solo locales",
+            report.added, report.updated, report.local_only
+        ),
+        Language::En => format!(
+            "{} new, {} updated, {} local only",
+            report.added, report.updated, report.local_only
+        ),
+    }
+}
+
+/// Teclas de los modos que no son EXPLORAR (cuyas teclas salen del mapa de teclas).
+pub fn mode_keys(language: Language, mode: &InputMode) -> &'static str {
+    let (es, en) = match mode {
+        InputMode::History => (
+            "Escribir: Buscar | ↑/↓: Navegar | Enter: Cargar | Esc: Cerrar",
+            "Type: Search | ↑/↓: Move | Enter: Load | Esc: Close",
+        ),
+        InputMode::Library => (
+            "↑/↓: Navegar | Enter: Cargar | o: Orden | t: Etiqueta | Supr: Eliminar | Esc: Cerrar",
+            "↑/↓: Move | Enter: Load | o: Sort | t: Tag | Del: Delete | Esc: Close",
+        ),
+        InputMode::NamingPattern | InputMode::DescribingPattern | InputMode::TaggingPattern => {
+            ("Enter: Siguiente | Esc: Cancelar", "Enter: Next | Esc: Cancel")
+        }
+        InputMode::ExemplifyingPattern => ("Enter: Guardar | Esc: Cancelar", "Enter: Save | Esc: Cancel"),
+        InputMode::OpeningFile => ("Enter: Abrir | Esc: Cancelar", "Enter: Open | Esc: Cancel"),
+        InputMode::Recent => ("↑/↓: Navegar | Enter: Abrir | Esc: Cerrar", "↑/↓: Move | Enter: Open | Esc: Close"),
+        InputMode::Loading => ("Esc: Cancelar la carga", "Esc: Cancel loading"),
+        InputMode::Catalog => (
+            "↑/↓: Navegar | Enter: Usar | a: Agregar como bloque | Esc: Cerrar",
+            "↑/↓: Move | Enter: Use | a: Append as a block | Esc: Close",
+        ),
+        InputMode::Exercises => (
+            "↑/↓: Navegar | ←/→: Lección | Enter: Empezar | h: Pista | Supr: Dejar el ejercicio | Esc: Cerrar",
+            "↑/↓: Move | ←/→: Lesson | Enter: Start | h: Hint | Del: Leave the exercise | Esc: Close",
+        ),
+        InputMode::ExportingCode => (
+            "↑/↓: Elegir destino | Enter: Copiar al portapapeles | Esc: Cerrar",
+            "↑/↓: Pick a target | Enter: Copy to clipboard | Esc: Close",
+        ),
+        InputMode::CommandPalette => (
+            "Escribir: Filtrar | ↑/↓: Navegar | Enter: Cargar | Esc: Cerrar",
+            "Type: Filter | ↑/↓: Move | Enter: Load | Esc: Close",
+        ),
+        InputMode::Help => ("↑/↓: Desplazar | Esc: Cerrar", "↑/↓: Scroll | Esc: Close"),
+        InputMode::EnteringApiKey => ("Enter: Guardar | Esc: Cancelar", "Enter: Save | Esc: Cancel"),
+        InputMode::ExportingLibrary | InputMode::ExportingReport => {
+            ("Enter: Exportar | Esc: Cancelar", "Enter: Export | Esc: Cancel")
+        }
+        InputMode::ImportingLibrary | InputMode::ImportingRegex101 | InputMode::ImportingTests => {
+            ("Enter: Importar | Esc: Cancelar", "Enter: Import | Esc: Cancel")
+        }
+        InputMode::Modal => ("Responder en la ventana", "Answer the dialog"),
+        InputMode::NearMisses => (
+            "↑/↓: Navegar | +: Agregar como caso que coincide | -: Agregar como caso que no | Esc: Cerrar",
+            "↑/↓: Move | +: Add as a matching case | -: Add as a non-matching case | Esc: Close",
+        ),
+        InputMode::MarkingSpans => (
+            "←/→/↑/↓: Mover | Enter: Empezar/cerrar tramo | Supr: Quitar tramo | c: Quitar todos | Esc: Salir",
+            "←/→/↑/↓: Move | Enter: Start/close span | Del: Remove span | c: Remove all | Esc: Exit",
+        ),
+        InputMode::Debugging => (
+            "↓/Enter: Paso siguiente | ↑: Anterior | n/N: ±100 | p: Reproducir/pausar | Esc: Salir",
+            "↓/Enter: Next step | ↑: Previous | n/N: ±100 | p: Play/pause | Esc: Exit",
+        ),
+        InputMode::Tour => (
+            "Enter: Siguiente | Retroceso: Anterior | Esc: Cerrar",
+            "Enter: Next | Backspace: Back | Esc: Close",
+        ),
+        InputMode::EditingCompare => (
+            "Escribir: Patrón B, comparado con el principal | Vacío: Sin comparación | Esc: Terminar",
+            "Type: Pattern B, compared with the main one | Empty: No comparison | Esc: Finish",
+        ),
+        InputMode::EditingTests => (
+            "'+ texto': Debe coincidir | '- texto': No debe | '-> a | b': Capturas | Esc: Terminar",
+            "'+ text': Must match | '- text': Must not | '-> a | b': Captures | Esc: Finish",
+        ),
+        InputMode::Normal | InputMode::EditingSource | InputMode::EditingRegex | InputMode::EditingReplace => {
+            ("Esc: Confirmar edición", "Esc: Finish editing")
+        }
+    };
+    pick(language, es, en)
+}
+
+/// Título y texto de cada paso del recorrido de bienvenida (`app::TOUR`).
+pub fn tour(language: Language) -> [(&'static str, &'static str); 6] {
+    let steps = [
+        (
+            ("Bienvenida", "Welcome"),
+            (
+                "regex-wysiwyg muestra en vivo qué hace una expresión regular sobre un texto. \
+                 Este recorrido presenta los paneles en cinco pasos.",
+                "regex-wysiwyg shows live what a regular expression does to a text. \
+                 This tour walks through the panes in five steps.",
+            ),
+        ),
+        (
+            ("1. Texto fuente", "1. Source text"),
+            (
+                "Primero, el texto sobre el que se busca: se escribe o se pega aquí, \
+                 o se abre un archivo.",
+                "First, the text to search: type or paste it here, or open a file.",
+            ),
+        ),
+        (
+            ("2. Patrón", "2. Pattern"),
+            (
+                "Después, la expresión regular. La salida se recalcula mientras se escribe, \
+                 y los paréntesis se colorean según su anidamiento.",
+                "Then the regular expression. The output updates as you type, \
+                 and brackets are colored by nesting.",
+            ),
+        ),
+        (
+            ("3. Reemplazo", "3. Replacement"),
+            (
+                "Opcional. Vacío, la salida lista lo que coincide; con un reemplazo \
+                 ($1 para el primer grupo) muestra el texto transformado.",
+                "Optional. When empty, the output lists what matched; with a replacement \
+                 ($1 for the first group) it shows the transformed text.",
+            ),
+        ),
+        (
+            ("4. Salida", "4. Output"),
+            (
+                "El resultado, al instante. Se puede ampliar a pantalla completa \
+                 para leer salidas largas.",
+                "The result, instantly. It can be zoomed to full screen to read long outputs.",
+            ),
+        ),
+        (
+            ("5. Estado y ayuda", "5. Status and help"),
+            (
+                "Abajo, el modo actual, los flags y la cantidad de coincidencias. \
+                 La ayuda lista todos los atajos.",
+                "At the bottom, the current mode, the flags and the match count. \
+                 Help lists every shortcut.",
+            ),
+        ),
+    ];
+    steps.map(|((title_es, title_en), (body_es, body_en))| {
+        (pick(language, title_es, title_en), pick(language, body_es, body_en))
+    })
+}
+
+/// Resumen de la comparación entre el patrón principal (A) y el B.
+pub fn comparison(language: Language, score: &SpanScore) -> String {
+    let (both, only_a, only_b) = (score.correct.len(), score.missed.len(), score.extra.len());
+    let same = only_a == 0 && only_b == 0;
+    match language {
+        Language::Es => format!(
+            "ambos {} | solo A {} | solo B {}{}",
+            both,
+            only_a,
+            only_b,
+            if same { " | equivalentes en este texto" } else { "" }
+        ),
+        Language::En => format!(
+            "both {} | only A {} | only B {}{}",
+            both,
+            only_a,
+            only_b,
+            if same { " | equivalent on this text" } else { "" }
+        ),
+    }
+}
+
+/// Resumen de la comparación entre los tramos marcados y los encontrados.
+/// Cambio relativo de un tiempo respecto del patrón anterior; por debajo de
+/// `noise` (en las dos direcciones) se considera igual.
+pub fn timing_change(language: Language, change: f64, noise: f64) -> String {
+    let percent = change.abs() * 100.0;
+    if change.abs() < noise {
+        return pick(language, "≈ igual", "≈ same").to_string();
+    }
+    match (language, change > 0.0) {
+        (Language::Es, true) => format!("+{:.0}% más lento", percent),
+        (Language::Es, false) => format!("−{:.0}% más rápido", percent),
+        (Language::En, true) => format!("+{:.0}% slower", percent),
+        (Language::En, false) => format!("−{:.0}% faster", percent),
+    }
+}
+
+/// Tamaño de una clase de caracteres.
+pub fn class_size(language: Language, count: u32, ranges: usize) -> String {
+    match language {
+        Language::Es => format!("{} caracteres en {} rangos", count, ranges),
+        Language::En => format!("{} characters in {} ranges", count, ranges),
+    }
+}
+
+/// Resumen de la cobertura del patrón: partes usadas sobre medidas.
+pub fn coverage(language: Language, coverage: &Coverage) -> String {
+    let used = coverage.used.iter().filter(|&&used| used).count();
+    let unused = coverage.unused().len();
+    match language {
+        Language::Es => format!("cobertura {}/{} | sin usar {}", used, coverage.parts.len(), unused),
+        Language::En => format!("coverage {}/{} | unused {}", used, coverage.parts.len(), unused),
+    }
+}
+
+pub fn span_score(language: Language, score: &SpanScore) -> String {
+    let percent = |ratio: Option<f64>| ratio.map_or("—".to_string(), |ratio| format!("{:.0}%", ratio * 100.0));
+    let (correct, missed, extra) = (score.correct.len(), score.missed.len(), score.extra.len());
+    match language {
+        Language::Es => format!(
+            "correctos {} | faltan {} | de más {} | precisión {} | exhaustividad {}",
+            correct,
+            missed,
+            extra,
+            percent(score.precision()),
+            percent(score.recall())
+        ),
+        Language::En => format!(
+            "correct {} | missed {} | extra {} | precision {} | recall {}",
+            correct,
+            missed,
+            extra,
+            percent(score.precision()),
+            percent(score.recall())
+        ),
+    }
+}
+
+/// Qué pasa en un paso del depurador, en la posición `position` (línea:columna).
+/// `group_name` da el nombre de un grupo de captura, si lo tiene.
+pub fn describe_step(
+    language: Language,
+    step: &Step,
+    position: &str,
+    group_name: impl Fn(usize) -> Option<String>,
+) -> String {
+    let group = |index: usize| match group_name(index) {
+        Some(name) => format!("{} ({})", index, name),
+        None => index.to_string(),
+    };
+    match (&step.kind, language) {
+        (StepKind::NoMatch, Language::Es) => format!("{}: ninguna coincidencia empieza aquí", position),
+        (StepKind::NoMatch, Language::En) => format!("{}: no match starts here", position),
+        (StepKind::Start, Language::Es) => format!("{}: empieza una coincidencia", position),
+        (StepKind::Start, Language::En) => format!("{}: a match starts", position),
+        (StepKind::Enter(index), Language::Es) => format!("{}: entra al grupo {}", position, group(*index)),
+        (StepKind::Enter(index), Language::En) => format!("{}: enters group {}", position, group(*index)),
+        (StepKind::Exit(index), Language::Es) => format!("{}: sale del grupo {}", position, group(*index)),
+        (StepKind::Exit(index), Language::En) => format!("{}: leaves group {}", position, group(*index)),
+        (StepKind::Match(range), Language::Es) => {
+            format!("{}: coincidencia completa ({} bytes)", position, range.len())
+        }
+        (StepKind::Match(range), Language::En) => format!("{}: full match ({} bytes)", position, range.len()),
+    }
+}
+
+/// Teclas de una ventana modal: las respuestas de su pregunta o cómo cerrarla.
+pub fn modal_keys(language: Language, kind: &ModalKind) -> &'static str {
+    let (es, en) = match kind {
+        ModalKind::Error => ("Enter/Esc: Cerrar", "Enter/Esc: Close"),
+        ModalKind::Question(Question::RestoreAutosave) => ("s: Recuperar | n: Descartar", "s: Restore | n: Discard"),
+        ModalKind::Question(Question::KeepPartial) => ("s: Conservar | n: Descartar", "s: Keep | n: Discard"),
+        ModalKind::Question(Question::ImportConflicts) => (
+            "c: Conservar existentes | s: Sobrescribir | r: Renombrar importados | Esc: Cancelar",
+            "c: Keep existing | s: Overwrite | r: Rename imported | Esc: Cancel",
+        ),
+        ModalKind::Question(Question::LibraryRegressions) => {
+            ("s: Sobrescribir igual | n/Enter: Cancelar", "s: Overwrite anyway | n/Enter: Cancel")
+        }
+        ModalKind::Question(Question::OverwriteLibrary | Question::OverwriteReport) => {
+            ("s: Sobrescribir | n: Cancelar", "s: Overwrite | n: Cancel")
+        }
+    };
+    pick(language, es, en)
+}
+
+pub fn overwrite_prompt(language: Language, path: &Path) -> String {
+    match language {
+        Language::Es => format!("{} ya existe. ¿Sobrescribirlo?", path.display()),
+        Language::En => format!("{} already exists. Overwrite it?", path.display()),
+    }
+}
+
+/// Casos mostrados en el aviso de casos que dejan de pasar; del resto solo se da la cantidad.
+const REGRESSIONS_SHOWN: usize = 5;
+
+/// Aviso antes de sobrescribir `name` con un patrón que rompe casos que pasaban,
+/// dados como (texto del caso, motivo).
+pub fn regressions_prompt(language: Language, name: &str, regressions: &[(String, String)]) -> String {
+    let mut message = match language {
+        Language::Es => format!("Con el nuevo patrón, {} casos guardados con '{}' dejan de pasar:", regressions.len(), name),
+        Language::En => format!("With the new pattern, {} cases saved with '{}' stop passing:", regressions.len(), name),
+    };
+    for (case, reason) in regressions.iter().take(REGRESSIONS_SHOWN) {
+        message.push_str(&format!("\n{}  ({})", case, reason));
+    }
+    if regressions.len() > REGRESSIONS_SHOWN {
+        message.push_str(&match language {
+            Language::Es => format!("\n... y {} más", regressions.len() - REGRESSIONS_SHOWN),
+            Language::En => format!("\n... and {} more", regressions.len() - REGRESSIONS_SHOWN),
+        });
+    }
+    message.push_str(pick(language, "\n¿Sobrescribir igual?", "\nOverwrite anyway?"));
+    message
+}
+
+/// Dónde difiere la salida de la de referencia, con la línea de cada una.
+pub fn golden_drift(language: Language, drift: &Drift) -> String {
+    let line = |text: &Option<String>| match (text, language) {
+        (Some(text), _) => format!("{:?}", text),
+        (None, Language::Es) => "(fin de la salida)".to_string(),
+        (None, Language::En) => "(end of output)".to_string(),
+    };
+    match language {
+        Language::Es => format!(
+            "Difiere de la referencia en la línea {}.\nEsperado: {}\nObtenido: {}",
+            drift.line,
+            line(&drift.expected),
+            line(&drift.actual)
+        ),
+        Language::En => format!(
+            "Differs from the golden result at line {}.\nExpected: {}\nActual:   {}",
+            drift.line,
+            line(&drift.expected),
+            line(&drift.actual)
+        ),
+    }
+}
+
+pub fn conflicts_prompt(language: Language, conflicts: usize) -> String {
+    match language {
+        Language::Es => format!("{} patrones importados tienen un nombre que ya está en la biblioteca.", conflicts),
+        Language::En => format!("{} imported patterns have a name already in the library.", conflicts),
+    }
+}
+
+/// Qué hace cada acción, para la ayuda en pantalla.
+pub fn action_description(language: Language, action: Action) -> &'static str {
+    let (es, en) = match action {
+        Action::Quit => ("Salir", "Quit"),
+        Action::EditSource => ("Escribir el texto fuente (lo vacía)", "Type the source text (clears it)"),
+        Action::EditRegex => ("Escribir el patrón", "Type the pattern"),
+        Action::EditReplace => ("Escribir el reemplazo (modo sed)", "Type the replacement (sed mode)"),
+        Action::EditCompare => ("Editar el patrón B para comparar", "Edit pattern B to compare"),
+        Action::Debug => ("Depurar el patrón paso a paso", "Step through the pattern"),
+        Action::MarkSpans => ("Marcar lo que el patrón debería encontrar", "Mark what the pattern should match"),
+        Action::NearMisses => ("Textos que casi coinciden con el patrón", "Strings that almost match the pattern"),
+        Action::StressTest => ("Medir el patrón con entradas de estrés", "Time the pattern on stress inputs"),
+        Action::EditTests => ("Editar los casos de prueba del patrón", "Edit the pattern's test cases"),
+        Action::SuggestAi => ("Pedir un patrón a la IA", "Ask the AI for a pattern"),
+        Action::CommitResult => ("Registrar el resultado en el log (--log)", "Append the result to the log (--log)"),
+        Action::ToggleUnit => ("Alternar texto completo / por línea", "Toggle whole text / per line"),
+        Action::ToggleCaseInsensitive => ("Flag i: ignorar mayúsculas", "Flag i: ignore case"),
+        Action::ToggleMultiLine => ("Flag m: ^ y $ por línea", "Flag m: ^ and $ per line"),
+        Action::ToggleDotAll => ("Flag s: el punto incluye saltos de línea", "Flag s: dot matches newlines"),
+        Action::ToggleIgnoreWhitespace => ("Flag x: ignorar espacios del patrón", "Flag x: ignore pattern whitespace"),
+        Action::OpenHistory => ("Historial de patrones", "Pattern history"),
+        Action::OpenLibrary => ("Biblioteca de patrones", "Pattern library"),
+        Action::SavePattern => ("Guardar el patrón en la biblioteca", "Save the pattern to the library"),
+        Action::SaveSession => ("Guardar la sesión", "Save the session"),
+        Action::LoadSession => ("Cargar la sesión", "Load the session"),
+        Action::RecordGolden => {
+            ("Registrar la salida como referencia de la sesión", "Record the output as the session's golden result")
+        }
+        Action::CheckGolden => {
+            ("Comprobar que la salida sigue igual a la de referencia", "Check the output still matches the golden result")
+        }
+        Action::NextSample => ("Siguiente texto de ejemplo", "Next sample text"),
+        Action::OpenFile => ("Abrir un archivo como texto fuente", "Open a file as the source text"),
+        Action::OpenRecent => ("Archivos recientes", "Recent files"),
+        Action::ExportLibrary => ("Exportar la biblioteca", "Export the library"),
+        Action::ImportLibrary => ("Importar una biblioteca", "Import a library"),
+        Action::ImportRegex101 => ("Importar de regex101", "Import from regex101"),
+        Action::ImportTests => ("Importar un corpus de casos de prueba", "Import a test case corpus"),
+        Action::OpenCatalog => ("Catálogo de construcciones", "Construct catalog"),
+        Action::OpenExercises => ("Ejercicios para aprender regex", "Exercises to learn regex"),
+        Action::ExportCode => ("Copiar el patrón como código", "Copy the pattern as code"),
+        Action::ExportReport => ("Exportar un informe HTML", "Export an HTML report"),
+        Action::SetApiKey => ("Configurar la API key de la IA", "Set the AI API key"),
+        Action::CommandPalette => ("Buscar en la biblioteca", "Search the library"),
+        Action::ScrollSourceUp => ("Subir el texto fuente", "Scroll the source up"),
+        Action::ScrollSourceDown => ("Bajar el texto fuente", "Scroll the source down"),
+        Action::ScrollOutputUp => ("Subir la salida", "Scroll the output up"),
+        Action::ScrollOutputDown => ("Bajar la salida", "Scroll the output down"),
+        Action::ToggleStats => ("Panel de tiempos y diagnóstico", "Timings and diagnostics pane"),
+        Action::ToggleCoverage => {
+            ("Resaltar las partes del patrón que nunca coinciden", "Highlight pattern parts that never match")
+        }
+        Action::CycleLayout => ("Cambiar la distribución de paneles", "Cycle the pane layout"),
+        Action::GrowOutput => ("Agrandar la salida", "Grow the output"),
+        Action::ShrinkOutput => ("Achicar la salida", "Shrink the output"),
+        Action::ToggleZoom => ("Ampliar el panel activo", "Zoom the focused pane"),
+        Action::ShowHelp => ("Esta ayuda", "This help"),
+        Action::NewWorkspace => ("Nuevo espacio de trabajo", "New workspace"),
+        Action::CloseWorkspace => ("Cerrar el espacio de trabajo", "Close the workspace"),
+        Action::NextWorkspace => ("Siguiente espacio de trabajo (1-9: ir a uno)", "Next workspace (1-9: jump to one)"),
+        Action::PreviousWorkspace => ("Espacio de trabajo anterior", "Previous workspace"),
+        Action::ToggleSplit => ("Fuente y salida apiladas / lado a lado", "Source and output stacked / side by side"),
+    };
+    pick(language, es, en)
+}
+
+/// Secciones de la ayuda para los modos que no son EXPLORAR.
+pub fn help_sections(language: Language) -> [(&'static str, &'static str); 9] {
+    let sections = [
+        (
+            ("Edición de fuente, regex y reemplazo", "Editing source, regex and replacement"),
+            ("Escribir | Backspace: Borrar | Esc: Terminar", "Type | Backspace: Delete | Esc: Finish"),
+        ),
+        (("Historial", "History"), ("Escribir: Buscar | ↑/↓ | Enter: Cargar | Esc", "Type: Search | ↑/↓ | Enter: Load | Esc")),
+        (
+            ("Biblioteca", "Library"),
+            ("↑/↓ | Enter: Cargar | o: Orden | t: Etiqueta | Supr: Eliminar | Esc", "↑/↓ | Enter: Load | o: Sort | t: Tag | Del: Delete | Esc"),
+        ),
+        (("Paleta", "Palette"), ("Escribir: Filtrar | ↑/↓ | Enter: Cargar | Esc", "Type: Filter | ↑/↓ | Enter: Load | Esc")),
+        (
+            ("Catálogo", "Catalog"),
+            ("↑/↓ | Enter: Usar | a: Agregar como bloque | Esc", "↑/↓ | Enter: Use | a: Append as a block | Esc"),
+        ),
+        (
+            ("Ejercicios", "Exercises"),
+            (
+                "↑/↓ | ←/→: Lección | Enter: Empezar | h: Pista | Supr: Dejar | Esc",
+                "↑/↓ | ←/→: Lesson | Enter: Start | h: Hint | Del: Leave | Esc",
+            ),
+        ),
+        (
+            ("Exportar código", "Export code"),
+            ("↑/↓: Elegir destino | Enter: Copiar | Esc", "↑/↓: Pick a target | Enter: Copy | Esc"),
+        ),
+        (("Rutas y nombres", "Paths and names"), ("Escribir | Enter: Confirmar | Esc: Cancelar", "Type | Enter: Confirm | Esc: Cancel")),
+        (
+            ("Carga de archivo", "File loading"),
+            ("Esc: Cancelar (luego s/n: conservar lo leído)", "Esc: Cancel (then s/n: keep what was read)"),
+        ),
+    ];
+    sections.map(|((title_es, title_en), (keys_es, keys_en))| {
+        (pick(language, title_es, title_en), pick(language, keys_es, keys_en))
+    })
+}
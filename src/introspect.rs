@@ -0,0 +1,117 @@
+//! Lo que el motor sabe de un patrón antes de buscar: grupos de captura, largos
+//! posibles, si hay literales con los que prefiltrar y cuánto ocupa compilado.
+//! Sirve para entender por qué una formulación es más rápida que otra.
+
+use regex_syntax::hir::literal::Extractor;
+use regex_syntax::hir::{Hir, Look};
+
+use crate::EngineOptions;
+
+/// Granularidad con la que se estima el tamaño compilado.
+const SIZE_STEP: usize = 1024;
+
+pub struct Introspection {
+    /// Grupos de captura explícitos (sin contar el grupo 0).
+    pub captures: usize,
+    /// `Some` si toda coincidencia captura siempre la misma cantidad de grupos.
+    pub static_captures: Option<usize>,
+    pub min_len: Option<usize>,
+    /// `None` si la coincidencia puede ser arbitrariamente larga.
+    pub max_len: Option<usize>,
+    /// Literales con los que empieza toda coincidencia, si son pocos y conocidos:
+    /// el motor los busca primero y solo corre la regex completa donde aparecen.
+    pub prefixes: Option<Vec<String>>,
+    /// Los prefijos son la coincidencia completa: basta con buscarlos.
+    pub prefixes_exact: bool,
+    pub anchored_start: bool,
+    /// Tamaño aproximado del programa compilado, en bytes.
+    pub compiled_size: usize,
+}
+
+pub fn introspect(pattern: &str, options: &EngineOptions) -> Result<Introspection, String> {
+    let hir = regex_syntax::ParserBuilder::new()
+        .case_insensitive(options.flags.case_insensitive)
+        .multi_line(options.flags.multi_line)
+        .dot_matches_new_line(options.flags.dot_matches_new_line)
+        .ignore_whitespace(options.flags.ignore_whitespace)
+        .build()
+        .parse(pattern)
+        .map_err(|e| e.to_string())?;
+    let compiled_size = compiled_size(pattern, options).map_err(|e| e.to_string())?;
+    let properties = hir.properties();
+    let (prefixes, prefixes_exact) = prefixes(&hir);
+    Ok(Introspection {
+        captures: properties.explicit_captures_len(),
+        static_captures: properties.static_explicit_captures_len(),
+        min_len: properties.minimum_len(),
+        max_len: properties.maximum_len(),
+        prefixes,
+        prefixes_exact,
+        anchored_start: properties.look_set_prefix().contains(Look::Start),
+        compiled_size,
+    })
+}
+
+/// Los prefijos que elegiría el motor para su prefiltro; `None` si no hay un
+/// conjunto finito o alguno es vacío (no filtraría nada).
+fn prefixes(hir: &Hir) -> (Option<Vec<String>>, bool) {
+    let mut seq = Extractor::new().extract(hir);
+    seq.optimize_for_prefix_by_preference();
+    let Some(literals) = seq.literals() else {
+        return (None, false);
+    };
+    if literals.is_empty() || literals.iter().any(|literal| literal.as_bytes().is_empty()) {
+        return (None, false);
+    }
+    let exact = seq.is_exact();
+    let texts = literals.iter().map(|literal| String::from_utf8_lossy(literal.as_bytes()).into_owned()).collect();
+    (Some(texts), exact)
+}
+
+/// El crate regex no informa el tamaño de lo que compila, pero sí rechaza lo que
+/// excede `size_limit`: se busca el menor límite con el que el patrón compila.
+fn compiled_size(pattern: &str, options: &EngineOptions) -> Result<usize, regex::Error> {
+    let fits = |limit: usize| crate::compile(pattern, &EngineOptions { size_limit: limit, ..*options }).is_ok();
+    crate::compile(pattern, options)?;
+    let (mut low, mut high) = (0, options.size_limit.div_ceil(SIZE_STEP));
+    while low < high {
+        let middle = (low + high) / 2;
+        if fits(middle * SIZE_STEP) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    Ok(high * SIZE_STEP)
+}
+
+impl Introspection {
+    /// Resumen para mostrar, una línea por dato.
+    pub fn lines(&self) -> Vec<String> {
+        let captures = match self.static_captures {
+            Some(_) => format!("grupos: {}", self.captures),
+            None => format!("grupos: {} (no todos participan siempre)", self.captures),
+        };
+        let length = match (self.min_len, self.max_len) {
+            (Some(min), Some(max)) if min == max => format!("largo: {} bytes", min),
+            (Some(min), Some(max)) => format!("largo: {}–{} bytes", min, max),
+            (Some(min), None) => format!("largo: {} bytes o más", min),
+            _ => "largo: nunca coincide".to_string(),
+        };
+        let prefilter = match &self.prefixes {
+            Some(prefixes) => {
+                let shown: Vec<String> = prefixes.iter().take(3).map(|prefix| format!("{:?}", prefix)).collect();
+                let more = if prefixes.len() > 3 { format!(" y {} más", prefixes.len() - 3) } else { String::new() };
+                let exact = if self.prefixes_exact { " (exactos)" } else { "" };
+                format!("prefiltro: {}{}{}", shown.join(", "), more, exact)
+            }
+            None => "prefiltro: no".to_string(),
+        };
+        let mut lines = vec![captures, length, prefilter];
+        if self.anchored_start {
+            lines.push("anclado al inicio del texto".to_string());
+        }
+        lines.push(format!("tamaño compilado: ~{} KiB", self.compiled_size / 1024));
+        lines
+    }
+}
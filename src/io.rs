@@ -0,0 +1,398 @@
+//! Entrada y salida de la aplicación: archivos fuente, sesiones, log de
+//! resultados, importación y exportación de la biblioteca y sincronización.
+
+use anyhow::Result;
+use crossterm::clipboard::CopyToClipboard;
+use crossterm::execute;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use ropey::Rope;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+
+use regex_wysiwyg::library::{ConflictPolicy, Library, LibraryEntry};
+use regex_wysiwyg::{WriteSink, cases, compile, regex101, report, sync, transform_into};
+
+use crate::app::{App, Background, InputMode, Loading, Question};
+use crate::i18n::{self, Notice, Text, tr};
+use crate::keymap::Action;
+use crate::session::Session;
+
+impl App {
+    /// Lee `path` en segundo plano, informando el progreso; al terminar pasa a
+    /// ser el texto fuente y se registra en la lista de recientes.
+    pub fn open_file(&mut self, path: PathBuf) {
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.loading = Some(Loading { path: path.clone(), read: 0, total: 0, cancel: cancel.clone() });
+        self.input_mode = InputMode::Loading;
+        let tasks = self.tasks.clone();
+        tokio::spawn(async move {
+            let finished = read_with_progress(path, &tasks, &cancel).await;
+            let _ = tasks.send(finished);
+        });
+    }
+
+    /// Pide detener la carga en curso; el resultado parcial llega después.
+    pub fn cancel_load(&mut self) {
+        match &self.loading {
+            Some(loading) => loading.cancel.store(true, Ordering::Relaxed),
+            None => self.input_mode = InputMode::Normal,
+        }
+    }
+
+    pub fn file_loaded(&mut self, path: PathBuf, text: io::Result<String>) {
+        self.loading = None;
+        if self.input_mode == InputMode::Loading {
+            self.input_mode = InputMode::Normal;
+        }
+        match text {
+            Ok(text) => {
+                tracing::info!(path = %path.display(), bytes = text.len(), "archivo abierto");
+                self.source_text = Rope::from(text);
+                self.source_scroll = 0;
+                self.notify(i18n::notice(self.language, Notice::Opened(&path)));
+                if let Err(e) = self.recent.touch(&path) {
+                    self.notify_error(i18n::notice(self.language, Notice::RecentNotSaved(&e)));
+                }
+                self.source_path = Some(path);
+                self.apply_transform();
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "no se pudo abrir");
+                self.show_error(tr(self.language, Text::OpenFailed), format!("{}: {}", path.display(), e));
+            }
+        }
+    }
+
+    pub fn load_cancelled(&mut self, path: PathBuf, partial: String) {
+        self.loading = None;
+        if partial.is_empty() {
+            self.input_mode = InputMode::Normal;
+            self.notify(i18n::notice(self.language, Notice::LoadStopped(&path)));
+            return;
+        }
+        let message = format!("{} ({} KiB)", tr(self.language, Text::KeepPartialPrompt), partial.len() / 1024);
+        self.partial_load = Some((path, partial));
+        self.ask(Question::KeepPartial, tr(self.language, Text::LoadCancelled), message);
+    }
+
+    /// Conserva como texto fuente el inicio leído antes de cancelar, o lo descarta.
+    pub fn resolve_partial(&mut self, keep: bool) {
+        self.input_mode = InputMode::Normal;
+        let Some((path, partial)) = self.partial_load.take() else {
+            return;
+        };
+        if !keep {
+            self.notify(i18n::notice(self.language, Notice::PartialDiscarded(&path)));
+            return;
+        }
+        self.notify(i18n::notice(self.language, Notice::PartialKept(&path, partial.len() / 1024)));
+        self.source_text = Rope::from(partial);
+        self.source_scroll = 0;
+        // No es el archivo completo: no se asocia a su ruta.
+        self.source_path = None;
+        self.apply_transform();
+    }
+
+    /// Exporta la biblioteca a la ruta escrita, preguntando antes si ya existe.
+    pub fn export_library(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if !self.confirm_overwrite(Question::OverwriteLibrary) {
+            self.write_library_export();
+        }
+    }
+
+    pub fn write_library_export(&mut self) {
+        let path = PathBuf::from(self.path_input.trim());
+        match self.library.export(&path) {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::LibraryExported(self.library.entries().len(), &path))),
+            Err(e) => self.show_error(tr(self.language, Text::ExportFailed), format!("{:#}", e)),
+        }
+    }
+
+    pub fn export_report(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if !self.confirm_overwrite(Question::OverwriteReport) {
+            self.write_report();
+        }
+    }
+
+    pub fn write_report(&mut self) {
+        let path = PathBuf::from(self.path_input.trim());
+        let source = self.source_text.to_string();
+        match report::write(&path, &self.export_input(&source)) {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::ReportSaved(&path))),
+            Err(e) => self.show_error(tr(self.language, Text::ExportFailed), format!("{:#}", e)),
+        }
+    }
+
+    /// Si el destino escrito ya existe, pregunta antes de pisarlo y devuelve `true`.
+    fn confirm_overwrite(&mut self, question: Question) -> bool {
+        let path = PathBuf::from(self.path_input.trim());
+        if !path.exists() {
+            return false;
+        }
+        let message = i18n::overwrite_prompt(self.language, &path);
+        self.ask(question, tr(self.language, Text::Overwrite), message);
+        true
+    }
+
+    /// Carga una exportación JSON de regex101 como estado actual.
+    pub fn import_regex101(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let path = PathBuf::from(self.path_input.trim());
+        let imported = match regex101::load(&path) {
+            Ok(imported) => imported,
+            Err(e) => {
+                self.show_error(tr(self.language, Text::ImportFailed), format!("{:#}", e));
+                return;
+            }
+        };
+        self.regex_input = imported.pattern;
+        self.replace_input = imported.replacement;
+        self.flags = imported.flags;
+        if !imported.source.is_empty() {
+            self.source_text = Rope::from(imported.source);
+            self.source_scroll = 0;
+        }
+        self.record_history();
+        let warnings = imported.warnings.join("; ");
+        let notice = if warnings.is_empty() {
+            Notice::ImportedRegex101(&path)
+        } else {
+            Notice::ImportedRegex101WithWarnings(&warnings)
+        };
+        self.notify(i18n::notice(self.language, notice));
+    }
+
+    /// Agrega al panel de casos los de un corpus (por líneas o TOML).
+    pub fn import_tests(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let path = PathBuf::from(self.path_input.trim());
+        let imported = match cases::load(&path) {
+            Ok(imported) => imported,
+            Err(e) => {
+                self.show_error(tr(self.language, Text::ImportFailed), format!("{:#}", e));
+                return;
+            }
+        };
+        let count = imported.lines().filter(|line| cases::parse_line(line).is_some()).count();
+        if !self.test_cases.is_empty() && !self.test_cases.ends_with('\n') {
+            self.test_cases.push('\n');
+        }
+        self.test_cases.push_str(&imported);
+        self.notify(i18n::notice(self.language, Notice::CasesImported(count, &path)));
+    }
+
+    /// Lee un archivo de patrones; si hay nombres en conflicto pregunta cómo
+    /// resolverlos antes de mezclar.
+    pub fn import_library(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let path = PathBuf::from(self.path_input.trim());
+        let incoming = match Library::read_entries(&path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.show_error(tr(self.language, Text::ImportFailed), format!("{:#}", e));
+                return;
+            }
+        };
+
+        let conflicts = self.library.conflicts(&incoming);
+        if conflicts == 0 {
+            self.finish_import(incoming, ConflictPolicy::KeepExisting);
+        } else {
+            let message = i18n::conflicts_prompt(self.language, conflicts);
+            self.pending_import = Some(incoming);
+            self.ask(Question::ImportConflicts, tr(self.language, Text::Conflicts), message);
+        }
+    }
+
+    pub fn finish_import(&mut self, incoming: Vec<LibraryEntry>, policy: ConflictPolicy) {
+        self.input_mode = InputMode::Normal;
+        let summary = i18n::merge_summary(self.language, &self.library.merge(incoming, policy));
+        match self.save_library() {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::Imported(&summary))),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::LibraryNotSaved(&e))),
+        }
+    }
+
+    /// Trae en segundo plano los patrones de la fuente compartida configurada en
+    /// `[sync]`. Un fallo no impide usar la biblioteca local; solo se informa.
+    pub fn sync_library(&mut self) {
+        let config = self.config.sync.clone();
+        let tasks = self.tasks.clone();
+        tokio::task::spawn_blocking(move || {
+            let _ = tasks.send(Background::LibraryFetched(sync::fetch(&config)));
+        });
+    }
+
+    pub fn library_fetched(&mut self, entries: Result<Vec<LibraryEntry>>) {
+        let report = match entries {
+            Ok(entries) => self.library.apply_remote(entries),
+            Err(e) => {
+                self.notify_error(i18n::notice(self.language, Notice::SyncFailed(&e)));
+                return;
+            }
+        };
+        if self.input_mode == InputMode::Library {
+            self.refresh_library_preview();
+        }
+        let summary = i18n::sync_summary(self.language, &report);
+        match self.library.save() {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::Synced(&summary))),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::LibraryNotSaved(&e))),
+        }
+    }
+
+    /// Archivo de sesión activo: el de `--session` o, en su defecto, la última sesión.
+    fn active_session_path(&self) -> Option<PathBuf> {
+        self.session_path.clone().or_else(Session::last_session_path)
+    }
+
+    pub fn save_session(&mut self) {
+        let Some(path) = self.active_session_path() else {
+            self.notify(tr(self.language, Text::NoSessionPath));
+            return;
+        };
+        match self.to_session().save(&path) {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::SessionSaved(&path))),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::SessionNotSaved(&e))),
+        }
+    }
+
+    /// Registra la salida completa actual como la de referencia y guarda la
+    /// sesión para conservarla.
+    pub fn record_golden(&mut self) {
+        match self.to_session().output(self.config.limits.regex_size_limit) {
+            Ok(output) => {
+                let lines = output.lines().count();
+                self.golden = Some(output);
+                self.save_session();
+                self.notify(i18n::notice(self.language, Notice::GoldenRecorded(lines)));
+            }
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::RegexError(&e))),
+        }
+    }
+
+    /// Vuelve a calcular la salida y la compara con la de referencia.
+    pub fn check_golden(&mut self) {
+        if self.golden.is_none() {
+            let key = self.keymap.key_label(Action::RecordGolden);
+            self.notify(i18n::notice(self.language, Notice::NoGolden(&key)));
+            return;
+        }
+        match self.to_session().check_golden(self.config.limits.regex_size_limit) {
+            Ok(None) => self.notify(tr(self.language, Text::GoldenMatches)),
+            Ok(Some(drift)) => {
+                let message = i18n::golden_drift(self.language, &drift);
+                self.show_error(tr(self.language, Text::GoldenDrift), message);
+            }
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::RegexError(&e))),
+        }
+    }
+
+    pub fn load_session(&mut self) {
+        let Some(path) = self.active_session_path() else {
+            self.notify(tr(self.language, Text::NoSessionToRestore));
+            return;
+        };
+        match Session::load(&path) {
+            Ok(session) => {
+                self.restore_session(session);
+                self.notify(i18n::notice(self.language, Notice::SessionRestored(&path)));
+            }
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::SessionNotRestored(&e))),
+        }
+    }
+
+    /// Escribe la salida completa: la mostrada o, si se cortó por tamaño, la que se
+    /// vuelve a generar directamente sobre `out` sin pasar por memoria.
+    fn write_full_output(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.output_is_source {
+            return self.source_text.write_to(out);
+        }
+        if !self.output_truncated {
+            return out.write_all(self.output_text.as_bytes());
+        }
+        let options = self.engine_options();
+        let re = compile(&self.regex_input, &options).map_err(|e| io::Error::other(e.to_string()))?;
+        let mut sink = WriteSink { out: io::BufWriter::new(out), error: None };
+        transform_into(&re, &self.source_text.to_string(), &self.replace_input, options.unit, &mut sink);
+        match sink.error {
+            Some(e) => Err(e),
+            None => sink.out.flush(),
+        }
+    }
+
+    /// Agrega el resultado actual al archivo de log (`--log`), con fecha y resumen,
+    /// para dejar un rastro de auditoría de cada extracción confirmada.
+    pub fn commit_result(&mut self) {
+        let Some(path) = self.log_path.clone() else {
+            self.notify(tr(self.language, Text::NoLog));
+            return;
+        };
+
+        let mode = if self.replace_input.is_empty() { "filtro" } else { "reemplazo" };
+        let header = format!(
+            "[{}] modo: {} | regex: {} | reemplazo: {} | coincidencias: {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            mode,
+            self.regex_input,
+            self.replace_input,
+            self.match_count,
+        );
+
+        let result = OpenOptions::new().create(true).append(true).open(&path).and_then(|mut file| {
+            file.write_all(header.as_bytes())?;
+            self.write_full_output(&mut file)?;
+            file.write_all(b"\n---\n")
+        });
+
+        match result {
+            Ok(()) => self.notify(i18n::notice(self.language, Notice::ResultLogged(&path))),
+            Err(e) => self.notify_error(i18n::notice(self.language, Notice::LogNotWritten(&e))),
+        }
+    }
+}
+
+/// Lee `path` por bloques, enviando el progreso a `tasks`, hasta terminar o
+/// hasta que se pida cancelar.
+async fn read_with_progress(path: PathBuf, tasks: &mpsc::UnboundedSender<Background>, cancel: &AtomicBool) -> Background {
+    let mut file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) => return Background::FileLoaded(path, Err(e)),
+    };
+    let total = file.metadata().await.map(|metadata| metadata.len()).unwrap_or(0);
+    let mut bytes = Vec::with_capacity(total as usize);
+    let mut chunk = vec![0; 1 << 20];
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            // Sin cortar un carácter UTF-8 a la mitad.
+            let valid = match std::str::from_utf8(&bytes) {
+                Ok(_) => bytes.len(),
+                Err(e) => e.valid_up_to(),
+            };
+            bytes.truncate(valid);
+            let partial = String::from_utf8(bytes).unwrap_or_default();
+            return Background::LoadCancelled(path, partial);
+        }
+        let read = match file.read(&mut chunk).await {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) => return Background::FileLoaded(path, Err(e)),
+        };
+        bytes.extend_from_slice(&chunk[..read]);
+        let _ = tasks.send(Background::LoadProgress { read: bytes.len() as u64, total });
+    }
+    let text = String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+    Background::FileLoaded(path, text)
+}
+
+/// Copia `text` al portapapeles mediante la secuencia OSC 52 de la terminal, que
+/// también funciona a través de SSH.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    execute!(io::stdout(), CopyToClipboard::to_clipboard_from(text))
+}
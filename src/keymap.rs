@@ -0,0 +1,309 @@
+//! Atajos de teclado del modo EXPLORAR, remapeables desde `[keys]` en la
+//! configuración (`accion = "tecla"`, p. ej. `quit = "ctrl+q"`).
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    EditSource,
+    EditRegex,
+    EditReplace,
+    EditTests,
+    EditCompare,
+    Debug,
+    MarkSpans,
+    NearMisses,
+    StressTest,
+    SuggestAi,
+    CommitResult,
+    ToggleUnit,
+    ToggleCaseInsensitive,
+    ToggleMultiLine,
+    ToggleDotAll,
+    ToggleIgnoreWhitespace,
+    OpenHistory,
+    OpenLibrary,
+    SavePattern,
+    SaveSession,
+    LoadSession,
+    RecordGolden,
+    CheckGolden,
+    NextSample,
+    OpenFile,
+    OpenRecent,
+    ExportLibrary,
+    ImportLibrary,
+    ImportRegex101,
+    ImportTests,
+    OpenCatalog,
+    OpenExercises,
+    ExportCode,
+    ExportReport,
+    SetApiKey,
+    CommandPalette,
+    ScrollSourceUp,
+    ScrollSourceDown,
+    ScrollOutputUp,
+    ScrollOutputDown,
+    ToggleStats,
+    ToggleCoverage,
+    CycleLayout,
+    GrowOutput,
+    ShrinkOutput,
+    ToggleZoom,
+    ShowHelp,
+    NewWorkspace,
+    CloseWorkspace,
+    NextWorkspace,
+    PreviousWorkspace,
+    ToggleSplit,
+}
+
+impl Action {
+    /// Todas las acciones, con su nombre en la configuración y su tecla por defecto.
+    pub const ALL: [(Action, &'static str, &'static str); 53] = [
+        (Action::Quit, "quit", "q"),
+        (Action::EditSource, "edit_source", "s"),
+        (Action::EditRegex, "edit_regex", "r"),
+        (Action::EditReplace, "edit_replace", "t"),
+        (Action::EditTests, "edit_tests", "u"),
+        (Action::EditCompare, "edit_compare", "B"),
+        (Action::Debug, "debug", "D"),
+        (Action::MarkSpans, "mark_spans", "M"),
+        (Action::NearMisses, "near_misses", "N"),
+        (Action::StressTest, "stress_test", "S"),
+        (Action::SuggestAi, "suggest_ai", "tab"),
+        (Action::CommitResult, "commit_result", "c"),
+        (Action::ToggleUnit, "toggle_unit", "l"),
+        (Action::ToggleCaseInsensitive, "toggle_case_insensitive", "i"),
+        (Action::ToggleMultiLine, "toggle_multi_line", "m"),
+        (Action::ToggleDotAll, "toggle_dot_all", "d"),
+        (Action::ToggleIgnoreWhitespace, "toggle_ignore_whitespace", "x"),
+        (Action::OpenHistory, "open_history", "h"),
+        (Action::OpenLibrary, "open_library", "b"),
+        (Action::SavePattern, "save_pattern", "g"),
+        (Action::SaveSession, "save_session", "w"),
+        (Action::LoadSession, "load_session", "o"),
+        (Action::RecordGolden, "record_golden", "G"),
+        (Action::CheckGolden, "check_golden", "V"),
+        (Action::NextSample, "next_sample", "n"),
+        (Action::OpenFile, "open_file", "f"),
+        (Action::OpenRecent, "open_recent", "e"),
+        (Action::ExportLibrary, "export_library", "E"),
+        (Action::ImportLibrary, "import_library", "I"),
+        (Action::ImportRegex101, "import_regex101", "J"),
+        (Action::ImportTests, "import_tests", "U"),
+        (Action::OpenCatalog, "open_catalog", "k"),
+        (Action::OpenExercises, "open_exercises", "X"),
+        (Action::ExportCode, "export_code", "y"),
+        (Action::ExportReport, "export_report", "R"),
+        (Action::SetApiKey, "set_api_key", "K"),
+        (Action::CommandPalette, "command_palette", "ctrl+p"),
+        (Action::ScrollSourceUp, "scroll_source_up", "up"),
+        (Action::ScrollSourceDown, "scroll_source_down", "down"),
+        (Action::ScrollOutputUp, "scroll_output_up", "pageup"),
+        (Action::ScrollOutputDown, "scroll_output_down", "pagedown"),
+        (Action::ToggleStats, "toggle_stats", "p"),
+        (Action::ToggleCoverage, "toggle_coverage", "C"),
+        (Action::CycleLayout, "cycle_layout", "L"),
+        (Action::GrowOutput, "grow_output", "+"),
+        (Action::ShrinkOutput, "shrink_output", "-"),
+        (Action::ToggleZoom, "toggle_zoom", "z"),
+        (Action::ShowHelp, "show_help", "?"),
+        (Action::NewWorkspace, "new_workspace", "T"),
+        (Action::CloseWorkspace, "close_workspace", "W"),
+        (Action::NextWorkspace, "next_workspace", "]"),
+        (Action::PreviousWorkspace, "previous_workspace", "["),
+        (Action::ToggleSplit, "toggle_split", "v"),
+    ];
+
+    pub fn name(self) -> &'static str {
+        Action::ALL
+            .iter()
+            .find(|(action, _, _)| *action == self)
+            .map(|(_, name, _)| *name)
+            .unwrap_or("?")
+    }
+}
+
+/// Tecla con sus modificadores relevantes (Ctrl/Alt; Shift ya va en el carácter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn from_event(event: &KeyEvent) -> KeyBinding {
+        KeyBinding {
+            code: event.code,
+            modifiers: event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT),
+        }
+    }
+
+    /// Interpreta textos como `"q"`, `"ctrl+p"`, `"alt+enter"`, `"f5"` o `"pagedown"`.
+    pub fn parse(text: &str) -> Result<KeyBinding> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = text;
+        // Un "+" final es la propia tecla (p. ej. "ctrl++"), no un separador.
+        while let Some((prefix, rest)) = key.split_once('+').filter(|(_, rest)| !rest.is_empty()) {
+            match prefix.to_lowercase().as_str() {
+                "ctrl" => modifiers |= KeyModifiers::CONTROL,
+                "alt" => modifiers |= KeyModifiers::ALT,
+                other => anyhow::bail!("Modificador desconocido '{}' en '{}'", other, text),
+            }
+            key = rest;
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "tab" => KeyCode::Tab,
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                        _ => anyhow::bail!("Tecla desconocida: '{}'", text),
+                    },
+                }
+            }
+        };
+        Ok(KeyBinding { code, modifiers })
+    }
+
+    /// Representación legible, en el mismo formato que acepta `parse`.
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("alt+");
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("space"),
+            KeyCode::Char(c) => label.push(c),
+            KeyCode::Tab => label.push_str("tab"),
+            KeyCode::Enter => label.push_str("enter"),
+            KeyCode::Esc => label.push_str("esc"),
+            KeyCode::Backspace => label.push_str("backspace"),
+            KeyCode::Delete => label.push_str("delete"),
+            KeyCode::Up => label.push_str("up"),
+            KeyCode::Down => label.push_str("down"),
+            KeyCode::Left => label.push_str("left"),
+            KeyCode::Right => label.push_str("right"),
+            KeyCode::Home => label.push_str("home"),
+            KeyCode::End => label.push_str("end"),
+            KeyCode::PageUp => label.push_str("pageup"),
+            KeyCode::PageDown => label.push_str("pagedown"),
+            KeyCode::F(n) => label.push_str(&format!("f{}", n)),
+            other => label.push_str(&format!("{:?}", other)),
+        }
+        label
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyBinding, Action>,
+    keys: HashMap<Action, KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::from_config(&BTreeMap::new()).expect("los atajos por defecto no tienen conflictos")
+    }
+}
+
+impl Keymap {
+    /// Construye el mapa aplicando las redefiniciones de `[keys]` sobre los valores
+    /// por defecto. Falla ante acciones o teclas desconocidas y ante dos acciones
+    /// asignadas a la misma tecla.
+    pub fn from_config(overrides: &BTreeMap<String, String>) -> Result<Keymap> {
+        for name in overrides.keys() {
+            if !Action::ALL.iter().any(|(_, action_name, _)| *action_name == name.as_str()) {
+                anyhow::bail!("Acción desconocida en [keys]: '{}'", name);
+            }
+        }
+
+        let mut bindings: HashMap<KeyBinding, Action> = HashMap::new();
+        let mut keys = HashMap::new();
+        for (action, name, default) in Action::ALL {
+            let text = overrides.get(name).map(String::as_str).unwrap_or(default);
+            let binding = KeyBinding::parse(text)?;
+            if let Some(other) = bindings.insert(binding, action) {
+                anyhow::bail!(
+                    "Conflicto de teclas: '{}' está asignada a '{}' y a '{}'",
+                    binding.label(),
+                    other.name(),
+                    name
+                );
+            }
+            keys.insert(action, binding);
+        }
+        Ok(Keymap { bindings, keys })
+    }
+
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings.get(&KeyBinding::from_event(event)).copied()
+    }
+
+    /// Etiqueta de la tecla asignada a `action`, para las ayudas en pantalla.
+    pub fn key_label(&self, action: Action) -> String {
+        self.keys.get(&action).map(KeyBinding::label).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(action, key)| (action.to_string(), key.to_string())).collect()
+    }
+
+    #[test]
+    fn unknown_actions_are_rejected() {
+        let error = Keymap::from_config(&overrides(&[("volar", "x")])).unwrap_err();
+        assert!(error.to_string().contains("'volar'"), "{}", error);
+    }
+
+    #[test]
+    fn two_actions_on_one_key_are_rejected() {
+        let error = Keymap::from_config(&overrides(&[("quit", "v")])).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("'quit'") && message.contains("'toggle_split'"), "{}", message);
+        // Mover también la otra acción deshace el conflicto.
+        let keymap = Keymap::from_config(&overrides(&[("quit", "v"), ("toggle_split", "ctrl+v")])).unwrap();
+        assert_eq!(keymap.key_label(Action::Quit), "v");
+        assert_eq!(keymap.key_label(Action::ToggleSplit), "ctrl+v");
+    }
+
+    #[test]
+    fn labels_parse_back_to_the_same_binding() {
+        for text in ["q", "Q", "ctrl+p", "alt+enter", "ctrl+alt+f5", "space", "pagedown", "ctrl++", "alt+home"] {
+            let binding = KeyBinding::parse(text).unwrap();
+            assert_eq!(binding.label(), text);
+            assert_eq!(KeyBinding::parse(&binding.label()).unwrap(), binding);
+        }
+        assert_eq!(KeyBinding::parse("Ctrl+PageUp").unwrap().label(), "ctrl+pageup");
+        assert!(KeyBinding::parse("shift+x").is_err());
+        assert!(KeyBinding::parse("f13").is_err());
+    }
+}
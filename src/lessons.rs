@@ -0,0 +1,116 @@
+//! Lecciones incorporadas para aprender regex: una explicación en markdown y
+//! ejercicios. Cada ejercicio da un texto y las coincidencias que hay que
+//! obtener, y se resuelve escribiendo un patrón que encuentre exactamente esas.
+//! El progreso (los ejercicios resueltos) se guarda como JSON en el directorio
+//! de datos de la plataforma.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+/// Archivos de lección, en orden, incluidos en el binario.
+const LESSON_FILES: &[&str] = &[
+    include_str!("../lessons/01-literales.toml"),
+    include_str!("../lessons/02-cuantificadores.toml"),
+    include_str!("../lessons/03-grupos.toml"),
+    include_str!("../lessons/04-clases.toml"),
+    include_str!("../lessons/05-rust.toml"),
+];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Lesson {
+    /// Identificador estable, con el que se guarda el progreso.
+    pub id: String,
+    pub title: String,
+    /// Explicación de la lección en markdown (títulos, listas y `código`).
+    #[serde(default)]
+    pub body: String,
+    #[serde(rename = "exercise")]
+    pub exercises: Vec<Exercise>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Exercise {
+    pub prompt: String,
+    pub text: String,
+    /// Coincidencias esperadas sobre `text`, en orden.
+    pub expected: Vec<String>,
+    #[serde(default)]
+    pub hint: String,
+    /// Una solución de referencia (las pruebas comprueban que resuelve el ejercicio).
+    pub solution: String,
+}
+
+/// Las lecciones incorporadas, en orden.
+pub fn lessons() -> Vec<Lesson> {
+    LESSON_FILES
+        .iter()
+        .map(|file| toml::from_str(file).expect("lección incorporada inválida"))
+        .collect()
+}
+
+/// Clave de progreso del ejercicio `index` (desde 0) de `lesson`.
+pub fn exercise_key(lesson: &Lesson, index: usize) -> String {
+    format!("{}/{}", lesson.id, index + 1)
+}
+
+/// `re` resuelve el ejercicio: sus coincidencias sobre el texto son
+/// exactamente las esperadas, en el mismo orden.
+pub fn check(re: &Regex, exercise: &Exercise) -> bool {
+    re.find_iter(&exercise.text).map(|m| m.as_str()).eq(exercise.expected.iter().map(String::as_str))
+}
+
+/// Ejercicios resueltos, por clave de progreso.
+#[derive(Debug, Default)]
+pub struct Progress {
+    solved: BTreeSet<String>,
+    /// `None` deja el progreso solo en memoria (sin directorio de datos).
+    path: Option<PathBuf>,
+}
+
+impl Progress {
+    pub fn default_path() -> Option<PathBuf> {
+        crate::paths::data_file("progress.json")
+    }
+
+    pub fn load() -> Result<Progress> {
+        let Some(path) = Progress::default_path() else {
+            return Ok(Progress::default());
+        };
+        if !path.exists() {
+            return Ok(Progress { solved: BTreeSet::new(), path: Some(path) });
+        }
+
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("No se pudo leer {}", path.display()))?;
+        let solved = serde_json::from_str(&text)
+            .with_context(|| format!("Progreso inválido en {}", path.display()))?;
+        Ok(Progress { solved, path: Some(path) })
+    }
+
+    pub fn is_solved(&self, key: &str) -> bool {
+        self.solved.contains(key)
+    }
+
+    pub fn solved_count(&self) -> usize {
+        self.solved.len()
+    }
+
+    /// Marca `key` como resuelto y lo persiste. Devuelve si es la primera vez.
+    pub fn mark_solved(&mut self, key: &str) -> Result<bool> {
+        if !self.solved.insert(key.to_string()) {
+            return Ok(false);
+        }
+        let Some(path) = &self.path else {
+            return Ok(true);
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(&self.solved)?;
+        std::fs::write(path, text).with_context(|| format!("No se pudo escribir {}", path.display()))?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,47 @@
+//! Núcleo de regex-wysiwyg, independiente de la interfaz de terminal: el motor
+//! de coincidencias y reemplazos, la explicación de patrones, los exportadores a
+//! otros lenguajes y herramientas, y los datos persistentes (configuración,
+//! historial, biblioteca, proyectos).
+//!
+//! ```
+//! use regex_wysiwyg::{EngineOptions, ProcessingUnit, RegexFlags, transform};
+//!
+//! let options = EngineOptions { unit: ProcessingUnit::WholeText, flags: RegexFlags::default(), size_limit: 1 << 20 };
+//! let result = transform("a1 b22", r"\d+", "", &options).unwrap();
+//! assert_eq!(result.output, "1 | 22");
+//! ```
+
+pub mod assertions;
+pub mod branches;
+pub mod cases;
+pub mod catalog;
+pub mod classes;
+pub mod config;
+pub mod coverage;
+pub mod debugger;
+pub mod engine;
+pub mod explain;
+pub mod export;
+pub mod fuzzy;
+pub mod history;
+pub mod introspect;
+pub mod lessons;
+pub mod library;
+pub mod nearmiss;
+pub mod paths;
+pub mod project;
+pub mod recent;
+pub mod regex101;
+pub mod report;
+pub mod search;
+pub mod secrets;
+pub mod server;
+pub mod stress;
+pub mod sync;
+pub mod syntax;
+pub mod worker;
+
+pub use engine::{
+    EngineOptions, OutputSink, ProcessingUnit, RegexCache, RegexFlags, Transform, WriteSink, compile, count_matches,
+    has_match, locate_matches, match_spans, replace_with, split_lines, transform, transform_capped, transform_into, transform_with,
+};
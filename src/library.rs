@@ -0,0 +1,492 @@
+//! Biblioteca de patrones con nombre (patrón + reemplazo + flags), guardada como
+//! un arreglo JSON en el directorio de datos de la plataforma.
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::{EngineOptions, ProcessingUnit, RegexFlags};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub replacement: String,
+    #[serde(default)]
+    pub flags: RegexFlags,
+    /// Para qué sirve el patrón, en texto libre.
+    #[serde(default)]
+    pub description: String,
+    /// Texto de ejemplo sobre el que el patrón debería coincidir.
+    #[serde(default)]
+    pub example: String,
+    /// Etiquetas libres para agrupar y filtrar (`logs`, `pii`, `fechas`, ...).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Veces que se cargó desde la biblioteca.
+    #[serde(default)]
+    pub uses: u32,
+    /// Fecha del último uso (`AAAA-MM-DD HH:MM:SS`, ordenable como texto).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+    /// Entrada recibida de la fuente compartida (`[sync]`); las demás son solo locales.
+    #[serde(default)]
+    pub remote: bool,
+    /// Casos de prueba del patrón, en el formato del panel de casos. Se vuelven
+    /// a evaluar antes de sobrescribir la entrada con otro patrón.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub tests: String,
+}
+
+impl LibraryEntry {
+    /// Igualdad de definición, sin contar las estadísticas de uso.
+    pub fn same_definition(&self, other: &LibraryEntry) -> bool {
+        self.name == other.name
+            && self.pattern == other.pattern
+            && self.replacement == other.replacement
+            && self.flags == other.flags
+            && self.description == other.description
+            && self.example == other.example
+            && self.tags == other.tags
+            && self.tests == other.tests
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Orden de la lista de la biblioteca.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LibrarySort {
+    Recent,
+    Frequent,
+    Name,
+}
+
+impl LibrarySort {
+    pub fn next(self) -> LibrarySort {
+        match self {
+            LibrarySort::Recent => LibrarySort::Frequent,
+            LibrarySort::Frequent => LibrarySort::Name,
+            LibrarySort::Name => LibrarySort::Recent,
+        }
+    }
+}
+
+/// Qué hacer al importar una entrada cuyo nombre ya existe con otro contenido.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    Overwrite,
+    KeepExisting,
+    Rename,
+}
+
+/// Resumen de una importación.
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    pub added: usize,
+    pub overwritten: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+}
+
+/// Resumen de una sincronización con la fuente compartida.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub added: usize,
+    pub updated: usize,
+    pub local_only: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Library {
+    entries: Vec<LibraryEntry>,
+    /// `None` deja la biblioteca solo en memoria (sin directorio de datos).
+    path: Option<PathBuf>,
+}
+
+impl Library {
+    pub fn default_path() -> Option<PathBuf> {
+        crate::paths::data_file("library.json")
+    }
+
+    pub fn load() -> Result<Library> {
+        let Some(path) = Library::default_path() else {
+            return Ok(Library::default());
+        };
+        if !path.exists() {
+            return Ok(Library { entries: Vec::new(), path: Some(path) });
+        }
+
+        let entries = Library::read_entries(&path)?;
+        Ok(Library { entries, path: Some(path) })
+    }
+
+    /// Lee un arreglo JSON de entradas (el formato de la biblioteca y de las exportaciones).
+    pub fn read_entries(path: &Path) -> Result<Vec<LibraryEntry>> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("No se pudo leer {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Biblioteca inválida en {}", path.display()))
+    }
+
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let text = serde_json::to_string_pretty(&self.entries)?;
+        std::fs::write(path, text).with_context(|| format!("No se pudo escribir {}", path.display()))
+    }
+
+    /// Biblioteca solo en memoria, p. ej. los patrones de un proyecto abierto.
+    pub fn in_memory(entries: Vec<LibraryEntry>) -> Library {
+        Library { entries, path: None }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(&self.entries)?;
+        std::fs::write(path, text).with_context(|| format!("No se pudo escribir {}", path.display()))
+    }
+
+    pub fn entries(&self) -> &[LibraryEntry] {
+        &self.entries
+    }
+
+    /// Agrega la entrada o reemplaza la que tenga el mismo nombre (conservando sus
+    /// estadísticas de uso). Devuelve `true` si sobrescribió una existente.
+    pub fn upsert(&mut self, mut entry: LibraryEntry) -> bool {
+        match self.entries.iter_mut().find(|existing| existing.name == entry.name) {
+            Some(existing) => {
+                entry.uses = existing.uses;
+                entry.last_used = existing.last_used.take();
+                *existing = entry;
+                true
+            }
+            None => {
+                self.entries.push(entry);
+                self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+                false
+            }
+        }
+    }
+
+    /// Cantidad de entradas entrantes con un nombre existente pero distinto contenido.
+    pub fn conflicts(&self, incoming: &[LibraryEntry]) -> usize {
+        incoming
+            .iter()
+            .filter(|entry| {
+                self.entries
+                    .iter()
+                    .any(|existing| existing.name == entry.name && !existing.same_definition(entry))
+            })
+            .count()
+    }
+
+    /// Incorpora entradas importadas. Las idénticas a una existente se omiten; los
+    /// conflictos de nombre se resuelven según `policy`. Lo importado queda como
+    /// solo local aunque viniera marcado como recibido de la fuente compartida.
+    pub fn merge(&mut self, incoming: Vec<LibraryEntry>, policy: ConflictPolicy) -> MergeReport {
+        let mut report = MergeReport::default();
+        for mut entry in incoming {
+            entry.remote = false;
+            let Some(index) = self.entries.iter().position(|existing| existing.name == entry.name) else {
+                self.entries.push(entry);
+                report.added += 1;
+                continue;
+            };
+            if self.entries[index].same_definition(&entry) {
+                report.skipped += 1;
+                continue;
+            }
+            match policy {
+                ConflictPolicy::Overwrite => {
+                    self.entries[index] = entry;
+                    report.overwritten += 1;
+                }
+                ConflictPolicy::KeepExisting => report.skipped += 1,
+                ConflictPolicy::Rename => {
+                    entry.name = self.unused_name(&entry.name);
+                    self.entries.push(entry);
+                    report.renamed += 1;
+                }
+            }
+        }
+        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        report
+    }
+
+    /// Aplica el conjunto publicado por la fuente compartida: sus entradas se
+    /// agregan o reemplazan a las locales del mismo nombre (conservando las
+    /// estadísticas de uso) y todo lo demás queda marcado como solo local.
+    pub fn apply_remote(&mut self, incoming: Vec<LibraryEntry>) -> SyncReport {
+        let mut report = SyncReport::default();
+        for entry in &mut self.entries {
+            entry.remote = false;
+        }
+        for mut entry in incoming {
+            entry.remote = true;
+            match self.entries.iter_mut().find(|existing| existing.name == entry.name) {
+                Some(existing) => {
+                    if !existing.same_definition(&entry) {
+                        report.updated += 1;
+                    }
+                    entry.uses = existing.uses;
+                    entry.last_used = existing.last_used.take();
+                    *existing = entry;
+                }
+                None => {
+                    self.entries.push(entry);
+                    report.added += 1;
+                }
+            }
+        }
+        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        report.local_only = self.entries.iter().filter(|entry| !entry.remote).count();
+        report
+    }
+
+    /// Primer nombre libre de la forma `base (2)`, `base (3)`, ...
+    fn unused_name(&self, base: &str) -> String {
+        (2..)
+            .map(|n| format!("{} ({})", base, n))
+            .find(|candidate| !self.entries.iter().any(|existing| existing.name == *candidate))
+            .expect("siempre hay un sufijo libre")
+    }
+
+    /// Índices de las entradas en el orden pedido, opcionalmente solo las que
+    /// llevan la etiqueta `tag`.
+    pub fn view(&self, sort: LibrarySort, tag: Option<&str>) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.entries.len())
+            .filter(|&index| tag.is_none_or(|tag| self.entries[index].has_tag(tag)))
+            .collect();
+        indices.sort_by(|&a, &b| {
+            let (a, b) = (&self.entries[a], &self.entries[b]);
+            let by_recency = b.last_used.cmp(&a.last_used);
+            let order = match sort {
+                LibrarySort::Recent => by_recency,
+                LibrarySort::Frequent => b.uses.cmp(&a.uses).then(by_recency),
+                LibrarySort::Name => std::cmp::Ordering::Equal,
+            };
+            order.then_with(|| a.name.cmp(&b.name))
+        });
+        indices
+    }
+
+    /// Anota un uso de la entrada (contador y fecha).
+    pub fn record_use(&mut self, index: usize) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.uses += 1;
+            entry.last_used = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+    }
+
+    /// Todas las etiquetas usadas, ordenadas y sin repetir.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self.entries.iter().flat_map(|entry| entry.tags.iter().cloned()).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    pub fn find(&self, name: &str) -> Option<&LibraryEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<LibraryEntry> {
+        (index < self.entries.len()).then(|| self.entries.remove(index))
+    }
+}
+
+/// Dónde coincide una entrada por primera vez: línea y columna (desde 1) y el
+/// texto coincidente.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstMatch {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+/// Vista previa de la biblioteca sobre un texto. Las entradas se compilan una
+/// sola vez al abrirla, como las compila el motor: un `RegexSet` decide de una
+/// pasada cuáles coinciden y solo esas se vuelven a recorrer para ubicar su
+/// primera coincidencia, así que moverse por la lista no compila ni busca nada.
+/// Con `ProcessingUnit::PerLine` se busca línea por línea, como en la salida.
+#[derive(Debug, Default)]
+pub struct LibraryPreview {
+    /// Por entrada (mismo índice que `Library::entries`): el error si no compila.
+    errors: Vec<Option<String>>,
+    /// Por entrada: su primera coincidencia en el texto, si la hay.
+    first_matches: Vec<Option<FirstMatch>>,
+}
+
+impl LibraryPreview {
+    pub fn build(entries: &[LibraryEntry], text: &str, unit: ProcessingUnit, size_limit: usize) -> LibraryPreview {
+        let mut errors = Vec::with_capacity(entries.len());
+        let mut compiled: Vec<(usize, Regex)> = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            let options = EngineOptions { unit, flags: entry.flags, size_limit };
+            match crate::compile(&entry.pattern, &options) {
+                Ok(re) => {
+                    errors.push(None);
+                    compiled.push((index, re));
+                }
+                Err(e) => errors.push(Some(e.to_string())),
+            }
+        }
+
+        let mut first_matches = vec![None; entries.len()];
+        // Cada patrón lleva sus flags en línea, porque las del builder del set son comunes a todos.
+        let set = RegexSetBuilder::new(compiled.iter().map(|(index, _)| inline_flags(&entries[*index])))
+            .size_limit(size_limit.saturating_mul(compiled.len().max(1)))
+            .build();
+        // Tramos donde se busca, con su posición en el texto.
+        let segments: Vec<(usize, &str)> = match unit {
+            ProcessingUnit::WholeText => vec![(0, text)],
+            ProcessingUnit::PerLine => crate::split_lines(text)
+                .scan(0, |offset, (line, ending)| {
+                    let start = *offset;
+                    *offset += line.len() + ending.len();
+                    Some((start, line))
+                })
+                .collect(),
+        };
+        // Si el conjunto excede el límite, se prueba cada entrada por separado.
+        let candidates: Vec<usize> = match set {
+            Ok(set) => {
+                let mut matched = vec![false; compiled.len()];
+                for (_, segment) in &segments {
+                    for position in set.matches(segment).iter() {
+                        matched[position] = true;
+                    }
+                }
+                (0..compiled.len()).filter(|&position| matched[position]).collect()
+            }
+            Err(_) => (0..compiled.len()).collect(),
+        };
+        for position in candidates {
+            let (index, re) = &compiled[position];
+            let found = segments.iter().find_map(|&(offset, segment)| re.find(segment).map(|m| (offset, m)));
+            first_matches[*index] = found.map(|(offset, m)| {
+                let before = &text[..offset + m.start()];
+                let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+                FirstMatch {
+                    line: before.matches('\n').count() + 1,
+                    column: before[line_start..].chars().count() + 1,
+                    text: m.as_str().to_string(),
+                }
+            });
+        }
+        LibraryPreview { errors, first_matches }
+    }
+
+    /// El error de compilación de la entrada, si no compila.
+    pub fn error(&self, index: usize) -> Option<&str> {
+        self.errors.get(index).and_then(|error| error.as_deref())
+    }
+
+    pub fn first_match(&self, index: usize) -> Option<&FirstMatch> {
+        self.first_matches.get(index).and_then(Option::as_ref)
+    }
+}
+
+/// El patrón de la entrada precedido de sus flags como grupo en línea (`(?imsx)`).
+fn inline_flags(entry: &LibraryEntry) -> String {
+    let flags = entry.flags.summary();
+    if flags == "-" { entry.pattern.clone() } else { format!("(?{}){}", flags, entry.pattern) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, pattern: &str) -> LibraryEntry {
+        LibraryEntry { name: name.to_string(), pattern: pattern.to_string(), ..LibraryEntry::default() }
+    }
+
+    fn sample() -> Library {
+        Library::in_memory(vec![entry("fecha", r"\d{4}-\d{2}-\d{2}"), entry("hora", r"\d{2}:\d{2}")])
+    }
+
+    fn patterns(library: &Library) -> Vec<(&str, &str)> {
+        library.entries().iter().map(|entry| (entry.name.as_str(), entry.pattern.as_str())).collect()
+    }
+
+    #[test]
+    fn merge_skips_identical_entries_under_every_policy() {
+        for policy in [ConflictPolicy::Overwrite, ConflictPolicy::KeepExisting, ConflictPolicy::Rename] {
+            let mut library = sample();
+            let mut same = entry("hora", r"\d{2}:\d{2}");
+            same.uses = 7;
+            let report = library.merge(vec![same], policy);
+            assert_eq!((report.added, report.overwritten, report.renamed, report.skipped), (0, 0, 0, 1));
+            assert_eq!(library.entries().len(), 2);
+        }
+    }
+
+    #[test]
+    fn merge_resolves_conflicts_by_policy() {
+        let incoming = || vec![entry("hora", r"\d{1,2}:\d{2}"), entry("ip", r"\d+\.\d+\.\d+\.\d+")];
+
+        let mut library = sample();
+        let report = library.merge(incoming(), ConflictPolicy::Overwrite);
+        assert_eq!((report.added, report.overwritten), (1, 1));
+        assert_eq!(library.find("hora").unwrap().pattern, r"\d{1,2}:\d{2}");
+
+        let mut library = sample();
+        let report = library.merge(incoming(), ConflictPolicy::KeepExisting);
+        assert_eq!((report.added, report.skipped), (1, 1));
+        assert_eq!(library.find("hora").unwrap().pattern, r"\d{2}:\d{2}");
+
+        let mut library = sample();
+        let report = library.merge(incoming(), ConflictPolicy::Rename);
+        assert_eq!((report.added, report.renamed), (1, 1));
+        assert_eq!(
+            patterns(&library),
+            [
+                ("fecha", r"\d{4}-\d{2}-\d{2}"),
+                ("hora", r"\d{2}:\d{2}"),
+                ("hora (2)", r"\d{1,2}:\d{2}"),
+                ("ip", r"\d+\.\d+\.\d+\.\d+"),
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_entries_are_local() {
+        let mut library = sample();
+        let mut shared = entry("ip", r"\d+(\.\d+){3}");
+        shared.remote = true;
+        library.merge(vec![shared], ConflictPolicy::Overwrite);
+        assert!(!library.find("ip").unwrap().remote);
+        let report = library.apply_remote(Vec::new());
+        assert_eq!(report.local_only, 3);
+    }
+
+    #[test]
+    fn unused_name_skips_taken_suffixes() {
+        let mut library = sample();
+        library.upsert(entry("hora (2)", "x"));
+        library.upsert(entry("hora (4)", "y"));
+        assert_eq!(library.unused_name("hora"), "hora (3)");
+        assert_eq!(library.unused_name("ip"), "ip (2)");
+    }
+
+    #[test]
+    fn preview_follows_the_processing_unit() {
+        let entries = [entry("vacía", "^$"), entry("fin", r"b$"), entry("rota", "(")];
+        let text = "a\r\n\nab\n";
+
+        let preview = LibraryPreview::build(&entries, text, ProcessingUnit::WholeText, 1 << 20);
+        assert!(preview.first_match(0).is_none() && preview.first_match(1).is_none());
+        assert!(preview.error(2).is_some());
+
+        let preview = LibraryPreview::build(&entries, text, ProcessingUnit::PerLine, 1 << 20);
+        assert_eq!(preview.first_match(0), Some(&FirstMatch { line: 2, column: 1, text: String::new() }));
+        assert_eq!(preview.first_match(1), Some(&FirstMatch { line: 3, column: 2, text: "b".to_string() }));
+    }
+}
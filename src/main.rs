@@ -1,275 +1,531 @@
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use futures::StreamExt;
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph, Wrap},
-    Frame, Terminal,
+    layout::Rect,
+    Terminal,
 };
-use std::{io, time::Duration};
-use std::process::Command;
-
-#[derive(Debug, PartialEq)]
-enum InputMode {
-    Normal,
-    EditingSource,
-    EditingRegex,
-    EditingReplace,
-}
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+mod ai;
+mod app;
+mod i18n;
+mod io;
+mod keymap;
+mod session;
+mod theme;
+mod ui;
+mod update;
 
-struct App {
-    source_text: String,
-    regex_input: String,
-    replace_input: String,
-    output_text: String,
-    input_mode: InputMode,
-    status_message: String,
+use app::{App, InputMode, Question};
+use regex_wysiwyg::config::Config;
+use regex_wysiwyg::project::OpenProject;
+use regex_wysiwyg::{EngineOptions, ProcessingUnit, compile, paths, search, server, transform};
+use session::Session;
+use ui::ui;
+use update::{Msg, update};
+
+/// Opciones de línea de comandos.
+#[derive(Default)]
+struct Args {
+    log_path: Option<PathBuf>,
+    session_path: Option<PathBuf>,
+    project_path: Option<PathBuf>,
+    file_path: Option<PathBuf>,
+    profile: Option<String>,
+    serve: bool,
+    batch: bool,
+    pattern: Option<String>,
+    replace: String,
+    per_line: bool,
+    json_output: bool,
+    debug: bool,
+    inputs: Vec<PathBuf>,
+    /// Al cancelar una búsqueda en varios archivos, no imprimir lo ya encontrado.
+    discard_partial: bool,
+    /// Sesiones cuya salida de referencia se comprueba (`--check`).
+    check: Vec<PathBuf>,
 }
 
-impl Default for App {
-    fn default() -> App {
-        App {
-            source_text: "Praliné saber no ocupa el lugar de argentino.".to_string(),
-            regex_input: String::new(),
-            replace_input: String::new(),
-            output_text: String::new(),
-            input_mode: InputMode::Normal,
-            status_message: "Listo. 's': Fuente, 'r': Regex, 't': Reemplazar, 'TAB': IA".to_string(),
+fn parse_args() -> Result<Args> {
+    let mut args = Args::default();
+    let mut positional = Vec::new();
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--log" => {
+                let path = iter.next().ok_or_else(|| anyhow::anyhow!("--log requiere una ruta"))?;
+                args.log_path = Some(PathBuf::from(path));
+            }
+            "--session" => {
+                let path = iter.next().ok_or_else(|| anyhow::anyhow!("--session requiere una ruta"))?;
+                args.session_path = Some(PathBuf::from(path));
+            }
+            "--project" => {
+                let path = iter.next().ok_or_else(|| anyhow::anyhow!("--project requiere una ruta"))?;
+                args.project_path = Some(PathBuf::from(path));
+            }
+            "--file" => {
+                let path = iter.next().ok_or_else(|| anyhow::anyhow!("--file requiere una ruta"))?;
+                args.file_path = Some(PathBuf::from(path));
+            }
+            "--profile" => {
+                args.profile = Some(iter.next().ok_or_else(|| anyhow::anyhow!("--profile requiere un nombre"))?);
+            }
+            "--check" => {
+                let path = iter.next().ok_or_else(|| anyhow::anyhow!("--check requiere una ruta"))?;
+                args.check.push(PathBuf::from(path));
+            }
+            "--serve" => args.serve = true,
+            "--batch" => args.batch = true,
+            "--replace" => {
+                args.replace = iter.next().ok_or_else(|| anyhow::anyhow!("--replace requiere un texto"))?;
+            }
+            "--per-line" => args.per_line = true,
+            "--discard-partial" => args.discard_partial = true,
+            "--debug" => args.debug = true,
+            "--output" => match iter.next().as_deref() {
+                Some("json") => args.json_output = true,
+                Some("text") => args.json_output = false,
+                _ => anyhow::bail!("--output requiere 'text' o 'json'"),
+            },
+            other if other.starts_with("--") => anyhow::bail!("Argumento desconocido: {}", other),
+            _ => positional.push(arg),
         }
     }
+
+    if !positional.is_empty() && !args.batch {
+        anyhow::bail!("Los argumentos posicionales solo se admiten con --batch");
+    }
+    let mut positional = positional.into_iter();
+    args.pattern = positional.next();
+    args.inputs = positional.map(PathBuf::from).collect();
+    Ok(args)
 }
 
-impl App {
-    fn apply_transform(&mut self) {
-        if self.regex_input.is_empty() {
-            self.output_text = self.source_text.clone();
-            return;
+/// Modo no interactivo: aplica el patrón sobre un archivo (o stdin), imprime el
+/// resultado y devuelve un código de salida compatible con grep: 0 si hubo
+/// coincidencias, 1 si no, 2 ante errores de patrón o de E/S. Con varios
+/// archivos o un directorio, busca en todos (ver [`run_batch_files`]).
+fn run_batch(args: &Args, config: &Config) -> i32 {
+    let Some(pattern) = &args.pattern else {
+        eprintln!(
+            "Uso: regex-wysiwyg --batch [--replace <texto>] [--per-line] [--output text|json] [--discard-partial] <patrón> [archivo|directorio...]"
+        );
+        return 2;
+    };
+
+    let options = EngineOptions {
+        unit: if args.per_line { ProcessingUnit::PerLine } else { ProcessingUnit::WholeText },
+        flags: config.flags,
+        size_limit: config.limits.regex_size_limit,
+    };
+    if args.inputs.len() > 1 || args.inputs.first().is_some_and(|path| path.is_dir()) {
+        return run_batch_files(args, pattern, &options);
+    }
+
+    let source = match args.inputs.first() {
+        Some(path) => std::fs::read_to_string(path),
+        None => std::io::read_to_string(std::io::stdin()),
+    };
+    let source = match source {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Error de lectura: {}", e);
+            return 2;
         }
+    };
 
-        let re = match regex::Regex::new(&self.regex_input) {
-            Ok(r) => r,
-            Err(e) => {
-                self.output_text = format!("Regex Error: {}", e);
-                return;
+    if args.json_output {
+        return run_batch_json(&source, pattern, &args.replace, &options);
+    }
+    match transform(&source, pattern, &args.replace, &options) {
+        Ok(result) => {
+            if result.output.ends_with('\n') {
+                print!("{}", result.output);
+            } else if !result.output.is_empty() {
+                println!("{}", result.output);
             }
-        };
+            if result.match_count > 0 { 0 } else { 1 }
+        }
+        Err(e) => {
+            eprintln!("Regex Error: {}", e);
+            2
+        }
+    }
+}
 
-        if self.replace_input.is_empty() {
-            // MODO FILTRO (Grep): Mostrar solo coincidencias
-            let matches: Vec<&str> = re.find_iter(&self.source_text).map(|m| m.as_str()).collect();
-            if matches.is_empty() {
-                self.output_text = "(No hay coincidencias)".to_string();
-            } else {
-                self.output_text = matches.join(" | ");
+/// Búsqueda en varios archivos: se recorren en paralelo y se listan, en el orden
+/// dado (y por nombre dentro de cada directorio), las líneas con coincidencias
+/// como `ruta:línea:texto`. Siempre trabaja por líneas; no admite `--replace` ni
+/// `--output json`. Un archivo ilegible se informa y no detiene el resto; como
+/// en grep, el código de salida es 2 si hubo algún error.
+///
+/// En una terminal, stderr muestra cuántos archivos van. Ctrl+C cancela: no se
+/// empiezan más archivos y se imprimen las coincidencias de los ya leídos, salvo
+/// con `--discard-partial`; el código de salida es entonces 130.
+fn run_batch_files(args: &Args, pattern: &str, options: &EngineOptions) -> i32 {
+    if !args.replace.is_empty() || args.json_output {
+        eprintln!("--replace y --output json solo admiten un archivo");
+        return 2;
+    }
+    let re = match compile(pattern, options) {
+        Ok(re) => re,
+        Err(e) => {
+            eprintln!("Regex Error: {}", e);
+            return 2;
+        }
+    };
+    let files = match search::expand(&args.inputs) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Error de lectura: {}", e);
+            return 2;
+        }
+    };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let on_interrupt = cancel.clone();
+    let interrupt = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            on_interrupt.store(true, Ordering::Relaxed);
+        }
+    });
+    let show_progress = std::io::stderr().is_terminal();
+    let done = AtomicUsize::new(0);
+    let results = search::search_files_cancellable(&re, &files, &cancel, || {
+        let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+        if show_progress {
+            eprint!("\r{}", progress_bar(done, files.len()));
+        }
+    });
+    interrupt.abort();
+    if show_progress {
+        eprint!("\r\x1b[K");
+    }
+    let cancelled = cancel.load(Ordering::Relaxed);
+    if cancelled {
+        eprintln!("Búsqueda cancelada: {} de {} archivos leídos", results.len(), files.len());
+        if args.discard_partial {
+            return 130;
+        }
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    let mut matched = false;
+    let mut failed = false;
+    for file in results {
+        match file.lines {
+            Ok(lines) => {
+                matched |= !lines.is_empty();
+                for line in lines {
+                    if writeln!(stdout, "{}:{}:{}", file.path.display(), line.line, line.text).is_err() {
+                        return 2;
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", file.path.display(), e);
+                failed = true;
             }
-        } else {
-            // MODO REEMPLAZO (Sed): Mostrar texto completo con cambios
-            self.output_text = re.replace_all(&self.source_text, &self.replace_input).to_string();
         }
     }
+    if cancelled {
+        130
+    } else if failed {
+        2
+    } else if matched {
+        0
+    } else {
+        1
+    }
+}
 
-    fn suggest_ai(&mut self) {
-        self.status_message = "Consultando a Gemini IA...".to_string();
-        
-        let prompt = format!(
-            "Give me ONLY the regex pattern (no text, no backticks, no markdown) to match or extract this: '{}' in the text: '{}'.",
-            self.regex_input, self.source_text
-        );
+/// Barra de avance de la búsqueda en varios archivos, para stderr.
+fn progress_bar(done: usize, total: usize) -> String {
+    const WIDTH: usize = 30;
+    let filled = (done * WIDTH).checked_div(total).unwrap_or(WIDTH);
+    format!("[{}{}] {}/{} archivos", "#".repeat(filled), "-".repeat(WIDTH - filled), done, total)
+}
 
-        let output = Command::new("cmd")
-            .arg("/C")
-            .arg("gemini")
-            .arg("-p")
-            .arg(prompt)
-            .output();
-
-        match output {
-            Ok(out) if out.status.success() => {
-                let suggestion = String::from_utf8_lossy(&out.stdout).trim().to_string();
-                if !suggestion.is_empty() {
-                    let clean = suggestion
-                        .replace("```regex", "")
-                        .replace("```", "")
-                        .replace("`", "")
-                        .trim()
-                        .to_string();
-                    self.regex_input = clean;
-                    self.status_message = "Sugerencia aplicada!".to_string();
-                    self.apply_transform();
-                } else {
-                    self.status_message = "Gemini devolvió vacío.".to_string();
-                }
+/// `--output json`: un objeto con las coincidencias (posiciones y grupos) o, con
+/// `--replace`, el texto resultante y la cantidad de cambios. Mismos códigos de
+/// salida que el modo texto; los errores también se informan como JSON.
+fn run_batch_json(source: &str, pattern: &str, replacement: &str, options: &EngineOptions) -> i32 {
+    let re = match compile(pattern, options) {
+        Ok(re) => re,
+        Err(e) => {
+            println!("{}", serde_json::json!({ "error": e.to_string() }));
+            return 2;
+        }
+    };
+    let (report, match_count) = if replacement.is_empty() {
+        let matches = server::matches_json(&re, source, options.unit);
+        let count = matches.len();
+        (serde_json::json!({ "match_count": count, "matches": matches }), count)
+    } else {
+        let result = match transform(source, pattern, replacement, options) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("{}", serde_json::json!({ "error": e.to_string() }));
+                return 2;
             }
+        };
+        (serde_json::json!({ "match_count": result.match_count, "output": result.output }), result.match_count)
+    };
+    println!("{}", report);
+    if match_count > 0 { 0 } else { 1 }
+}
+
+/// `--check`: vuelve a calcular la salida de cada sesión y la compara con su
+/// salida de referencia. Código de salida 0 si todas son idénticas, 1 si alguna
+/// cambió y 2 si alguna no se pudo comprobar (ilegible, sin referencia o con un
+/// patrón inválido).
+fn run_check(paths: &[PathBuf], config: &Config) -> i32 {
+    let mut drifted = false;
+    let mut failed = false;
+    for path in paths {
+        let session = match Session::load(path) {
+            Ok(session) => session,
             Err(e) => {
-                self.status_message = format!("Error de ejecución: {}", e);
+                eprintln!("{}: {:#}", path.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+        match session.check_golden(config.limits.regex_size_limit) {
+            Ok(None) => println!("{}: ok", path.display()),
+            Ok(Some(drift)) => {
+                println!("{}: {}", path.display(), i18n::golden_drift(i18n::detect(config.language), &drift));
+                drifted = true;
             }
-            Ok(out) => {
-                let err_msg = String::from_utf8_lossy(&out.stderr);
-                self.status_message = format!("Gemini Error: {}", err_msg.chars().take(30).collect::<String>());
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                failed = true;
             }
         }
     }
+    if failed {
+        2
+    } else if drifted {
+        1
+    } else {
+        0
+    }
 }
 
-fn main() -> Result<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+/// `--debug`: registra eventos, tiempos y metadatos de las consultas a la IA en
+/// `debug.log` (en el directorio de caché), para adjuntarlo a un reporte de
+/// error. Se sobrescribe en cada ejecución; nunca incluye la API key ni el texto
+/// de las respuestas.
+fn init_debug_log() -> Result<PathBuf> {
+    let path = paths::cache_file("debug.log").ok_or_else(|| anyhow::anyhow!("Sin directorio de caché para el log"))?;
+    let file = std::fs::File::create(&path)?;
+    tracing_subscriber::fmt()
+        .with_writer(std::sync::Mutex::new(file))
+        .with_ansi(false)
+        .with_max_level(tracing::Level::DEBUG)
+        .init();
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), profile = paths::profile(), "inicio");
+    Ok(path)
+}
 
-    let mut app = App::default();
-    app.apply_transform(); 
-    let res = run_app(&mut terminal, &mut app);
+/// Modo crudo, pantalla alternativa y captura del mouse mientras viva; al
+/// soltarse (también si se sale con `?`) deja la terminal como estaba.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> std::io::Result<TerminalGuard> {
+        enable_raw_mode()?;
+        let guard = TerminalGuard;
+        execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Deshace lo que hace `TerminalGuard::enter`; ignora errores porque se llama
+/// también desde el hook de pánico.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Show);
+}
+
+/// Ante un pánico, restaura la terminal antes de imprimirlo: si no, el mensaje
+/// queda en la pantalla alternativa y la shell sigue en modo crudo.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        tracing::error!(%info, "pánico");
+        default_hook(info);
+    }));
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(profile) = &args.profile
+        && let Err(e) = paths::set_profile(profile)
+    {
+        eprintln!("Error: {}", e);
+        std::process::exit(2);
+    }
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(2);
+        }
+    };
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    if args.serve {
+        if let Err(e) = server::serve(&config) {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(2);
+        }
+        return Ok(());
+    }
+    if !args.check.is_empty() {
+        std::process::exit(run_check(&args.check, &config));
+    }
+    if args.batch {
+        std::process::exit(run_batch(&args, &config));
+    }
+
+    let first_run = paths::first_run();
+    paths::ensure_dirs()?;
+    let debug_log = if args.debug { Some(init_debug_log()?) } else { None };
+    let restore_last = config.session.restore_last;
+    let mut app = App::new(config)?;
+    app.log_path = args.log_path;
+    app.session_path = args.session_path;
+    let startup_session = match &app.session_path {
+        Some(path) if path.exists() => Some(path.clone()),
+        Some(_) => None,
+        None if restore_last => Session::last_session_path().filter(|path| path.exists()),
+        None => None,
+    };
+    if let Some(path) = startup_session {
+        app.restore_session(Session::load(&path)?);
+    }
+    if let Some(path) = args.project_path {
+        app.open_project(OpenProject::open(path)?);
+    }
+    if let Some(path) = args.file_path {
+        app.open_file(path);
+    }
+    if Session::autosave_path().is_some_and(|path| path.exists()) {
+        app.ask(
+            Question::RestoreAutosave,
+            i18n::tr(app.language, i18n::Text::Recovery),
+            i18n::tr(app.language, i18n::Text::RecoveryPrompt).to_string(),
+        );
+    }
+
+    // La bienvenida no tapa una carga ni una pregunta pendiente.
+    if first_run && app.input_mode == InputMode::Normal {
+        app.start_tour();
+    }
+
+    install_panic_hook();
+    let guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+
+    app.apply_transform(); 
+    let res = run_app(&mut terminal, &mut app).await;
+    drop(guard);
 
     if let Err(err) = res {
+        tracing::error!(%err, "la interfaz terminó con error");
         eprintln!("Error: {}", err);
     }
+    if let Some(path) = debug_log {
+        eprintln!("Diagnóstico guardado en {}", path.display());
+    }
+
+    if let Some(path) = Session::last_session_path()
+        && let Err(err) = app.to_session().save(&path)
+    {
+        eprintln!("Error al guardar la última sesión: {}", err);
+    }
+    // Salida limpia: el autoguardado de recuperación ya no hace falta.
+    if let Some(path) = Session::autosave_path() {
+        let _ = std::fs::remove_file(path);
+    }
 
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
+async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> std::io::Result<()> {
+    let mut events = EventStream::new();
     loop {
-        terminal.draw(|f| ui(f, app)).map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
-
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
-                }
+        app.transform_tick();
+        app.receive_transform();
+        app.autosave_tick();
+        app.config_reload_tick();
+        app.toast_tick();
+        app.debug_tick();
+        if app.dirty {
+            terminal.draw(|f| ui(f, app)).map_err(|e| std::io::Error::other(e.to_string()))?;
+            app.dirty = false;
+        }
 
-                match app.input_mode {
-                    InputMode::Normal => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('s') => {
-                            app.input_mode = InputMode::EditingSource;
-                            app.source_text.clear();
-                        }
-                        KeyCode::Char('r') => {
-                            app.input_mode = InputMode::EditingRegex;
-                            app.regex_input.clear();
-                        }
-                        KeyCode::Char('t') => {
-                            app.input_mode = InputMode::EditingReplace;
-                            app.replace_input.clear();
-                        }
-                        KeyCode::Tab => {
-                            app.suggest_ai();
+        let timeout = app.poll_timeout();
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    if let Some(msg) = Msg::from_key(&app.keymap, &app.input_mode, &key) {
+                        if app.input_mode == InputMode::EnteringApiKey {
+                            tracing::debug!(mode = ?app.input_mode, "tecla (oculta)");
+                        } else {
+                            tracing::debug!(?msg, mode = ?app.input_mode, "tecla");
                         }
-                        _ => {}
-                    },
-                    InputMode::EditingSource => match key.code {
-                        KeyCode::Esc => app.input_mode = InputMode::Normal,
-                        KeyCode::Char(c) => app.source_text.push(c),
-                        KeyCode::Backspace => { app.source_text.pop(); },
-                        KeyCode::Enter => app.source_text.push('\n'),
-                        _ => {}
-                    },
-                    InputMode::EditingRegex => match key.code {
-                        KeyCode::Esc => app.input_mode = InputMode::Normal,
-                        KeyCode::Char(c) => app.regex_input.push(c),
-                        KeyCode::Backspace => { app.regex_input.pop(); },
-                        KeyCode::Enter => app.input_mode = InputMode::Normal,
-                        _ => {}
-                    },
-                    InputMode::EditingReplace => match key.code {
-                        KeyCode::Esc => app.input_mode = InputMode::Normal,
-                        KeyCode::Char(c) => app.replace_input.push(c),
-                        KeyCode::Backspace => { app.replace_input.pop(); },
-                        KeyCode::Enter => app.input_mode = InputMode::Normal,
-                        _ => {}
-                    },
+                        update(app, msg);
+                        app.dirty = true;
+                    }
+                    if app.quit {
+                        return Ok(());
+                    }
                 }
-                app.apply_transform();
-            }
+                Some(Ok(Event::Mouse(mouse))) => {
+                    let screen = terminal.size().map_err(|e| std::io::Error::other(e.to_string()))?;
+                    let screen = Rect::new(0, 0, screen.width, screen.height);
+                    if let Some(msg) = Msg::from_mouse(app, screen, &mouse) {
+                        update(app, msg);
+                        app.dirty = true;
+                    }
+                }
+                Some(Ok(Event::Resize(..))) => app.dirty = true,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()),
+            },
+            Some(finished) = app.finished.recv() => app.handle_background(finished),
+            _ = tokio::time::sleep(timeout) => {}
         }
     }
 }
 
-fn ui(f: &mut Frame, app: &App) {
-    let area = f.area();
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Length(3), // Title
-                Constraint::Min(4),    // Source
-                Constraint::Length(3), // Regex
-                Constraint::Length(3), // Replace
-                Constraint::Min(4),    // Output
-                Constraint::Length(3), // Help
-            ]
-            .as_ref(),
-        )
-        .split(area);
-
-    let mode_name = match app.input_mode {
-        InputMode::Normal => "EXPLORAR",
-        InputMode::EditingSource => "EDITANDO FUENTE",
-        InputMode::EditingRegex => "EDITANDO REGEX",
-        InputMode::EditingReplace => "EDITANDO REEMPLAZO",
-    };
-
-    let title = Paragraph::new(format!(" REGEX WYSIWYG - MODO: {} ", mode_name))
-        .style(Style::default().fg(Color::LightCyan).add_modifier(Modifier::BOLD))
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(title, chunks[0]);
-
-    let source_style = if app.input_mode == InputMode::EditingSource { Style::default().fg(Color::Yellow) } else { Style::default() };
-    f.render_widget(
-        Paragraph::new(app.source_text.as_str())
-            .style(source_style)
-            .wrap(Wrap { trim: true })
-            .block(Block::default().borders(Borders::ALL).title(" [Source Text] ('s') ")),
-        chunks[1]
-    );
-
-    let regex_style = if app.input_mode == InputMode::EditingRegex { Style::default().fg(Color::Magenta) } else { Style::default() };
-    f.render_widget(
-        Paragraph::new(app.regex_input.as_str())
-            .style(regex_style)
-            .block(Block::default().borders(Borders::ALL).title(" [Regex Pattern] ('r') ")),
-        chunks[2]
-    );
-
-    let replace_style = if app.input_mode == InputMode::EditingReplace { Style::default().fg(Color::LightBlue) } else { Style::default() };
-    f.render_widget(
-        Paragraph::new(app.replace_input.as_str())
-            .style(replace_style)
-            .block(Block::default().borders(Borders::ALL).title(" [Replace With] ('t' - sed mode) ")),
-        chunks[3]
-    );
-
-    f.render_widget(
-        Paragraph::new(app.output_text.as_str())
-            .wrap(Wrap { trim: true })
-            .style(Style::default().fg(Color::Green))
-            .block(Block::default().borders(Borders::ALL).title(" [Output Preview] ")),
-        chunks[4]
-    );
-
-    let help_text = match app.input_mode {
-        InputMode::Normal => format!("{} | q: Salir", app.status_message),
-        _ => "Esc: Confirmar edición".to_string(),
-    };
-    f.render_widget(
-        Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Gray))
-            .block(Block::default().borders(Borders::ALL)),
-        chunks[5]
-    );
-}
@@ -0,0 +1,152 @@
+//! Textos que casi coinciden con un patrón: se arma un ejemplo que coincide
+//! completo y se le cambia, quita o agrega un carácter. Los que siguen
+//! coincidiendo delatan un patrón demasiado permisivo en ese punto; los que no,
+//! muestran dónde está el borde.
+
+use regex::Regex;
+use regex_syntax::hir::{Class, Hir, HirKind};
+
+use crate::EngineOptions;
+
+/// Caracteres preferidos para representar una clase en el ejemplo, en orden.
+const PREFERRED: [char; 6] = ['a', '0', 'A', '_', '-', ' '];
+
+/// Largo máximo del ejemplo: las repeticiones largas se acortan a su mínimo.
+const MAX_EXAMPLE: usize = 200;
+
+/// Variante de un ejemplo con un carácter de diferencia.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearMiss {
+    pub text: String,
+    /// Qué se cambió respecto del ejemplo.
+    pub change: String,
+    /// El patrón la acepta completa.
+    pub matches: bool,
+}
+
+/// Un texto que `pattern` acepta completo, o `None` si no se encontró uno
+/// (por ejemplo, por aserciones como `\b` que el ejemplo no cumple).
+pub fn example(pattern: &str, options: &EngineOptions) -> Option<String> {
+    let hir = parse(pattern, options).ok()?;
+    let mut text = String::new();
+    build(&hir, &mut text);
+    let anchored = full_match(pattern, options).ok()?;
+    (text.len() <= MAX_EXAMPLE && anchored.is_match(&text)).then_some(text)
+}
+
+/// El ejemplo de `pattern` y hasta `limit` variantes, o el error si el patrón
+/// no compila. Sin ejemplo no hay variantes.
+pub fn near_misses(pattern: &str, options: &EngineOptions, limit: usize) -> Result<Option<(String, Vec<NearMiss>)>, String> {
+    let anchored = full_match(pattern, options).map_err(|e| e.to_string())?;
+    let Some(example) = example(pattern, options) else {
+        return Ok(None);
+    };
+    let chars: Vec<char> = example.chars().collect();
+    let mut variants: Vec<(String, String)> = Vec::new();
+    for (index, &c) in chars.iter().enumerate() {
+        let other = different(c);
+        variants.push((replace_at(&chars, index, Some(other)), format!("'{}' por '{}' en la posición {}", c, other, index + 1)));
+        variants.push((replace_at(&chars, index, None), format!("sin '{}' de la posición {}", c, index + 1)));
+    }
+    if let (Some(&first), Some(&last)) = (chars.first(), chars.last()) {
+        variants.push((format!("{}{}", first, example), format!("'{}' repetido al principio", first)));
+        variants.push((format!("{}{}", example, last), format!("'{}' repetido al final", last)));
+    }
+    variants.push((format!("{}x", example), "'x' agregada al final".to_string()));
+
+    let mut seen = vec![example.clone()];
+    let misses = variants
+        .into_iter()
+        .filter(|(text, _)| {
+            let new = !seen.contains(text);
+            seen.push(text.clone());
+            new
+        })
+        .take(limit)
+        .map(|(text, change)| NearMiss { matches: anchored.is_match(&text), text, change })
+        .collect();
+    Ok(Some((example, misses)))
+}
+
+pub(crate) fn parse(pattern: &str, options: &EngineOptions) -> Result<Hir, Box<regex_syntax::Error>> {
+    regex_syntax::ParserBuilder::new()
+        .case_insensitive(options.flags.case_insensitive)
+        .multi_line(options.flags.multi_line)
+        .dot_matches_new_line(options.flags.dot_matches_new_line)
+        .ignore_whitespace(options.flags.ignore_whitespace)
+        .build()
+        .parse(pattern)
+        .map_err(Box::new)
+}
+
+/// `pattern` anclado para aceptar solo textos que coinciden completos.
+fn full_match(pattern: &str, options: &EngineOptions) -> Result<Regex, regex::Error> {
+    crate::compile(&format!(r"\A(?:{})\z", pattern), options)
+}
+
+/// Agrega a `text` lo más corto y simple que acepta `hir`: la primera
+/// alternativa, el mínimo de cada repetición (al menos una vez si se puede) y
+/// un carácter representativo de cada clase.
+pub(crate) fn build(hir: &Hir, text: &mut String) {
+    if text.len() > MAX_EXAMPLE {
+        return;
+    }
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => text.push_str(&String::from_utf8_lossy(&literal.0)),
+        HirKind::Class(class) => {
+            if let Some(c) = representative(class) {
+                text.push(c);
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            let times = match (repetition.min, repetition.max) {
+                (0, Some(0)) => 0,
+                (0, _) => 1,
+                (min, _) => min,
+            };
+            for _ in 0..times {
+                build(&repetition.sub, text);
+                if text.len() > MAX_EXAMPLE {
+                    return;
+                }
+            }
+        }
+        HirKind::Capture(capture) => build(&capture.sub, text),
+        HirKind::Concat(items) => items.iter().for_each(|item| build(item, text)),
+        HirKind::Alternation(branches) => {
+            if let Some(first) = branches.first() {
+                build(first, text);
+            }
+        }
+    }
+}
+
+fn representative(class: &Class) -> Option<char> {
+    match class {
+        Class::Unicode(class) => {
+            let contains = |c: char| class.ranges().iter().any(|range| range.start() <= c && c <= range.end());
+            PREFERRED.into_iter().find(|&c| contains(c)).or_else(|| class.ranges().first().map(|range| range.start()))
+        }
+        Class::Bytes(class) => {
+            let contains = |c: char| class.ranges().iter().any(|range| range.start() as char <= c && c <= range.end() as char);
+            PREFERRED
+                .into_iter()
+                .find(|&c| contains(c))
+                .or_else(|| class.ranges().first().map(|range| range.start()).filter(u8::is_ascii).map(char::from))
+        }
+    }
+}
+
+/// Un carácter de otra clase: un dígito en lugar de una letra y una letra en
+/// lugar de cualquier otra cosa.
+pub(crate) fn different(c: char) -> char {
+    if c.is_alphabetic() { '0' } else { 'a' }
+}
+
+fn replace_at(chars: &[char], index: usize, with: Option<char>) -> String {
+    let mut text: String = chars[..index].iter().collect();
+    text.extend(with);
+    text.extend(&chars[index + 1..]);
+    text
+}
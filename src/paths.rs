@@ -0,0 +1,73 @@
+//! Directorios de la aplicación según la plataforma (XDG en Linux, `Library/`
+//! en macOS, `AppData` en Windows), resueltos con `directories`:
+//!
+//! - configuración: `config.toml`
+//! - datos: historial, biblioteca, recientes y última sesión
+//! - caché: autoguardado de recuperación
+//!
+//! Con un perfil activo (`--profile trabajo`) los tres directorios pasan a
+//! `profiles/trabajo/` dentro de cada uno, de modo que configuración, biblioteca
+//! e historial de cada perfil quedan separados.
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "regex-wysiwyg")
+}
+
+/// Activa un perfil para el resto de la ejecución. Solo puede llamarse una vez,
+/// antes de resolver cualquier ruta.
+pub fn set_profile(name: &str) -> Result<()> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        anyhow::bail!("Nombre de perfil inválido: '{}' (usar letras, números, '-' o '_')", name);
+    }
+    PROFILE
+        .set(name.to_string())
+        .map_err(|_| anyhow::anyhow!("El perfil ya estaba definido"))
+}
+
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+fn scoped(dir: &Path) -> PathBuf {
+    match profile() {
+        Some(name) => dir.join("profiles").join(name),
+        None => dir.to_path_buf(),
+    }
+}
+
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    project_dirs().map(|dirs| scoped(dirs.config_dir()).join(name))
+}
+
+pub fn data_file(name: &str) -> Option<PathBuf> {
+    project_dirs().map(|dirs| scoped(dirs.data_dir()).join(name))
+}
+
+pub fn cache_file(name: &str) -> Option<PathBuf> {
+    project_dirs().map(|dirs| scoped(dirs.cache_dir()).join(name))
+}
+
+/// Primera ejecución (del perfil activo): todavía no hay directorio de
+/// configuración ni de datos. Hay que consultarlo antes de `ensure_dirs`.
+pub fn first_run() -> bool {
+    project_dirs().is_some_and(|dirs| !scoped(dirs.config_dir()).exists() && !scoped(dirs.data_dir()).exists())
+}
+
+/// Crea los directorios de configuración, datos y caché si todavía no existen.
+pub fn ensure_dirs() -> Result<()> {
+    let Some(dirs) = project_dirs() else {
+        return Ok(());
+    };
+    for dir in [dirs.config_dir(), dirs.data_dir(), dirs.cache_dir()] {
+        let dir = scoped(dir);
+        std::fs::create_dir_all(&dir).with_context(|| format!("No se pudo crear {}", dir.display()))?;
+    }
+    Ok(())
+}
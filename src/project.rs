@@ -0,0 +1,87 @@
+//! Archivos de proyecto (`.rwproj`): un TOML que agrupa textos de muestra,
+//! patrones guardados y casos de prueba, para compartir un espacio de trabajo
+//! reproducible sobre un formato concreto (p. ej. un tipo de log).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::library::LibraryEntry;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Project {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub samples: Vec<Sample>,
+    #[serde(default)]
+    pub patterns: Vec<LibraryEntry>,
+    #[serde(default)]
+    pub tests: Vec<TestCase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sample {
+    pub name: String,
+    pub text: String,
+}
+
+/// Texto que el patrón debe (o no debe) encontrar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCase {
+    pub text: String,
+    pub should_match: bool,
+}
+
+impl Project {
+    pub fn load(path: &Path) -> Result<Project> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("No se pudo leer {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("Proyecto inválido en {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = toml::to_string_pretty(self)?;
+        std::fs::write(path, text).with_context(|| format!("No se pudo escribir {}", path.display()))
+    }
+}
+
+/// Proyecto abierto en la sesión actual, con la muestra visible.
+#[derive(Debug)]
+pub struct OpenProject {
+    pub path: PathBuf,
+    pub project: Project,
+    pub sample_index: usize,
+}
+
+impl OpenProject {
+    /// Abre el proyecto en `path`, o crea uno vacío (nombrado como el archivo) si
+    /// todavía no existe; se escribirá al guardar el primer patrón.
+    pub fn open(path: PathBuf) -> Result<OpenProject> {
+        let project = if path.exists() {
+            Project::load(&path)?
+        } else {
+            Project {
+                name: path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                ..Project::default()
+            }
+        };
+        Ok(OpenProject { path, project, sample_index: 0 })
+    }
+
+    pub fn current_sample(&self) -> Option<&Sample> {
+        self.project.samples.get(self.sample_index)
+    }
+
+    /// Avanza a la siguiente muestra (circular).
+    pub fn next_sample(&mut self) -> Option<&Sample> {
+        if self.project.samples.is_empty() {
+            return None;
+        }
+        self.sample_index = (self.sample_index + 1) % self.project.samples.len();
+        self.current_sample()
+    }
+}
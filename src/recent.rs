@@ -0,0 +1,56 @@
+//! Lista de archivos fuente abiertos recientemente (el más reciente primero),
+//! guardada como JSON en el directorio de datos de la plataforma.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+const MAX_RECENT: usize = 20;
+
+#[derive(Debug, Default)]
+pub struct RecentFiles {
+    files: Vec<PathBuf>,
+    /// `None` deja la lista solo en memoria (sin directorio de datos).
+    path: Option<PathBuf>,
+}
+
+impl RecentFiles {
+    pub fn default_path() -> Option<PathBuf> {
+        crate::paths::data_file("recent.json")
+    }
+
+    pub fn load() -> Result<RecentFiles> {
+        let Some(path) = RecentFiles::default_path() else {
+            return Ok(RecentFiles::default());
+        };
+        if !path.exists() {
+            return Ok(RecentFiles { files: Vec::new(), path: Some(path) });
+        }
+
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("No se pudo leer {}", path.display()))?;
+        let files = serde_json::from_str(&text)
+            .with_context(|| format!("Lista de recientes inválida en {}", path.display()))?;
+        Ok(RecentFiles { files, path: Some(path) })
+    }
+
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Mueve `file` al principio de la lista (sin duplicados) y la persiste.
+    pub fn touch(&mut self, file: &Path) -> Result<()> {
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        self.files.retain(|existing| *existing != file);
+        self.files.insert(0, file);
+        self.files.truncate(MAX_RECENT);
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(&self.files)?;
+        std::fs::write(path, text).with_context(|| format!("No se pudo escribir {}", path.display()))
+    }
+}
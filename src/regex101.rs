@@ -0,0 +1,85 @@
+//! Importación de exportaciones JSON de regex101 (patrón, flags, texto de prueba y
+//! sustitución), traduciendo flags y referencias a grupos a la sintaxis local.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::RegexFlags;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Export {
+    regex: String,
+    #[serde(default)]
+    flags: String,
+    #[serde(default)]
+    flavor: String,
+    #[serde(default)]
+    test_string: String,
+    #[serde(default)]
+    substitution: String,
+    #[serde(default)]
+    unit_tests: Vec<serde_json::Value>,
+}
+
+/// Estado listo para cargar en la aplicación, con avisos de lo que no se pudo
+/// trasladar tal cual.
+pub struct Imported {
+    pub pattern: String,
+    pub replacement: String,
+    pub flags: RegexFlags,
+    pub source: String,
+    pub warnings: Vec<String>,
+}
+
+pub fn load(path: &Path) -> Result<Imported> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("No se pudo leer {}", path.display()))?;
+    let export: Export = serde_json::from_str(&text)
+        .with_context(|| format!("Exportación de regex101 inválida en {}", path.display()))?;
+
+    let mut warnings = Vec::new();
+    if !export.flavor.is_empty() && export.flavor != "rust" {
+        warnings.push(format!("sabor {}: aquí no hay lookaround ni backreferences", export.flavor));
+    }
+    let mut flags = RegexFlags::default();
+    for flag in export.flags.chars() {
+        match flag {
+            'i' => flags.case_insensitive = true,
+            'm' => flags.multi_line = true,
+            's' => flags.dot_matches_new_line = true,
+            'x' => flags.ignore_whitespace = true,
+            'g' | 'u' => {}
+            other => warnings.push(format!("flag '{}' sin equivalente", other)),
+        }
+    }
+    if !export.unit_tests.is_empty() {
+        warnings.push(format!("{} tests de regex101 no importados", export.unit_tests.len()));
+    }
+
+    Ok(Imported {
+        pattern: export.regex,
+        replacement: translate_substitution(&export.substitution),
+        flags,
+        source: export.test_string,
+        warnings,
+    })
+}
+
+/// `\1` y `$1` de PCRE pasan a `${1}`; `${nombre}` y `$nombre` ya son válidos.
+fn translate_substitution(substitution: &str) -> String {
+    let mut out = String::new();
+    let mut chars = substitution.chars().peekable();
+    while let Some(c) = chars.next() {
+        if (c == '\\' || c == '$') && chars.peek().is_some_and(char::is_ascii_digit) {
+            let mut number = String::new();
+            while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                number.push(d);
+            }
+            out.push_str(&format!("${{{}}}", number));
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
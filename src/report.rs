@@ -0,0 +1,177 @@
+//! Informe HTML autocontenido (sin recursos externos) del estado actual: patrón
+//! con su explicación, texto fuente con las coincidencias resaltadas, tabla de
+//! capturas y, si hay reemplazo, el resultado.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::ProcessingUnit;
+use crate::config::Language;
+use crate::export::ExportInput;
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem}\
+pre{background:#f6f8fa;padding:1rem;overflow:auto;white-space:pre-wrap}\
+mark{background:#ffe08a;border-radius:2px}\
+table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:.25rem .5rem;font-family:monospace}";
+
+fn pick(language: Language, es: &'static str, en: &'static str) -> &'static str {
+    match language {
+        Language::Es => es,
+        Language::En => en,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Texto con cada coincidencia de `re` envuelta en `<mark>`.
+fn highlight(re: &regex::Regex, text: &str) -> String {
+    let mut html = String::new();
+    let mut last = 0;
+    for m in re.find_iter(text) {
+        html.push_str(&escape(&text[last..m.start()]));
+        html.push_str(&format!("<mark>{}</mark>", escape(m.as_str())));
+        last = m.end();
+    }
+    html.push_str(&escape(&text[last..]));
+    html
+}
+
+/// Informe en el idioma de `input.language`.
+pub fn html(input: &ExportInput) -> String {
+    let language = input.language;
+    let flags = input.options.flags.summary();
+    let mut body = format!(
+        "<h1>{}</h1>\n<h2>{}</h2>\n<pre>/{}/{}</pre>\n",
+        pick(language, "Informe de regex", "Regex report"),
+        pick(language, "Patrón", "Pattern"),
+        escape(input.pattern),
+        flags
+    );
+    let source_heading = pick(language, "Texto fuente", "Source text");
+    if input.pattern.is_empty() {
+        body.push_str(&format!("<h2>{}</h2>\n<pre>{}</pre>\n", source_heading, escape(input.source)));
+        return page(language, &body);
+    }
+    if let Ok(lines) = crate::explain::explain(input.pattern, &input.options.flags) {
+        body.push_str(&format!("<pre>{}</pre>\n", escape(&lines.join("\n"))));
+    }
+    let re = match crate::compile(input.pattern, &input.options) {
+        Ok(re) => re,
+        Err(e) => {
+            body.push_str(&format!(
+                "<p>{}: {}</p>\n",
+                pick(language, "Error de regex", "Regex Error"),
+                escape(&e.to_string())
+            ));
+            return page(language, &body);
+        }
+    };
+
+    let highlighted = match input.options.unit {
+        ProcessingUnit::WholeText => highlight(&re, input.source),
+        ProcessingUnit::PerLine => {
+            crate::split_lines(input.source).map(|(line, ending)| format!("{}{}", highlight(&re, line), ending)).collect()
+        }
+    };
+    body.push_str(&format!("<h2>{}</h2>\n<pre>{}</pre>\n", source_heading, highlighted));
+
+    let mut header = format!("<tr><th>#</th><th>{}</th>", pick(language, "Coincidencia", "Match"));
+    for (index, name) in re.capture_names().enumerate().skip(1) {
+        header.push_str(&format!("<th>{}</th>", escape(&name.map(str::to_string).unwrap_or_else(|| index.to_string()))));
+    }
+    header.push_str("</tr>\n");
+    let texts: Vec<&str> = match input.options.unit {
+        ProcessingUnit::WholeText => vec![input.source],
+        ProcessingUnit::PerLine => crate::split_lines(input.source).map(|(line, _)| line).collect(),
+    };
+    let mut rows = String::new();
+    let mut count = 0;
+    for text in texts {
+        for caps in re.captures_iter(text) {
+            count += 1;
+            rows.push_str(&format!("<tr><td>{}</td>", count));
+            for group in caps.iter() {
+                rows.push_str(&format!("<td>{}</td>", escape(group.map_or("", |m| m.as_str()))));
+            }
+            rows.push_str("</tr>\n");
+        }
+    }
+    body.push_str(&format!(
+        "<h2>{} ({})</h2>\n<table>\n{}{}</table>\n",
+        pick(language, "Capturas", "Captures"),
+        count,
+        header,
+        rows
+    ));
+
+    if !input.replacement.is_empty()
+        && let Ok(result) = crate::transform(input.source, input.pattern, input.replacement, &input.options)
+    {
+        body.push_str(&format!(
+            "<h2>{}</h2>\n<pre>{}</pre>\n<h2>{}</h2>\n<pre>{}</pre>\n",
+            pick(language, "Reemplazo", "Replacement"),
+            escape(input.replacement),
+            pick(language, "Resultado", "Result"),
+            escape(&result.output)
+        ));
+    }
+    page(language, &body)
+}
+
+fn page(language: Language, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}<p><small>{} {}</small></p>\n</body>\n</html>\n",
+        pick(language, "es", "en"),
+        pick(language, "Informe de regex", "Regex report"),
+        STYLE,
+        body,
+        pick(language, "Generado por regex-wysiwyg el", "Generated by regex-wysiwyg on"),
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    )
+}
+
+pub fn write(path: &Path, input: &ExportInput) -> Result<()> {
+    std::fs::write(path, html(input)).with_context(|| format!("No se pudo escribir {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EngineOptions, RegexFlags};
+
+    fn input<'a>(pattern: &'a str, replacement: &'a str, source: &'a str, language: Language) -> ExportInput<'a> {
+        ExportInput {
+            pattern,
+            replacement,
+            options: EngineOptions { unit: ProcessingUnit::PerLine, flags: RegexFlags::default(), size_limit: 1 << 20 },
+            source,
+            source_path: None,
+            language,
+        }
+    }
+
+    #[test]
+    fn html_escapes_user_text_and_keeps_line_endings() {
+        let report = html(&input("<(?P<tag>script)>&", "&<$tag>", "a <script>& b\r\n<script>&\n", Language::En));
+        assert!(!report.contains("<script"));
+        assert!(report.contains("<pre>/&lt;(?P&lt;tag&gt;script)&gt;&amp;/-</pre>"));
+        assert!(report.contains(
+            "<h2>Source text</h2>\n<pre>a <mark>&lt;script&gt;&amp;</mark> b\r\n<mark>&lt;script&gt;&amp;</mark>\n</pre>"
+        ));
+        assert!(report.contains("<tr><th>#</th><th>Match</th><th>tag</th></tr>"));
+        assert!(report.contains("<td>&lt;script&gt;&amp;</td><td>script</td>"));
+        assert!(report.contains("<h2>Replacement</h2>\n<pre>&amp;&lt;$tag&gt;</pre>"));
+        assert!(report.contains("<pre>a &amp;&lt;script&gt; b\r\n&amp;&lt;script&gt;\n</pre>"));
+        assert!(report.contains("<html lang=\"en\">"));
+    }
+
+    #[test]
+    fn html_reports_errors_in_the_chosen_language() {
+        let report = html(&input("(<b>", "", "<b>", Language::Es));
+        assert!(report.contains("<p>Error de regex: "));
+        assert!(report.contains("<h2>Patrón</h2>\n<pre>/(&lt;b&gt;/-</pre>"));
+        assert!(report.contains("<html lang=\"es\">"));
+    }
+}
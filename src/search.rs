@@ -0,0 +1,102 @@
+//! Búsqueda en varios archivos a la vez: cada archivo se lee y se recorre en un
+//! hilo del pool de rayon, y los resultados se agrupan por archivo en el mismo
+//! orden en que se pidieron, sin importar cuál terminó antes.
+
+use rayon::prelude::*;
+use regex::Regex;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Una línea con al menos una coincidencia.
+pub struct LineMatch {
+    /// Número de línea, desde 1.
+    pub line: usize,
+    pub text: String,
+    pub match_count: usize,
+}
+
+/// Resultado de un archivo: sus líneas con coincidencias o el error de lectura.
+/// Los archivos binarios (que no son UTF-8) se dan por vacíos, como hace grep
+/// con `-I`.
+pub struct FileMatches {
+    pub path: PathBuf,
+    pub lines: io::Result<Vec<LineMatch>>,
+}
+
+impl FileMatches {
+    pub fn match_count(&self) -> usize {
+        self.lines.as_ref().map_or(0, |lines| lines.iter().map(|line| line.match_count).sum())
+    }
+}
+
+/// Archivos bajo `paths`: los directorios se recorren recursivamente, omitiendo
+/// entradas ocultas, y su contenido se ordena por nombre para que el resultado
+/// sea estable entre ejecuciones.
+pub fn expand(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        collect(path, &mut files)?;
+    }
+    Ok(files)
+}
+
+fn collect(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| !path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with('.')))
+        .collect();
+    entries.sort();
+    for entry in entries {
+        collect(&entry, files)?;
+    }
+    Ok(())
+}
+
+/// Busca `re` línea por línea en cada archivo, en paralelo.
+pub fn search_files(re: &Regex, paths: &[PathBuf]) -> Vec<FileMatches> {
+    search_files_cancellable(re, paths, &AtomicBool::new(false), || {})
+}
+
+/// Como [`search_files`], pero avisa a `progress` cada vez que termina un
+/// archivo y, en cuanto se activa `cancel`, no empieza ninguno más. Los
+/// archivos que no llegaron a leerse quedan fuera del resultado; los demás
+/// conservan el orden pedido.
+pub fn search_files_cancellable(
+    re: &Regex,
+    paths: &[PathBuf],
+    cancel: &AtomicBool,
+    progress: impl Fn() + Sync,
+) -> Vec<FileMatches> {
+    paths
+        .par_iter()
+        .filter_map(|path| {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            let lines = search_file(re, path);
+            progress();
+            Some(FileMatches { path: path.clone(), lines })
+        })
+        .collect()
+}
+
+fn search_file(re: &Regex, path: &Path) -> io::Result<Vec<LineMatch>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(text
+        .lines()
+        .enumerate()
+        .filter_map(|(n, line)| {
+            let match_count = re.find_iter(line).count();
+            (match_count > 0).then(|| LineMatch { line: n + 1, text: line.to_string(), match_count })
+        })
+        .collect())
+}
@@ -0,0 +1,30 @@
+//! API keys de los proveedores de IA, guardadas en el llavero del sistema
+//! operativo (Keychain, Credential Manager o Secret Service) en lugar de texto
+//! plano en la configuración. Cada perfil guarda sus propias keys.
+
+use anyhow::{Context, Result};
+
+const SERVICE: &str = "regex-wysiwyg";
+
+fn entry(provider: &str) -> Result<keyring::Entry> {
+    let account = match crate::paths::profile() {
+        Some(profile) => format!("{}/{}", profile, provider),
+        None => provider.to_string(),
+    };
+    keyring::Entry::new(SERVICE, &account).context("No se pudo acceder al llavero del sistema")
+}
+
+/// API key guardada para `provider`, o `None` si todavía no se configuró.
+pub fn api_key(provider: &str) -> Result<Option<String>> {
+    match entry(provider)?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).context("No se pudo leer la API key del llavero"),
+    }
+}
+
+pub fn set_api_key(provider: &str, key: &str) -> Result<()> {
+    entry(provider)?
+        .set_password(key)
+        .context("No se pudo guardar la API key en el llavero")
+}
@@ -0,0 +1,160 @@
+//! Modo servidor (`--serve`): JSON-RPC 2.0 por stdin/stdout, una petición y una
+//! respuesta por línea, para que editores y otras herramientas usen exactamente
+//! el mismo motor que la interfaz.
+//!
+//! Métodos (todos reciben `pattern` y opcionalmente `flags` y `per_line`):
+//! - `compile`: valida el patrón y describe sus grupos.
+//! - `match`: coincidencias sobre `text`, con posiciones y grupos.
+//! - `replace`: aplica `replacement` sobre `text`.
+//! - `explain`: explicación del patrón, una línea por construcción.
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::io::{self, BufRead, Write};
+
+use crate::config::Config;
+use crate::{EngineOptions, ProcessingUnit, RegexFlags};
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct Params {
+    pattern: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    replacement: String,
+    flags: Option<RegexFlags>,
+    #[serde(default)]
+    per_line: bool,
+}
+
+/// Error JSON-RPC: código y mensaje.
+struct RpcError(i64, String);
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const PATTERN_ERROR: i64 = -32000;
+
+/// Atiende peticiones hasta que se cierra la entrada estándar.
+pub fn serve(config: &Config) -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => match handle(config, &request.method, request.params) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+                Err(RpcError(code, message)) => error_response(request.id, code, message),
+            },
+            Err(e) => error_response(Value::Null, PARSE_ERROR, e.to_string()),
+        };
+        writeln!(stdout, "{}", response)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// Coincidencias de `re` sobre `text` con posiciones (en bytes) y grupos; en modo
+/// por línea cada una lleva su número de línea y posiciones relativas a ella.
+pub fn matches_json(re: &regex::Regex, text: &str, unit: ProcessingUnit) -> Vec<Value> {
+    let lines: Vec<(Option<usize>, &str)> = match unit {
+        ProcessingUnit::WholeText => vec![(None, text)],
+        ProcessingUnit::PerLine => crate::split_lines(text).enumerate().map(|(n, (line, _))| (Some(n + 1), line)).collect(),
+    };
+    let mut matches = Vec::new();
+    for (line, text) in lines {
+        for caps in re.captures_iter(text) {
+            let whole = caps.get(0).expect("el grupo 0 siempre existe");
+            let groups: Vec<Option<&str>> = caps.iter().skip(1).map(|group| group.map(|m| m.as_str())).collect();
+            matches.push(json!({
+                "line": line,
+                "start": whole.start(),
+                "end": whole.end(),
+                "text": whole.as_str(),
+                "groups": groups,
+            }));
+        }
+    }
+    matches
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+type Method = fn(&Params, &EngineOptions) -> Result<Value, RpcError>;
+
+fn handle(config: &Config, method: &str, params: Value) -> Result<Value, RpcError> {
+    // El método se valida antes que los parámetros, que dependen de él.
+    let method: Method = match method {
+        "compile" => |params, options| {
+            let re = crate::compile(&params.pattern, options).map_err(pattern_error)?;
+            let names: Vec<Option<&str>> = re.capture_names().skip(1).collect();
+            Ok(json!({ "groups": names.len(), "names": names }))
+        },
+        "match" => |params, options| {
+            let re = crate::compile(&params.pattern, options).map_err(pattern_error)?;
+            Ok(json!({ "matches": matches_json(&re, &params.text, options.unit) }))
+        },
+        "replace" => |params, options| {
+            // A diferencia de la interfaz, un reemplazo vacío borra las coincidencias.
+            let re = crate::compile(&params.pattern, options).map_err(pattern_error)?;
+            let result = crate::replace_with(&re, &params.text, &params.replacement, options.unit);
+            Ok(json!({ "output": result.output, "match_count": result.match_count }))
+        },
+        "explain" => |params, options| {
+            let lines = crate::explain::explain(&params.pattern, &options.flags)
+                .map_err(|message| RpcError(PATTERN_ERROR, message))?;
+            Ok(json!({ "lines": lines }))
+        },
+        other => return Err(RpcError(METHOD_NOT_FOUND, format!("Método desconocido: {}", other))),
+    };
+    let params: Params = serde_json::from_value(params).map_err(|e| RpcError(INVALID_PARAMS, e.to_string()))?;
+    let options = EngineOptions {
+        unit: if params.per_line { ProcessingUnit::PerLine } else { ProcessingUnit::WholeText },
+        flags: params.flags.unwrap_or(config.flags),
+        size_limit: config.limits.regex_size_limit,
+    };
+    method(&params, &options)
+}
+
+fn pattern_error(e: regex::Error) -> RpcError {
+    RpcError(PATTERN_ERROR, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(method: &str, params: Value) -> Result<Value, i64> {
+        handle(&Config::default(), method, params).map_err(|RpcError(code, _)| code)
+    }
+
+    #[test]
+    fn unknown_methods_are_reported_before_params() {
+        assert_eq!(call("nope", Value::Null), Err(METHOD_NOT_FOUND));
+        assert_eq!(call("match", Value::Null), Err(INVALID_PARAMS));
+        assert_eq!(call("match", json!({ "pattern": "(" })), Err(PATTERN_ERROR));
+    }
+
+    #[test]
+    fn replace_uses_the_engine_per_line() {
+        let params = json!({ "pattern": "^$|x$", "text": "ax\r\n\nb\n", "replacement": "", "per_line": true });
+        assert_eq!(call("replace", params).unwrap(), json!({ "output": "a\r\n\nb\n", "match_count": 2 }));
+        let params = json!({ "pattern": r"(\w)\d", "text": "a1 b2", "replacement": "$1" });
+        assert_eq!(call("replace", params).unwrap(), json!({ "output": "a b", "match_count": 2 }));
+    }
+}
@@ -0,0 +1,109 @@
+//! Sesiones: instantáneas JSON del estado editable de la aplicación (textos,
+//! flags, modo, desplazamientos y casos de prueba) que pueden guardarse y restaurarse.
+//!
+//! Una sesión puede llevar además su salida de referencia ("golden"): la salida
+//! completa que se registró como correcta. Volver a calcularla y compararla
+//! (con la tecla de verificación o con `--check`) detecta cambios de resultado
+//! por un patrón editado o por otra versión del motor.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use regex_wysiwyg::{EngineOptions, ProcessingUnit, RegexFlags, transform};
+
+use crate::app::InputMode;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub source_text: String,
+    pub regex_input: String,
+    pub replace_input: String,
+    #[serde(default)]
+    pub flags: RegexFlags,
+    pub processing_unit: ProcessingUnit,
+    pub input_mode: InputMode,
+    #[serde(default)]
+    pub source_scroll: u16,
+    #[serde(default)]
+    pub output_scroll: u16,
+    /// Casos de prueba del patrón, uno por línea (ver `regex_wysiwyg::cases`).
+    #[serde(default)]
+    pub test_cases: String,
+    /// Salida registrada como correcta para este texto, patrón y reemplazo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub golden: Option<String>,
+}
+
+/// Primera diferencia entre la salida de referencia y la obtenida.
+#[derive(Debug, PartialEq)]
+pub struct Drift {
+    /// Línea, desde 1.
+    pub line: usize,
+    /// Esa línea en cada salida; `None` si la salida ya terminó.
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+/// La primera línea en que difieren `expected` y `actual`, o `None` si son iguales.
+pub fn first_difference(expected: &str, actual: &str) -> Option<Drift> {
+    if expected == actual {
+        return None;
+    }
+    let (mut expected_lines, mut actual_lines) = (expected.split('\n'), actual.split('\n'));
+    let mut line = 1;
+    loop {
+        let (expected, actual) = (expected_lines.next(), actual_lines.next());
+        if expected != actual {
+            return Some(Drift { line, expected: expected.map(str::to_string), actual: actual.map(str::to_string) });
+        }
+        line += 1;
+    }
+}
+
+impl Session {
+    /// Archivo donde se guarda automáticamente la última sesión al salir.
+    pub fn last_session_path() -> Option<PathBuf> {
+        regex_wysiwyg::paths::data_file("last_session.json")
+    }
+
+    /// Instantánea periódica de la sesión en curso; si existe al iniciar, la
+    /// ejecución anterior no terminó limpiamente.
+    pub fn autosave_path() -> Option<PathBuf> {
+        regex_wysiwyg::paths::cache_file("autosave.json")
+    }
+
+    /// La salida completa, sin cortes por tamaño, del patrón y el reemplazo de la
+    /// sesión sobre su texto, o el error del patrón.
+    pub fn output(&self, size_limit: usize) -> Result<String, String> {
+        let options = EngineOptions { unit: self.processing_unit, flags: self.flags, size_limit };
+        transform(&self.source_text, &self.regex_input, &self.replace_input, &options)
+            .map(|result| result.output)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Vuelve a calcular la salida y la compara con la de referencia: `Ok(None)`
+    /// si es idéntica. Es un error no tener salida de referencia.
+    pub fn check_golden(&self, size_limit: usize) -> Result<Option<Drift>, String> {
+        let golden = self.golden.as_deref().ok_or_else(|| "la sesión no tiene salida de referencia".to_string())?;
+        Ok(first_difference(golden, &self.output(size_limit)?))
+    }
+
+    pub fn load(path: &Path) -> Result<Session> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("No se pudo leer {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Sesión inválida en {}", path.display()))
+    }
+
+    /// Escribe la sesión en un archivo temporal y lo renombra, para que un corte a
+    /// mitad de escritura nunca deje un archivo truncado.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(self)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, text).with_context(|| format!("No se pudo escribir {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("No se pudo escribir {}", path.display()))
+    }
+}
@@ -0,0 +1,101 @@
+//! Entradas armadas para forzar el patrón: corridas largas de lo que repite
+//! cada cuantificador, cortadas al final para que no haya coincidencia, y el
+//! comienzo de una coincidencia repetido sin completarse. Cada una se mide,
+//! para detectar un patrón lento antes de que lo sea en producción.
+
+use regex_syntax::hir::{Hir, HirKind};
+use std::time::{Duration, Instant};
+
+use crate::EngineOptions;
+use crate::nearmiss::{build, different, parse};
+
+/// Entradas que se miden como máximo.
+const MAX_CASES: usize = 8;
+
+/// Velocidad por debajo de la cual una entrada se considera lenta, en MB/s.
+pub const SLOW_MB_PER_SEC: f64 = 10.0;
+
+/// Una entrada de prueba con lo que tardó el patrón en recorrerla.
+#[derive(Debug, Clone)]
+pub struct StressCase {
+    /// Cómo se armó la entrada.
+    pub name: String,
+    pub len: usize,
+    pub elapsed: Duration,
+    pub matches: usize,
+}
+
+impl StressCase {
+    pub fn mb_per_sec(&self) -> f64 {
+        self.len as f64 / 1_000_000.0 / self.elapsed.as_secs_f64().max(1e-9)
+    }
+
+    pub fn is_slow(&self) -> bool {
+        self.mb_per_sec() < SLOW_MB_PER_SEC
+    }
+}
+
+/// Mide `pattern` sobre entradas de unos `size` bytes, de la más lenta a la
+/// más rápida, o el error si no compila.
+pub fn stress(pattern: &str, options: &EngineOptions, size: usize) -> Result<Vec<StressCase>, String> {
+    let re = crate::compile(pattern, options).map_err(|e| e.to_string())?;
+    let hir = parse(pattern, options).map_err(|e| e.to_string())?;
+    let mut inputs: Vec<(String, String)> = Vec::new();
+
+    let mut units = Vec::new();
+    repeated_units(&hir, &mut units);
+    for unit in units {
+        let last = unit.chars().last().unwrap_or('a');
+        let run = repeat_to(&unit, size);
+        inputs.push((format!("corrida de {:?} cortada con {:?}", unit, different(last)), format!("{}{}", run, different(last))));
+    }
+    let mut example = String::new();
+    build(&hir, &mut example);
+    let mut prefix = example.clone();
+    prefix.pop();
+    if !prefix.is_empty() {
+        inputs.push((format!("comienzo {:?} repetido sin completar", prefix), repeat_to(&prefix, size)));
+    }
+    if !example.is_empty() {
+        inputs.push((format!("coincidencia {:?} repetida", example), repeat_to(&example, size)));
+    }
+    inputs.push(("texto sin relación".to_string(), repeat_to("lorem ipsum ", size)));
+
+    let mut cases: Vec<StressCase> = inputs
+        .into_iter()
+        .take(MAX_CASES)
+        .map(|(name, input)| {
+            let started = Instant::now();
+            let matches = crate::count_matches(&re, &input, options.unit);
+            StressCase { name, len: input.len(), elapsed: started.elapsed(), matches }
+        })
+        .collect();
+    cases.sort_by(|a, b| a.mb_per_sec().total_cmp(&b.mb_per_sec()));
+    Ok(cases)
+}
+
+/// Lo mínimo que repite cada cuantificador sin tope (o con tope mayor a uno).
+fn repeated_units(hir: &Hir, units: &mut Vec<String>) {
+    match hir.kind() {
+        HirKind::Repetition(repetition) => {
+            if repetition.max.is_none_or(|max| max > 1) {
+                let mut unit = String::new();
+                build(&repetition.sub, &mut unit);
+                if !unit.is_empty() && !units.contains(&unit) {
+                    units.push(unit);
+                }
+            }
+            repeated_units(&repetition.sub, units);
+        }
+        HirKind::Capture(capture) => repeated_units(&capture.sub, units),
+        HirKind::Concat(items) | HirKind::Alternation(items) => {
+            items.iter().for_each(|item| repeated_units(item, units));
+        }
+        _ => {}
+    }
+}
+
+/// `unit` repetido hasta ocupar al menos `size` bytes.
+fn repeat_to(unit: &str, size: usize) -> String {
+    unit.repeat(size.div_ceil(unit.len().max(1)))
+}
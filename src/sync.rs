@@ -0,0 +1,48 @@
+//! Sincronización de la biblioteca con una fuente compartida del equipo: una URL
+//! HTTPS que sirve un arreglo JSON de patrones, o la ruta de un repositorio git
+//! local (se actualiza con `git pull --ff-only` y se lee el archivo indicado).
+//!
+//! Como corre con la interfaz en pantalla, git no puede escribir en la terminal
+//! ni pedir credenciales: si las necesita, falla y su mensaje va al error.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::config::SyncConfig;
+use crate::library::LibraryEntry;
+
+/// Descarga o lee las entradas publicadas en la fuente configurada.
+pub fn fetch(config: &SyncConfig) -> Result<Vec<LibraryEntry>> {
+    let source = config.source.trim();
+    let text = if source.starts_with("https://") {
+        fetch_url(source)?
+    } else if source.starts_with("http://") {
+        anyhow::bail!("La fuente {} no usa HTTPS", source);
+    } else {
+        fetch_git(Path::new(source), &config.file)?
+    };
+    serde_json::from_str(&text).with_context(|| format!("Biblioteca remota inválida en {}", source))
+}
+
+fn fetch_url(url: &str) -> Result<String> {
+    let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(10)).build();
+    let response = agent.get(url).call().with_context(|| format!("No se pudo descargar {}", url))?;
+    response.into_string().with_context(|| format!("Respuesta ilegible de {}", url))
+}
+
+fn fetch_git(repo: &Path, file: &str) -> Result<String> {
+    let mut git = Command::new("git");
+    git.arg("-C").arg(repo).args(["pull", "--ff-only", "--quiet"]).env("GIT_TERMINAL_PROMPT", "0").stdin(Stdio::null());
+    // ssh pide la clave directamente a la terminal aunque no tenga entrada.
+    if std::env::var_os("GIT_SSH_COMMAND").is_none() {
+        git.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    }
+    let output = git.output().context("No se pudo ejecutar git")?;
+    if !output.status.success() {
+        anyhow::bail!("git pull falló en {}: {}", repo.display(), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let path = repo.join(file);
+    std::fs::read_to_string(&path).with_context(|| format!("No se pudo leer {}", path.display()))
+}
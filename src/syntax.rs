@@ -0,0 +1,138 @@
+//! Lexer tolerante de patrones para colorear el campo de la regex: reconoce
+//! escapes, grupos, clases, cuantificadores y operadores sin exigir que el
+//! patrón esté completo o sea válido, como pasa mientras se escribe.
+
+use std::ops::Range;
+
+/// Clase de un token del patrón.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Literal,
+    /// `\d`, `\.`, `\p{Greek}`…
+    Escape,
+    /// Apertura de grupo (con su prefijo, como `(?:` o `(?P<nombre>`) o de clase.
+    Open,
+    Close,
+    /// `*`, `+`, `?`, `{n,m}`, con el `?` de la versión perezosa.
+    Quantifier,
+    /// `|`, `^`, `$` y `.`.
+    Operator,
+    /// Paréntesis o corchete sin pareja.
+    Unmatched,
+}
+
+/// Token del patrón: su clase, su rango de bytes y la profundidad de
+/// anidamiento de grupos y clases en la que está (0 fuera de todo grupo; una
+/// apertura y su cierre comparten la del exterior).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternToken {
+    pub kind: TokenKind,
+    pub range: Range<usize>,
+    pub depth: usize,
+}
+
+/// Tokens de `pattern`, que lo cubren entero y en orden.
+pub fn tokenize(pattern: &str) -> Vec<PatternToken> {
+    let mut tokens: Vec<PatternToken> = Vec::new();
+    // Índices de los tokens de apertura aún sin cerrar, y si son de clase.
+    let mut open: Vec<(usize, bool)> = Vec::new();
+    let in_class = |open: &[(usize, bool)]| open.last().is_some_and(|&(_, class)| class);
+    let mut pos = 0;
+    while pos < pattern.len() {
+        let rest = &pattern[pos..];
+        let c = rest.chars().next().unwrap_or_default();
+        let depth = open.len();
+        let (kind, len) = match c {
+            '\\' => (TokenKind::Escape, escape_len(rest)),
+            '[' if in_class(&open) && rest.starts_with("[:") && rest[2..].contains(":]") => {
+                (TokenKind::Literal, rest.find(":]").unwrap_or(0) + 2)
+            }
+            '[' => {
+                open.push((tokens.len(), true));
+                // Un `]` justo tras la apertura (o tras `^`) es literal.
+                let mut len = 1;
+                if rest[len..].starts_with('^') {
+                    len += 1;
+                }
+                (TokenKind::Open, len)
+            }
+            ']' if open.last().is_some_and(|&(index, class)| class && index + 1 == tokens.len()) => {
+                (TokenKind::Literal, 1)
+            }
+            ']' if in_class(&open) => {
+                open.pop();
+                tokens.push(PatternToken { kind: TokenKind::Close, range: pos..pos + 1, depth: depth - 1 });
+                pos += 1;
+                continue;
+            }
+            _ if in_class(&open) => (TokenKind::Literal, c.len_utf8()),
+            '(' => {
+                open.push((tokens.len(), false));
+                (TokenKind::Open, group_prefix_len(rest))
+            }
+            ')' => match open.pop() {
+                Some(_) => {
+                    tokens.push(PatternToken { kind: TokenKind::Close, range: pos..pos + 1, depth: depth - 1 });
+                    pos += 1;
+                    continue;
+                }
+                None => (TokenKind::Unmatched, 1),
+            },
+            '*' | '+' | '?' => (TokenKind::Quantifier, if rest[1..].starts_with('?') { 2 } else { 1 }),
+            '{' => match counted_len(rest) {
+                Some(len) => (TokenKind::Quantifier, len),
+                None => (TokenKind::Literal, 1),
+            },
+            '|' | '^' | '$' | '.' => (TokenKind::Operator, 1),
+            _ => (TokenKind::Literal, c.len_utf8()),
+        };
+        tokens.push(PatternToken { kind, range: pos..pos + len, depth });
+        pos += len;
+    }
+    for (index, _) in open {
+        tokens[index].kind = TokenKind::Unmatched;
+    }
+    tokens
+}
+
+/// Largo de un escape que empieza en `rest`: la barra y el carácter siguiente,
+/// más las llaves de `\p{…}`, `\x{…}` y similares.
+fn escape_len(rest: &str) -> usize {
+    let Some(next) = rest[1..].chars().next() else {
+        return 1;
+    };
+    let len = 1 + next.len_utf8();
+    if matches!(next, 'p' | 'P' | 'x' | 'u' | 'U')
+        && rest[len..].starts_with('{')
+        && let Some(close) = rest[len..].find('}')
+    {
+        return len + close + 1;
+    }
+    len
+}
+
+/// Largo de la apertura de grupo en `rest`: `(` más el prefijo `?…:` o
+/// `?P<nombre>` si lo hay. En `(?i)` el prefijo llega hasta antes del `)`.
+fn group_prefix_len(rest: &str) -> usize {
+    if !rest[1..].starts_with('?') {
+        return 1;
+    }
+    match rest[1..].find([':', '>', ')', '(']).map(|end| end + 1) {
+        Some(end) if matches!(rest.as_bytes()[end], b':' | b'>') => end + 1,
+        Some(end) if rest.as_bytes()[end] == b')' => end,
+        _ => 1,
+    }
+}
+
+/// Largo de un cuantificador `{n}`, `{n,}` o `{n,m}` (con `?` perezoso) al
+/// principio de `rest`, si está bien formado.
+fn counted_len(rest: &str) -> Option<usize> {
+    let close = rest.find('}')?;
+    let inner = &rest[1..close];
+    let (min, max) = inner.split_once(',').unwrap_or((inner, "0"));
+    let digits = |s: &str| s.trim().chars().all(|c| c.is_ascii_digit());
+    if min.trim().is_empty() || !digits(min) || !digits(max) {
+        return None;
+    }
+    Some(if rest[close + 1..].starts_with('?') { close + 2 } else { close + 1 })
+}
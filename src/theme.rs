@@ -0,0 +1,166 @@
+//! Colores de la interfaz: los presets de `Theme` y la interpretación de los
+//! colores de `[colors]` en la configuración.
+
+use anyhow::Result;
+use ratatui::style::{Color, Modifier};
+use std::str::FromStr;
+
+use regex_wysiwyg::config::{ColorsConfig, Theme};
+
+/// Colores del preset `theme`.
+pub fn preset(theme: Theme) -> Palette {
+    match theme {
+        Theme::Dark => Palette {
+            title: Color::LightCyan,
+            source: Color::Yellow,
+            regex: Color::Magenta,
+            replace: Color::LightBlue,
+            output: Color::Green,
+            matches: Color::Green,
+            separator: Color::DarkGray,
+            error: Color::LightRed,
+            border: Color::Reset,
+            help: Color::Gray,
+            emphasis: Modifier::empty(),
+            nesting: [Color::Yellow, Color::LightCyan, Color::LightMagenta, Color::LightGreen],
+        },
+        Theme::Light => Palette {
+            title: Color::Blue,
+            source: Color::Rgb(0x8a, 0x5a, 0x00),
+            regex: Color::Magenta,
+            replace: Color::Blue,
+            output: Color::Black,
+            matches: Color::Rgb(0x00, 0x6e, 0x00),
+            separator: Color::Gray,
+            error: Color::Red,
+            border: Color::DarkGray,
+            help: Color::DarkGray,
+            emphasis: Modifier::empty(),
+            nesting: [Color::Rgb(0x8a, 0x5a, 0x00), Color::Blue, Color::Magenta, Color::Rgb(0x00, 0x6e, 0x00)],
+        },
+        Theme::Solarized => Palette {
+            title: Color::Rgb(0x2a, 0xa1, 0x98),
+            source: Color::Rgb(0xb5, 0x89, 0x00),
+            regex: Color::Rgb(0xd3, 0x36, 0x82),
+            replace: Color::Rgb(0x26, 0x8b, 0xd2),
+            output: Color::Rgb(0x83, 0x94, 0x96),
+            matches: Color::Rgb(0x85, 0x99, 0x00),
+            separator: Color::Rgb(0x58, 0x6e, 0x75),
+            error: Color::Rgb(0xdc, 0x32, 0x2f),
+            border: Color::Rgb(0x58, 0x6e, 0x75),
+            help: Color::Rgb(0x58, 0x6e, 0x75),
+            emphasis: Modifier::empty(),
+            nesting: [
+                Color::Rgb(0xb5, 0x89, 0x00),
+                Color::Rgb(0x26, 0x8b, 0xd2),
+                Color::Rgb(0xd3, 0x36, 0x82),
+                Color::Rgb(0x2a, 0xa1, 0x98),
+            ],
+        },
+        Theme::Deuteranopia => Palette {
+            title: Color::Rgb(0x56, 0xb4, 0xe9),
+            source: Color::Rgb(0xf0, 0xe4, 0x42),
+            regex: Color::Rgb(0xcc, 0x79, 0xa7),
+            replace: Color::Rgb(0x00, 0x72, 0xb2),
+            output: Color::Reset,
+            matches: Color::Rgb(0x56, 0xb4, 0xe9),
+            separator: Color::DarkGray,
+            error: Color::Rgb(0xe6, 0x9f, 0x00),
+            border: Color::Reset,
+            help: Color::Gray,
+            emphasis: Modifier::BOLD,
+            nesting: [
+                Color::Rgb(0xe6, 0x9f, 0x00),
+                Color::Rgb(0x56, 0xb4, 0xe9),
+                Color::Rgb(0x00, 0x9e, 0x73),
+                Color::Rgb(0xf0, 0xe4, 0x42),
+            ],
+        },
+        Theme::Protanopia => Palette {
+            title: Color::Rgb(0x56, 0xb4, 0xe9),
+            source: Color::Rgb(0xe6, 0x9f, 0x00),
+            regex: Color::Rgb(0xcc, 0x79, 0xa7),
+            replace: Color::Rgb(0x00, 0x72, 0xb2),
+            output: Color::Reset,
+            matches: Color::Rgb(0x56, 0xb4, 0xe9),
+            separator: Color::DarkGray,
+            error: Color::Rgb(0xf0, 0xe4, 0x42),
+            border: Color::Reset,
+            help: Color::Gray,
+            emphasis: Modifier::BOLD,
+            nesting: [
+                Color::Rgb(0xf0, 0xe4, 0x42),
+                Color::Rgb(0x56, 0xb4, 0xe9),
+                Color::Rgb(0x00, 0x9e, 0x73),
+                Color::Rgb(0xcc, 0x79, 0xa7),
+            ],
+        },
+        Theme::HighContrast => Palette {
+            title: Color::White,
+            source: Color::White,
+            regex: Color::White,
+            replace: Color::White,
+            output: Color::White,
+            matches: Color::White,
+            separator: Color::Gray,
+            error: Color::White,
+            border: Color::White,
+            help: Color::White,
+            emphasis: Modifier::BOLD | Modifier::UNDERLINED,
+            nesting: [Color::White; 4],
+        },
+    }
+}
+
+/// Colores ya interpretados, listos para el renderizado.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub title: Color,
+    pub source: Color,
+    pub regex: Color,
+    pub replace: Color,
+    pub output: Color,
+    pub matches: Color,
+    pub separator: Color,
+    pub error: Color,
+    pub border: Color,
+    pub help: Color,
+    /// Modificadores de las coincidencias y de los errores en la salida, para
+    /// distinguirlos sin depender solo del color.
+    pub emphasis: Modifier,
+    /// Colores de paréntesis y corchetes del patrón según su anidamiento, en ciclo.
+    pub nesting: [Color; 4],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        preset(Theme::default())
+    }
+}
+
+/// Paleta del tema configurado, con los colores que lo redefinen.
+pub fn palette(colors: &ColorsConfig) -> Result<Palette> {
+    let preset = preset(colors.theme);
+    let pick = |key: &str, value: &Option<String>, default: Color| match value {
+        Some(value) => parse_color(key, value),
+        None => Ok(default),
+    };
+    Ok(Palette {
+        title: pick("colors.title", &colors.title, preset.title)?,
+        source: pick("colors.source", &colors.source, preset.source)?,
+        regex: pick("colors.regex", &colors.regex, preset.regex)?,
+        replace: pick("colors.replace", &colors.replace, preset.replace)?,
+        output: pick("colors.output", &colors.output, preset.output)?,
+        matches: pick("colors.matches", &colors.matches, preset.matches)?,
+        separator: pick("colors.separator", &colors.separator, preset.separator)?,
+        error: pick("colors.error", &colors.error, preset.error)?,
+        border: pick("colors.border", &colors.border, preset.border)?,
+        help: pick("colors.help", &colors.help, preset.help)?,
+        emphasis: preset.emphasis,
+        nesting: preset.nesting,
+    })
+}
+
+fn parse_color(key: &str, value: &str) -> Result<Color> {
+    Color::from_str(value).map_err(|_| anyhow::anyhow!("Color inválido en {}: '{}'", key, value))
+}
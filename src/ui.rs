@@ -0,0 +1,2052 @@
+//! Dibujo de la interfaz: paneles principales, barra de ayuda y ventanas
+//! emergentes de cada modo.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::Duration;
+
+use regex_wysiwyg::assertions::SpanScore;
+use regex_wysiwyg::cases::Verdict;
+use regex_wysiwyg::catalog::CATALOG;
+use regex_wysiwyg::classes::{self, ClassExpansion};
+use regex_wysiwyg::config::{Language, PaneKind, Split};
+use regex_wysiwyg::debugger::StepKind;
+use regex_wysiwyg::explain;
+use regex_wysiwyg::export::Target;
+use regex_wysiwyg::lessons;
+use regex_wysiwyg::paths;
+use regex_wysiwyg::syntax::{self, TokenKind};
+use regex_wysiwyg::worker::Timings;
+use ropey::Rope;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::app::{App, Debugger, InputMode, ModalKind, Pane, TOUR};
+use crate::i18n::{self, Text, tr};
+use crate::keymap::Action;
+
+/// Solo las líneas que caben en `area` (descontando el borde) a partir de la
+/// línea `scroll`: con miles de coincidencias no se construye ni se estila texto
+/// que no se va a ver.
+fn visible_text<'a>(text: &'a str, scroll: u16, area: Rect, to_line: impl Fn(&'a str) -> Line<'a>) -> ratatui::text::Text<'a> {
+    let height = area.height.saturating_sub(2) as usize;
+    ratatui::text::Text::from(text.lines().skip(scroll as usize).take(height).map(to_line).collect::<Vec<_>>())
+}
+
+/// Como `visible_text`, tomando las líneas directamente del rope.
+fn visible_rope(rope: &Rope, scroll: u16, area: Rect) -> ratatui::text::Text<'static> {
+    let height = area.height.saturating_sub(2) as usize;
+    let start = (scroll as usize).min(rope.len_lines());
+    let lines = rope
+        .lines_at(start)
+        .take(height)
+        .map(|line| Line::raw(line.to_string().trim_end_matches(['\n', '\r']).to_string()))
+        .collect::<Vec<_>>();
+    ratatui::text::Text::from(lines)
+}
+
+/// Bloque con borde en el color del tema; sin borde para lectores de pantalla,
+/// que leerían los caracteres de dibujo.
+fn bordered(app: &App) -> Block<'static> {
+    if app.screen_reader {
+        return Block::default();
+    }
+    Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.palette.border))
+}
+
+fn filter_line(line: &str, matched: Style, separator: Style) -> Line<'_> {
+    let mut spans = Vec::new();
+    for (index, part) in line.split(" | ").enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(" | ", separator));
+        }
+        spans.push(Span::styled(part, matched));
+    }
+    Line::from(spans)
+}
+
+/// Por debajo de este alto o ancho los paneles con borde quedarían de una o dos
+/// filas ilegibles, y se pasa a la distribución compacta.
+const COMPACT_HEIGHT: u16 = 20;
+const COMPACT_WIDTH: u16 = 50;
+
+pub fn ui(f: &mut Frame, app: &App) {
+    let area = f.area();
+    if let Some(pane) = app.zoomed {
+        match pane {
+            Pane::Source => render_source(f, app, area),
+            Pane::Output => render_output(f, app, area),
+        }
+        render_toasts(f, app);
+        render_popups(f, app);
+        return;
+    }
+    if is_compact(area) {
+        render_compact(f, app, area);
+        render_toasts(f, app);
+        render_popups(f, app);
+        return;
+    }
+    let mut regex_area = None;
+    for (kind, rect) in chunks(app, area) {
+        match kind {
+            PaneKind::Title => render_title(f, app, rect),
+            PaneKind::Source => render_source(f, app, rect),
+            PaneKind::Regex => {
+                let mut title = format!(" [{}] ('{}') ", tr(app.language, Text::PatternPane), app.keymap.key_label(Action::EditRegex));
+                if let Some(coverage) = &app.coverage {
+                    title.push_str(&format!(
+                        "{} ('{}') ",
+                        i18n::coverage(app.language, coverage),
+                        app.keymap.key_label(Action::ToggleCoverage)
+                    ));
+                }
+                let mut block = bordered(app).title(title);
+                if let Some((lesson, index)) = app.exercise {
+                    let content = &app.lessons[lesson];
+                    let solved = app.progress.is_solved(&lessons::exercise_key(content, index));
+                    block = block.title_bottom(format!(
+                        " {} {}.{}: {}{} ",
+                        tr(app.language, Text::Exercises),
+                        lesson + 1,
+                        index + 1,
+                        content.exercises[index].prompt,
+                        if solved { " ✓" } else { "" }
+                    ));
+                }
+                let edited_in = if app.input_mode == InputMode::EditingCompare {
+                    InputMode::EditingCompare
+                } else {
+                    InputMode::EditingRegex
+                };
+                let (block, style) = pane(app, block, Some(edited_in), app.palette.regex);
+                let lines = if comparing(app) {
+                    let label = Style::default().add_modifier(Modifier::BOLD);
+                    let mut a = vec![Span::styled("A ", label)];
+                    a.extend(pattern_spans(app, Style::default()));
+                    let b = vec![
+                        Span::styled(format!("B ('{}') ", app.keymap.key_label(Action::EditCompare)), label),
+                        Span::raw(app.compare_input.clone()),
+                    ];
+                    vec![Line::from(a), Line::from(b)]
+                } else {
+                    vec![Line::from(pattern_spans(app, Style::default()))]
+                };
+                f.render_widget(Paragraph::new(lines).style(style).block(block), rect);
+                regex_area = Some(rect);
+            }
+            PaneKind::Replace => {
+                let (block, style) = pane(
+                    app,
+                    bordered(app)
+                        .title(format!(
+                            " [{}] ('{}' - {}) ",
+                            tr(app.language, Text::ReplacePane),
+                            app.keymap.key_label(Action::EditReplace),
+                            tr(app.language, Text::SedMode)
+                        )),
+                    Some(InputMode::EditingReplace),
+                    app.palette.replace,
+                );
+                f.render_widget(Paragraph::new(app.replace_input.as_str()).style(style).block(block), rect);
+            }
+            PaneKind::Output => render_output(f, app, rect),
+            PaneKind::Tests => render_tests(f, app, rect),
+            PaneKind::Status => render_status(f, app, rect),
+        }
+    }
+    // Encima de los paneles que siguen al del patrón.
+    if let Some(rect) = regex_area {
+        render_class_expansion(f, app, rect);
+    }
+
+    render_toasts(f, app);
+    render_popups(f, app);
+}
+
+/// Barra de título: perfil, proyecto y pestañas de los espacios de trabajo.
+fn render_title(f: &mut Frame, app: &App, area: Rect) {
+    let lang = app.language;
+    let project_name = app
+        .project
+        .as_ref()
+        .map(|open| format!(" | {}: {}", tr(lang, Text::Project), open.project.name))
+        .unwrap_or_default();
+    let profile_name = paths::profile().map(|name| format!(" | {}: {}", tr(lang, Text::Profile), name)).unwrap_or_default();
+    let mut title_spans = vec![Span::raw(format!(" REGEX WYSIWYG{}{} ", profile_name, project_name))];
+    title_spans.extend(workspace_tabs(app));
+    let title = Paragraph::new(Line::from(title_spans))
+        .style(Style::default().fg(app.palette.title).add_modifier(Modifier::BOLD))
+        .block(bordered(app));
+    f.render_widget(title, area);
+}
+
+/// Barra de estado y, debajo, la ayuda del modo actual.
+fn render_status(f: &mut Frame, app: &App, area: Rect) {
+    let lang = app.language;
+    let mode_name = i18n::mode_name(lang, &app.input_mode);
+    let help_text = match app.input_mode {
+        InputMode::Normal => format!(
+            "{} | {}: {} | {}: {} | {}: {} | {}: {} | {}/{}: {} | {}: {}",
+            app.status_message,
+            app.keymap.key_label(Action::ShowHelp),
+            tr(lang, Text::Help),
+            app.keymap.key_label(Action::OpenHistory),
+            tr(lang, Text::History),
+            app.keymap.key_label(Action::OpenLibrary),
+            tr(lang, Text::Library),
+            app.keymap.key_label(Action::SavePattern),
+            tr(lang, Text::Save),
+            app.keymap.key_label(Action::SaveSession),
+            app.keymap.key_label(Action::LoadSession),
+            tr(lang, Text::Session),
+            app.keymap.key_label(Action::Quit),
+            tr(lang, Text::Quit)
+        ),
+        // Muestra además para qué proveedor es la clave.
+        InputMode::EnteringApiKey => {
+            format!("{} | {}", app.status_message, i18n::mode_keys(lang, &app.input_mode))
+        }
+        // Explica el token junto al cursor.
+        InputMode::EditingRegex => match token_at_cursor(app)
+            .and_then(|offset| explain::explain_at(&app.regex_input, &app.flags, offset))
+        {
+            Some(explanation) => format!("{} | {}", explanation.trim_end_matches(':'), i18n::mode_keys(lang, &app.input_mode)),
+            None => i18n::mode_keys(lang, &app.input_mode).to_string(),
+        },
+        _ => i18n::mode_keys(lang, &app.input_mode).to_string(),
+    };
+    f.render_widget(
+        Paragraph::new(vec![status_line(app, mode_name), Line::raw(help_text)])
+            .style(Style::default().fg(app.palette.help))
+            .block(bordered(app)),
+        area,
+    );
+}
+
+/// Pestañas de los espacios de trabajo, numeradas como las teclas que llevan a
+/// cada una; con un solo espacio no se muestran.
+fn workspace_tabs(app: &App) -> Vec<Span<'static>> {
+    if app.workspaces.len() < 2 {
+        return Vec::new();
+    }
+    let mut spans = Vec::new();
+    for (index, workspace) in app.workspaces.iter().enumerate() {
+        let (pattern, path) = match workspace {
+            Some(workspace) => (workspace.session.regex_input.as_str(), workspace.source_path.as_deref()),
+            None => (app.regex_input.as_str(), app.source_path.as_deref()),
+        };
+        let name = match path.and_then(|path| path.file_name()) {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None if pattern.is_empty() => "—".to_string(),
+            None => format!("/{}/", pattern.chars().take(12).collect::<String>()),
+        };
+        let label = format!(" {}:{} ", index + 1, name);
+        spans.push(if workspace.is_none() {
+            Span::styled(format!("[{}]", label.trim()), Style::default().add_modifier(Modifier::REVERSED))
+        } else {
+            Span::styled(label, Style::default().remove_modifier(Modifier::BOLD))
+        });
+    }
+    spans
+}
+
+/// Barra de estado: modo, flags, unidad, motor, coincidencias, posición del
+/// cursor (al editar) y estado de la IA, como segmentos separados.
+fn status_line(app: &App, mode_name: &str) -> Line<'static> {
+    let lang = app.language;
+    let matches = if app.worker.busy() {
+        tr(lang, Text::Calculating).to_string()
+    } else if app.output_error {
+        tr(lang, Text::InvalidPattern).to_string()
+    } else {
+        format!("{} {}", app.match_count, tr(lang, Text::Matches))
+    };
+    let cursor = match app.input_mode {
+        InputMode::EditingSource => {
+            let line = app.source_text.len_lines();
+            let column = app.source_text.line(line - 1).to_string().width() + 1;
+            Some(format!("Ln {}, Col {}", line, column))
+        }
+        InputMode::EditingTests => {
+            let lines: Vec<&str> = app.test_cases.split('\n').collect();
+            Some(format!("Ln {}, Col {}", lines.len(), lines[lines.len() - 1].width() + 1))
+        }
+        InputMode::EditingRegex => Some(format!("Col {}", app.regex_input[..app.regex_cursor()].width() + 1)),
+        InputMode::EditingReplace => Some(format!("Col {}", app.replace_input.width() + 1)),
+        InputMode::EditingCompare => Some(format!("Col {}", app.compare_input.width() + 1)),
+        _ => None,
+    };
+    let ai = if app.ai_pending {
+        format!("{}: {} ({})", tr(lang, Text::Ai), app.config.ai.provider, tr(lang, Text::Querying))
+    } else {
+        format!("{}: {}", tr(lang, Text::Ai), app.config.ai.provider)
+    };
+
+    let mut segments = vec![
+        format!("flags: {}", app.flags.summary()),
+        format!("{}: {}", tr(lang, Text::Unit), i18n::unit_name(lang, app.processing_unit)),
+        format!("{}: regex", tr(lang, Text::Engine)),
+        matches,
+    ];
+    segments.extend(cursor);
+    segments.push(ai);
+
+    let mut spans = if app.screen_reader {
+        vec![Span::raw(format!("{}: {}", tr(lang, Text::Mode), mode_name))]
+    } else {
+        vec![Span::styled(
+            format!(" {} ", mode_name),
+            Style::default()
+                .fg(Color::Black)
+                .bg(editing_color(app).unwrap_or(app.palette.title))
+                .add_modifier(Modifier::BOLD),
+        )]
+    };
+    for segment in segments {
+        spans.push(Span::styled(" │ ", Style::default().fg(app.palette.separator)));
+        spans.push(Span::raw(segment));
+    }
+    Line::from(spans)
+}
+
+/// Color del campo en edición, si el modo actual escribe en uno.
+fn editing_color(app: &App) -> Option<Color> {
+    match app.input_mode {
+        InputMode::EditingSource => Some(app.palette.source),
+        InputMode::EditingRegex => Some(app.palette.regex),
+        InputMode::EditingReplace => Some(app.palette.replace),
+        InputMode::EditingTests => Some(app.palette.matches),
+        InputMode::MarkingSpans => Some(app.palette.source),
+        InputMode::EditingCompare => Some(app.palette.regex),
+        _ => None,
+    }
+}
+
+/// Borde y estilo del contenido de un panel según el modo: el que recibe el
+/// teclado (`edited_in`) lleva borde grueso y título en negrita en su color, y
+/// mientras se edita otro campo el panel se atenúa.
+fn pane(app: &App, block: Block<'static>, edited_in: Option<InputMode>, color: Color) -> (Block<'static>, Style) {
+    if edited_in.as_ref() == Some(&app.input_mode) {
+        let highlight = Style::default().fg(color).add_modifier(Modifier::BOLD);
+        (block.border_type(BorderType::Thick).border_style(highlight).title_style(highlight), Style::default().fg(color))
+    } else if editing_color(app).is_some() {
+        let dim = Style::default().add_modifier(Modifier::DIM);
+        (block.border_style(dim.fg(app.palette.border)).title_style(dim), dim)
+    } else {
+        (block, Style::default())
+    }
+}
+
+fn is_compact(area: Rect) -> bool {
+    area.height < COMPACT_HEIGHT || area.width < COMPACT_WIDTH
+}
+
+/// Paneles a la vista, en el orden de `layout.panes` y sin repetidos (el de
+/// casos de prueba, solo si hay alguno). Un panel oculto se muestra al final
+/// mientras se edita, para ver lo que se escribe.
+fn visible_panes(app: &App) -> Vec<PaneKind> {
+    let mut panes: Vec<PaneKind> = Vec::new();
+    for &kind in &app.config.layout.panes {
+        let no_cases = kind == PaneKind::Tests && app.test_cases.is_empty();
+        if !panes.contains(&kind) && !no_cases {
+            panes.push(kind);
+        }
+    }
+    let edited = match app.input_mode {
+        InputMode::EditingSource => Some(PaneKind::Source),
+        InputMode::EditingRegex => Some(PaneKind::Regex),
+        InputMode::EditingReplace => Some(PaneKind::Replace),
+        InputMode::EditingTests => Some(PaneKind::Tests),
+        InputMode::EditingCompare => Some(PaneKind::Regex),
+        _ => None,
+    };
+    if let Some(kind) = edited
+        && !panes.contains(&kind)
+    {
+        panes.push(kind);
+    }
+    panes
+}
+
+/// Zonas de la distribución normal para cada panel visible. El texto fuente y
+/// la salida se reparten el alto sobrante o, lado a lado, una misma fila.
+fn chunks(app: &App, area: Rect) -> Vec<(PaneKind, Rect)> {
+    let panes = visible_panes(app);
+    let side_by_side = app.split == Split::Horizontal;
+    // El reemplazo vacío se puede ocultar; su alto pasa a los paneles flexibles.
+    let replace_height = if app.config.layout.collapse_replace
+        && app.replace_input.is_empty()
+        && app.input_mode != InputMode::EditingReplace
+    {
+        0
+    } else {
+        3
+    };
+    let flexible = |kind: PaneKind| matches!(kind, PaneKind::Source | PaneKind::Output);
+
+    // Filas de arriba abajo; lado a lado, fuente y salida comparten la fila del
+    // primero que aparezca.
+    let mut rows: Vec<Vec<PaneKind>> = Vec::new();
+    for &kind in &panes {
+        match rows.iter_mut().find(|row| side_by_side && flexible(kind) && flexible(row[0])) {
+            Some(row) => row.push(kind),
+            None => rows.push(vec![kind]),
+        }
+    }
+    let share = |kind: PaneKind| match kind {
+        PaneKind::Output => app.output_share,
+        _ => 100 - app.output_share,
+    };
+    let constraints: Vec<Constraint> = rows
+        .iter()
+        .map(|row| match row[0] {
+            PaneKind::Title => Constraint::Length(3),
+            PaneKind::Regex => Constraint::Length(if comparing(app) { 4 } else { 3 }),
+            PaneKind::Replace => Constraint::Length(replace_height),
+            PaneKind::Status => Constraint::Length(4),
+            PaneKind::Tests => Constraint::Length(app.test_cases.split('\n').count().min(TEST_ROWS) as u16 + 2),
+            _ if row.len() > 1 => Constraint::Fill(1),
+            kind => Constraint::Fill(share(kind)),
+        })
+        .collect();
+    let areas = Layout::default().direction(Direction::Vertical).constraints(constraints).split(area);
+
+    let mut chunks = Vec::with_capacity(panes.len());
+    for (row, &row_area) in rows.iter().zip(areas.iter()) {
+        if row.len() == 1 {
+            chunks.push((row[0], row_area));
+            continue;
+        }
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(row.iter().map(|&kind| Constraint::Fill(share(kind))))
+            .split(row_area);
+        chunks.extend(row.iter().copied().zip(columns.iter().copied()));
+    }
+    chunks
+}
+
+/// Filas de la distribución compacta: pestañas, panel visible, patrón y estado.
+fn compact_rows(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Fill(1), Constraint::Length(1), Constraint::Length(1)])
+        .split(area)
+}
+
+/// Panel visible en la distribución compacta: el último desplazado, o el texto
+/// fuente mientras se edita.
+fn compact_pane(app: &App) -> Pane {
+    if app.input_mode == InputMode::EditingSource { Pane::Source } else { app.focus }
+}
+
+/// Panel que se ve en la posición `(column, row)` de una pantalla `area`, con la
+/// misma distribución que dibuja `ui`.
+pub fn pane_at(app: &App, area: Rect, column: u16, row: u16) -> Option<Pane> {
+    let position = Position::new(column, row);
+    if !area.contains(position) {
+        return None;
+    }
+    if let Some(pane) = app.zoomed {
+        return Some(pane);
+    }
+    if is_compact(area) {
+        return compact_rows(area)[1].contains(position).then(|| compact_pane(app));
+    }
+    chunks(app, area).into_iter().find(|(_, rect)| rect.contains(position)).and_then(|(kind, _)| match kind {
+        PaneKind::Source => Some(Pane::Source),
+        PaneKind::Output => Some(Pane::Output),
+        _ => None,
+    })
+}
+
+/// Distribución para terminales chicas: sin título ni ayuda, patrón y reemplazo
+/// en una sola línea, y el texto fuente y la salida como pestañas que ocupan el
+/// resto.
+fn render_compact(f: &mut Frame, app: &App, area: Rect) {
+    let rows = compact_rows(area);
+    let shown = compact_pane(app);
+    let tab = |label: &'static str, pane: Pane| {
+        let style = if pane == shown {
+            Style::default().fg(app.palette.title).add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(app.palette.help)
+        };
+        Span::styled(format!(" {} ", label), style)
+    };
+    let mut tabs = vec![tab("Source", Pane::Source), Span::raw(" "), tab("Output", Pane::Output)];
+    tabs.extend(workspace_tabs(app));
+    f.render_widget(Paragraph::new(Line::from(tabs)), rows[0]);
+
+    match shown {
+        _ if app.input_mode == InputMode::EditingTests => render_tests(f, app, rows[1]),
+        Pane::Source => render_source(f, app, rows[1]),
+        Pane::Output => render_output(f, app, rows[1]),
+    }
+
+    let field = |mode: InputMode, color: Color| {
+        if app.input_mode == mode {
+            Style::default().fg(color).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(color)
+        }
+    };
+    let separator = Style::default().fg(app.palette.help);
+    let mut pattern = vec![Span::styled("/", separator)];
+    pattern.extend(pattern_spans(app, field(InputMode::EditingRegex, app.palette.regex)));
+    pattern.push(Span::styled("/", separator));
+    // Sin el panel de reemplazo en `layout.panes` queda solo el patrón.
+    if visible_panes(app).contains(&PaneKind::Replace) {
+        pattern.push(Span::styled(app.replace_input.clone(), field(InputMode::EditingReplace, app.palette.replace)));
+        pattern.push(Span::styled("/", separator));
+    }
+    f.render_widget(Paragraph::new(Line::from(pattern)), rows[2]);
+    render_class_expansion(f, app, rows[2]);
+
+    let mode_name = i18n::mode_name(app.language, &app.input_mode);
+    f.render_widget(
+        Paragraph::new(status_line(app, mode_name)).style(Style::default().fg(app.palette.help)),
+        rows[3],
+    );
+}
+
+/// Filas visibles del panel de casos de prueba; con más, se ven las últimas
+/// mientras se editan.
+const TEST_ROWS: usize = 8;
+
+/// Casos de prueba, cada uno con su resultado: ✔ si el patrón cumple lo
+/// esperado, ✘ con el motivo si no, y ? si la línea no es un caso.
+fn render_tests(f: &mut Frame, app: &App, area: Rect) {
+    let results: HashMap<usize, &Verdict> =
+        app.case_results.iter().map(|result| (result.line, &result.verdict)).collect();
+    let passed = app.case_results.iter().filter(|result| result.verdict == Verdict::Pass).count();
+    let failing = Style::default().fg(app.palette.error).add_modifier(app.palette.emphasis);
+    let lines: Vec<Line> = app
+        .test_cases
+        .split('\n')
+        .enumerate()
+        .map(|(index, text)| {
+            let (marker, note) = match results.get(&index) {
+                Some(Verdict::Pass) => (Span::styled("✔ ", Style::default().fg(app.palette.matches)), None),
+                Some(Verdict::Fail(reason)) => (Span::styled("✘ ", failing), Some(reason.as_str())),
+                Some(Verdict::Invalid(reason)) => {
+                    (Span::styled("? ", Style::default().fg(app.palette.help)), Some(reason.as_str()))
+                }
+                None => (Span::raw("  "), None),
+            };
+            let mut spans = vec![marker, Span::raw(text.to_string())];
+            if let Some(note) = note {
+                spans.push(Span::styled(format!("  ({})", note), Style::default().fg(app.palette.help)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    let title = format!(
+        " [{}] ('{}') {}/{} ",
+        tr(app.language, Text::TestsPane),
+        app.keymap.key_label(Action::EditTests),
+        passed,
+        app.case_results.len()
+    );
+    let (block, style) = pane(app, bordered(app).title(title), Some(InputMode::EditingTests), app.palette.matches);
+    let scroll = if app.input_mode == InputMode::EditingTests {
+        lines.len().saturating_sub(block.inner(area).height as usize) as u16
+    } else {
+        0
+    };
+    f.render_widget(Paragraph::new(lines).style(style).scroll((scroll, 0)).block(block), area);
+}
+
+/// El patrón coloreado por tokens: paréntesis y corchetes en el color de su
+/// profundidad de anidamiento (o de error si no tienen pareja), y escapes,
+/// cuantificadores y operadores en negrita. Cada token parte de `base`. Con la
+/// cobertura activada, las partes que nunca participaron se ven tachadas.
+fn pattern_spans(app: &App, base: Style) -> Vec<Span<'static>> {
+    let pattern = &app.regex_input;
+    let unused = app.coverage.as_ref().map(|coverage| coverage.unused()).unwrap_or_default();
+    let spans = syntax::tokenize(pattern)
+        .into_iter()
+        .map(|token| {
+            let style = match token.kind {
+                TokenKind::Literal => base,
+                TokenKind::Escape | TokenKind::Quantifier | TokenKind::Operator => base.add_modifier(Modifier::BOLD),
+                TokenKind::Open | TokenKind::Close => {
+                    base.fg(app.palette.nesting[token.depth % app.palette.nesting.len()]).add_modifier(Modifier::BOLD)
+                }
+                TokenKind::Unmatched => base.fg(app.palette.error).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            };
+            Span::styled(pattern[token.range].to_string(), style)
+        })
+        .collect();
+    let mut marks: Vec<(Range<usize>, Style)> = Vec::new();
+    let cursor = app.regex_cursor();
+    if app.input_mode == InputMode::EditingRegex
+        && let Some(c) = pattern[cursor..].chars().next()
+    {
+        marks.push((cursor..cursor + c.len_utf8(), Style::default().add_modifier(Modifier::REVERSED)));
+    }
+    let dead = Style::default().fg(app.palette.help).add_modifier(Modifier::CROSSED_OUT);
+    marks.extend(unused.into_iter().map(|range| (range, dead)));
+    if marks.is_empty() {
+        return spans;
+    }
+    restyle(spans, &marks)
+}
+
+/// Inicio del token junto al cursor del patrón: el que termina en el cursor o
+/// lo contiene, o el primero si el cursor está al principio.
+fn token_at_cursor(app: &App) -> Option<usize> {
+    let cursor = app.regex_cursor();
+    let tokens = syntax::tokenize(&app.regex_input);
+    let token = tokens.iter().find(|token| token.range.start < cursor && cursor <= token.range.end).or(tokens.first())?;
+    Some(token.range.start)
+}
+
+/// Parte `spans`, que cubren un texto de corrido, en los bordes de `marks` (por
+/// byte) y aplica a cada pedazo el estilo de la primera marca que lo contiene.
+fn restyle(spans: Vec<Span<'static>>, marks: &[(Range<usize>, Style)]) -> Vec<Span<'static>> {
+    let mut result = Vec::new();
+    let mut offset = 0;
+    for span in spans {
+        let text = span.content.to_string();
+        let mut cuts: Vec<usize> = vec![0, text.len()];
+        for (range, _) in marks {
+            for edge in [range.start, range.end] {
+                if offset < edge && edge < offset + text.len() {
+                    cuts.push(edge - offset);
+                }
+            }
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+        for piece in cuts.windows(2) {
+            let start = offset + piece[0];
+            let style = marks
+                .iter()
+                .find(|(range, _)| range.start <= start && start < range.end)
+                .map_or(span.style, |(_, mark)| span.style.patch(*mark));
+            result.push(Span::styled(text[piece[0]..piece[1]].to_string(), style));
+        }
+        offset += text.len();
+    }
+    result
+}
+
+/// La clase de caracteres del token junto al cursor mientras se edita el
+/// patrón, si la hay.
+fn class_at_cursor(app: &App) -> Option<ClassExpansion> {
+    if app.input_mode != InputMode::EditingRegex {
+        return None;
+    }
+    classes::class_at(&app.regex_input, &app.flags, token_at_cursor(app)?)
+}
+
+/// Caracteres de una muestra separados por espacios, con los espacios en
+/// blanco escritos como escapes para que se vean.
+fn shown_chars(chars: &[char]) -> String {
+    chars
+        .iter()
+        .map(|&c| match c {
+            ' ' => "␣".to_string(),
+            c if c.is_whitespace() => c.escape_unicode().to_string(),
+            c => c.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Muestra de lo que incluye y excluye la clase junto al cursor, en un recuadro
+/// pegado a `anchor` (debajo si cabe, si no arriba).
+fn render_class_expansion(f: &mut Frame, app: &App, anchor: Rect) {
+    let Some(expansion) = class_at_cursor(app) else {
+        return;
+    };
+    let lang = app.language;
+    let label = Style::default().add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::raw(i18n::class_size(lang, expansion.count(), expansion.ranges.len())),
+        Line::from(vec![
+            Span::styled(format!("{}: ", tr(lang, Text::Includes)), label.fg(app.palette.matches)),
+            Span::raw(shown_chars(&expansion.included)),
+        ]),
+        Line::from(vec![
+            Span::styled(format!("{}: ", tr(lang, Text::Excludes)), label.fg(app.palette.error)),
+            Span::raw(shown_chars(&expansion.excluded)),
+        ]),
+    ];
+    let screen = f.area();
+    let height = (lines.len() as u16 + 2).min(screen.height);
+    let y = if anchor.bottom() + height <= screen.bottom() {
+        anchor.bottom()
+    } else {
+        anchor.y.saturating_sub(height)
+    };
+    let area = Rect { x: anchor.x, y, width: anchor.width, height };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(bordered(app).title(format!(" [{}] {} ", tr(lang, Text::Class), expansion.source))),
+        area,
+    );
+}
+
+/// Texto fuente, con la ruta del archivo abierto en el título.
+fn render_source(f: &mut Frame, app: &App, area: Rect) {
+    let name = tr(app.language, Text::SourcePane);
+    let source_title = match &app.source_path {
+        Some(path) => format!(" [{}] {} ('{}') ", name, path.display(), app.keymap.key_label(Action::EditSource)),
+        None => format!(" [{}] ('{}') ", name, app.keymap.key_label(Action::EditSource)),
+    };
+    let (block, source_style) = pane(
+        app,
+        bordered(app).title(format!("{}{}", source_title, zoom_hint(app))),
+        Some(InputMode::EditingSource),
+        app.palette.source,
+    );
+    if let Some(debugger) = app.debugger.as_ref().filter(|_| app.input_mode == InputMode::Debugging) {
+        render_debugger(f, app, debugger, area);
+        return;
+    }
+    if app.input_mode == InputMode::MarkingSpans || !app.expected_spans.is_empty() {
+        render_marked_spans(f, app, area);
+        return;
+    }
+    if let Some(comparison) = &app.comparison {
+        render_comparison(f, app, comparison, area);
+        return;
+    }
+    if !app.config.layout.line_numbers {
+        f.render_widget(
+            Paragraph::new(visible_rope(&app.source_text, app.source_scroll, area))
+                .style(source_style)
+                .wrap(Wrap { trim: true })
+                .block(block),
+            area,
+        );
+        return;
+    }
+    let lines = numbered_rope(app, block.inner(area), app.source_scroll as usize, &[]);
+    f.render_widget(Paragraph::new(lines).style(source_style).block(block), area);
+}
+
+/// El texto fuente durante la depuración: la posición del paso actual en
+/// video inverso, los grupos abiertos subrayados en el color de su índice y lo
+/// que lleva la coincidencia en curso en el color de las coincidencias. El
+/// texto se desplaza para que la posición quede a la vista.
+fn render_debugger(f: &mut Frame, app: &App, debugger: &Debugger, area: Rect) {
+    let step = &debugger.steps[debugger.current];
+    let rope = &app.source_text;
+    let at = step.at.min(rope.len_bytes());
+    let line = rope.byte_to_line(at);
+    let column = rope.byte_to_char(at) - rope.line_to_char(line) + 1;
+    let position = format!("{}:{}", line + 1, column);
+
+    let cursor_len = rope.get_char(rope.byte_to_char(at)).map_or(1, char::len_utf8);
+    let mut marks = vec![(at..at + cursor_len, Style::default().add_modifier(Modifier::REVERSED))];
+    if let Some(start) = step.match_start {
+        // Grupos abiertos desde el inicio de la coincidencia, el más interno primero.
+        let first = debugger.steps[..=debugger.current]
+            .iter()
+            .rposition(|step| step.kind == StepKind::Start)
+            .unwrap_or(debugger.current);
+        let mut open: Vec<(usize, usize)> = Vec::new();
+        for step in &debugger.steps[first..=debugger.current] {
+            match step.kind {
+                StepKind::Enter(group) => open.push((group, step.at)),
+                StepKind::Exit(group) => open.retain(|(open_group, _)| *open_group != group),
+                _ => {}
+            }
+        }
+        for (group, entered) in open.into_iter().rev() {
+            let color = app.palette.nesting[group % app.palette.nesting.len()];
+            marks.push((entered..at, Style::default().fg(color).add_modifier(Modifier::UNDERLINED)));
+        }
+        let matched = Style::default().fg(app.palette.matches).add_modifier(Modifier::BOLD | app.palette.emphasis);
+        marks.push((start..at, matched));
+    }
+
+    let block = bordered(app)
+        .title(format!(
+            " [{}] {}/{}{}: {} ",
+            tr(app.language, Text::Debugger),
+            debugger.current + 1,
+            debugger.steps.len(),
+            if debugger.playing { " ▶" } else { "" },
+            i18n::describe_step(app.language, step, &position, |group| {
+                debugger.group_names.get(group).cloned().flatten()
+            })
+        ))
+        .border_style(Style::default().fg(app.palette.matches));
+    let inner = block.inner(area);
+    let scroll = scroll_to_line(app, line, inner);
+    f.render_widget(Paragraph::new(numbered_rope(app, inner, scroll, &marks)).block(block), area);
+}
+
+/// Hay un patrón B o se está escribiendo.
+fn comparing(app: &App) -> bool {
+    !app.compare_input.is_empty() || app.input_mode == InputMode::EditingCompare
+}
+
+/// El texto fuente con las coincidencias de los patrones A y B: las de ambos
+/// en el color de las coincidencias, y las de uno solo subrayadas en el color
+/// de su campo. El título resume cuántas hay de cada una.
+fn render_comparison(f: &mut Frame, app: &App, comparison: &Result<SpanScore, String>, area: Rect) {
+    let (summary, marks) = match comparison {
+        Ok(score) => {
+            let both = Style::default().fg(app.palette.matches).add_modifier(Modifier::BOLD);
+            let only_a = Style::default().fg(app.palette.regex).add_modifier(Modifier::UNDERLINED | Modifier::REVERSED);
+            let only_b = Style::default().fg(app.palette.replace).add_modifier(Modifier::UNDERLINED | Modifier::REVERSED);
+            let mut marks: Vec<(Range<usize>, Style)> = Vec::new();
+            marks.extend(score.correct.iter().map(|span| (span.clone(), both)));
+            marks.extend(score.missed.iter().map(|span| (span.clone(), only_a)));
+            marks.extend(score.extra.iter().map(|span| (span.clone(), only_b)));
+            (i18n::comparison(app.language, score), marks)
+        }
+        Err(e) => (format!("B: {}", e.lines().last().unwrap_or_default()), Vec::new()),
+    };
+    let (block, style) = pane(
+        app,
+        bordered(app).title(format!(" [A/B] {} ", summary)),
+        Some(InputMode::EditingSource),
+        app.palette.source,
+    );
+    let inner = block.inner(area);
+    let lines = numbered_rope(app, inner, app.source_scroll as usize, &marks);
+    f.render_widget(Paragraph::new(lines).style(style).block(block), area);
+}
+
+/// Desplazamiento del texto fuente más cercano al actual con el que `line` queda a la vista en `inner`.
+fn scroll_to_line(app: &App, line: usize, inner: Rect) -> usize {
+    let height = inner.height.max(1) as usize;
+    let scroll = app.source_scroll as usize;
+    if line < scroll {
+        line
+    } else if line >= scroll + height {
+        line + 1 - height
+    } else {
+        scroll
+    }
+}
+
+/// El texto fuente con los tramos marcados a mano según el patrón los
+/// encuentre o no, lo encontrado de más y, mientras se marca, el cursor y el
+/// tramo en curso. El título resume la comparación.
+fn render_marked_spans(f: &mut Frame, app: &App, area: Rect) {
+    let marking = app.input_mode == InputMode::MarkingSpans;
+    let rope = &app.source_text;
+    let cursor = app.mark_cursor.min(rope.len_bytes());
+    let mut marks = Vec::new();
+    if marking {
+        let cursor_len = rope.get_char(rope.byte_to_char(cursor)).map_or(1, char::len_utf8);
+        marks.push((cursor..cursor + cursor_len, Style::default().add_modifier(Modifier::REVERSED)));
+        if let Some(anchor) = app.mark_anchor {
+            let pending = Style::default().fg(app.palette.regex).add_modifier(Modifier::REVERSED);
+            marks.push((anchor.min(cursor)..anchor.max(cursor), pending));
+        }
+    }
+    let score = app.span_score().unwrap_or_default();
+    let correct = Style::default().fg(app.palette.matches).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let missed = Style::default().fg(app.palette.error).add_modifier(Modifier::UNDERLINED);
+    let extra = Style::default().fg(app.palette.error).add_modifier(Modifier::BOLD | Modifier::REVERSED);
+    marks.extend(score.correct.iter().map(|span| (span.clone(), correct)));
+    marks.extend(score.missed.iter().map(|span| (span.clone(), missed)));
+    marks.extend(score.extra.iter().map(|span| (span.clone(), extra)));
+
+    let title = if app.expected_spans.is_empty() {
+        format!(" [{}] ", i18n::mode_name(app.language, &InputMode::MarkingSpans))
+    } else {
+        format!(
+            " [{}] {} ('{}') ",
+            i18n::mode_name(app.language, &InputMode::MarkingSpans),
+            i18n::span_score(app.language, &score),
+            app.keymap.key_label(Action::MarkSpans)
+        )
+    };
+    let (block, style) = pane(app, bordered(app).title(title), Some(InputMode::MarkingSpans), app.palette.source);
+    let inner = block.inner(area);
+    let scroll = if marking { scroll_to_line(app, rope.byte_to_line(cursor), inner) } else { app.source_scroll as usize };
+    f.render_widget(Paragraph::new(numbered_rope(app, inner, scroll, &marks)).style(style).block(block), area);
+}
+
+/// Marcador del margen según cuántas coincidencias empiezan en la línea.
+fn density_marker(matches: usize) -> char {
+    match matches {
+        0 => ' ',
+        1 => '·',
+        2..=3 => '•',
+        _ => '●',
+    }
+}
+
+/// Las líneas visibles del texto fuente, desde la línea `scroll`, con un margen
+/// de número de línea y marcador de coincidencias. Las líneas largas se parten
+/// a mano al ancho disponible, con el margen en blanco en la continuación, para
+/// que cada número quede junto a su línea. Cada carácter toma el estilo de la
+/// primera de `marks` (rangos de bytes del texto) que lo contenga; un fin de
+/// línea marcado se ve como un espacio.
+fn numbered_rope(app: &App, inner: Rect, scroll: usize, marks: &[(Range<usize>, Style)]) -> Vec<Line<'static>> {
+    let digits = app.source_text.len_lines().to_string().len();
+    let gutter_width = digits + 2;
+    let text_width = (inner.width as usize).saturating_sub(gutter_width).max(1);
+    let height = inner.height as usize;
+    let gutter_style = Style::default().fg(app.palette.help);
+    let marker_style = Style::default().fg(app.palette.matches).add_modifier(app.palette.emphasis);
+
+    let start = scroll.min(app.source_text.len_lines());
+    let mut rows = Vec::with_capacity(height);
+    for (offset, line) in app.source_text.lines_at(start).enumerate() {
+        if rows.len() >= height {
+            break;
+        }
+        let number = start + offset + 1;
+        let mut piece_start = app.source_text.line_to_byte(number - 1);
+        let text = line.to_string();
+        let text = text.trim_end_matches(['\n', '\r']);
+        let line_end = piece_start + text.len();
+        let marker = density_marker(app.match_lines.get(&number).copied().unwrap_or(0));
+        let pieces = split_by_width(text, text_width);
+        let last_piece = pieces.len() - 1;
+        for (index, piece) in pieces.into_iter().enumerate() {
+            if rows.len() >= height {
+                break;
+            }
+            let mut spans = if index == 0 {
+                vec![
+                    Span::styled(format!("{:>width$}", number, width = digits), gutter_style),
+                    Span::styled(marker.to_string(), marker_style),
+                    Span::raw(" "),
+                ]
+            } else {
+                vec![Span::raw(" ".repeat(gutter_width))]
+            };
+            spans.extend(marked_spans(piece, piece_start, marks));
+            piece_start += piece.len();
+            if index == last_piece
+                && let Some((_, style)) = marks.iter().find(|(range, _)| range.contains(&line_end))
+            {
+                spans.push(Span::styled(" ", *style));
+            }
+            rows.push(Line::from(spans));
+        }
+    }
+    rows
+}
+
+/// `text`, que empieza en el byte `start` del texto fuente, en tramos según el
+/// estilo de la primera de `marks` que contiene cada carácter.
+fn marked_spans(text: &str, start: usize, marks: &[(Range<usize>, Style)]) -> Vec<Span<'static>> {
+    if marks.is_empty() {
+        return vec![Span::raw(text.to_string())];
+    }
+    let style_at = |offset: usize| {
+        marks.iter().find(|(range, _)| range.contains(&offset)).map_or(Style::default(), |(_, style)| *style)
+    };
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_style = Style::default();
+    for (index, c) in text.char_indices() {
+        let style = style_at(start + index);
+        if style != run_style && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_style = style;
+        run.push(c);
+    }
+    if !run.is_empty() || spans.is_empty() {
+        spans.push(Span::styled(run, run_style));
+    }
+    spans
+}
+
+/// Parte `text` en trozos que ocupan a lo sumo `width` columnas de terminal:
+/// los caracteres anchos (CJK, emoji) cuentan doble y nunca quedan partidos
+/// entre dos filas. Una línea vacía da un único trozo vacío.
+fn split_by_width(text: &str, width: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let (mut start, mut used) = (0, 0);
+    for (index, c) in text.char_indices() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width > width && index > start {
+            pieces.push(&text[start..index]);
+            (start, used) = (index, 0);
+        }
+        used += char_width;
+    }
+    if start < text.len() || pieces.is_empty() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
+
+/// Aviso en el título del panel ampliado de cómo volver a la vista normal.
+fn zoom_hint(app: &App) -> String {
+    match app.zoomed {
+        Some(_) => format!(
+            "({}; '{}' {}) ",
+            tr(app.language, Text::Zoomed),
+            app.keymap.key_label(Action::ToggleZoom),
+            tr(app.language, Text::Restores)
+        ),
+        None => String::new(),
+    }
+}
+
+/// Salida, con el panel de tiempos a la derecha si está activo.
+fn render_output(f: &mut Frame, app: &App, area: Rect) {
+    let output_area = if app.show_stats {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(20), Constraint::Length(36)])
+            .split(area);
+        render_stats(f, app, columns[1]);
+        columns[0]
+    } else {
+        area
+    };
+
+    let lang = app.language;
+    let output_title = if app.worker.busy() {
+        format!(" [{}] ({}) ", tr(lang, Text::OutputPane), tr(lang, Text::Calculating))
+    } else if app.output_truncated {
+        format!(
+            " [{}] ({} {} KiB; '{}' {}) ",
+            tr(lang, Text::OutputPane),
+            tr(lang, Text::TruncatedTo),
+            app.config.limits.output_size_limit / 1024,
+            app.keymap.key_label(Action::CommitResult),
+            tr(lang, Text::WritesFullResult)
+        )
+    } else {
+        format!(" [{}] ", tr(lang, Text::OutputPane))
+    };
+
+    // En modo filtro se atenúan los separadores entre coincidencias, y las
+    // coincidencias llevan el énfasis del tema además del color.
+    let separator = Style::default().fg(app.palette.separator);
+    let matched = Style::default().add_modifier(app.palette.emphasis);
+    // Con lector de pantalla la salida ya viene descrita en texto.
+    let filtering = app.replace_input.is_empty() && !app.screen_reader;
+    let output_color = if app.output_error {
+        app.palette.error
+    } else if filtering && !app.output_is_source {
+        app.palette.matches
+    } else {
+        app.palette.output
+    };
+    let output = if app.output_is_source {
+        visible_rope(&app.source_text, app.output_scroll, output_area)
+    } else {
+        visible_text(&app.output_text, app.output_scroll, output_area, |line| {
+            if filtering { filter_line(line, matched, separator) } else { Line::raw(line) }
+        })
+    };
+    let (block, dimmed) = pane(app, bordered(app).title(format!("{}{}", output_title, zoom_hint(app))), None, output_color);
+    let output_style = if app.output_error {
+        dimmed.fg(output_color).add_modifier(app.palette.emphasis)
+    } else {
+        dimmed.fg(output_color)
+    };
+    f.render_widget(Paragraph::new(output).wrap(Wrap { trim: true }).style(output_style).block(block), output_area);
+}
+
+/// Título de popup entre corchetes, en el idioma de la interfaz.
+fn popup_title(app: &App, text: Text) -> String {
+    format!(" [{}] ", tr(app.language, text))
+}
+
+fn render_popups(f: &mut Frame, app: &App) {
+    match app.input_mode {
+        InputMode::History => render_history_popup(f, app),
+        InputMode::Library => render_library_popup(f, app),
+        InputMode::NamingPattern => {
+            render_input_popup(f, app, &popup_title(app, Text::PatternName), &app.name_input, app.palette.regex)
+        }
+        InputMode::DescribingPattern => {
+            render_input_popup(f, app, &popup_title(app, Text::Description), &app.description_input, app.palette.regex)
+        }
+        InputMode::TaggingPattern => {
+            render_input_popup(f, app, &popup_title(app, Text::Tags), &app.tags_input, app.palette.regex)
+        }
+        InputMode::ExemplifyingPattern => {
+            render_input_popup(f, app, &popup_title(app, Text::Example), &app.example_input, app.palette.source)
+        }
+        InputMode::OpeningFile => {
+            render_input_popup(f, app, &popup_title(app, Text::OpenFile), &app.path_input, app.palette.source)
+        }
+        InputMode::Recent => render_recent_popup(f, app),
+        InputMode::Catalog => render_catalog_popup(f, app),
+        InputMode::Exercises => render_exercises_popup(f, app),
+        InputMode::NearMisses => render_near_misses_popup(f, app),
+        InputMode::ExportingCode => render_export_popup(f, app),
+        InputMode::CommandPalette => render_command_palette(f, app),
+        InputMode::Help => render_help_popup(f, app),
+        InputMode::EnteringApiKey => {
+            let masked = "*".repeat(app.secret_input.chars().count());
+            render_input_popup(f, app, " [API key] ", &masked, app.palette.regex)
+        }
+        InputMode::ExportingLibrary => {
+            render_input_popup(f, app, &popup_title(app, Text::ExportLibraryTo), &app.path_input, app.palette.source)
+        }
+        InputMode::ExportingReport => {
+            render_input_popup(f, app, &popup_title(app, Text::ExportReportTo), &app.path_input, app.palette.source)
+        }
+        InputMode::ImportingLibrary => {
+            render_input_popup(f, app, &popup_title(app, Text::ImportLibraryFrom), &app.path_input, app.palette.source)
+        }
+        InputMode::ImportingRegex101 => {
+            render_input_popup(f, app, &popup_title(app, Text::ImportRegex101), &app.path_input, app.palette.source)
+        }
+        InputMode::ImportingTests => {
+            render_input_popup(f, app, &popup_title(app, Text::ImportTests), &app.path_input, app.palette.source)
+        }
+        InputMode::Loading => render_loading_popup(f, app),
+        InputMode::Modal => render_modal(f, app),
+        InputMode::Tour => render_tour(f, app),
+        _ => {}
+    }
+}
+
+/// Rectángulo centrado que ocupa el porcentaje indicado del área.
+fn millis(duration: Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn timing_lines(lang: Language, title: Text, timings: &Option<(String, Timings)>) -> Vec<Line<'static>> {
+    let Some((pattern, timings)) = timings else {
+        return Vec::new();
+    };
+    let cached = tr(lang, Text::Cached).to_string();
+    vec![
+        Line::styled(format!("{}: /{}/", tr(lang, title), pattern), Style::default().add_modifier(Modifier::BOLD)),
+        Line::raw(format!("  {:<11} {}", format!("{}:", tr(lang, Text::Compile)), timings.compile.map_or(cached, millis))),
+        Line::raw(format!("  {:<11} {}", format!("{}:", tr(lang, Text::Search)), millis(timings.search))),
+        Line::raw(format!("  {:<11} {}", format!("{}:", tr(lang, Text::Replace)), millis(timings.replace))),
+        Line::raw(format!("  {:<11} {:.0}", format!("{}:", tr(lang, Text::MatchesPerSec)), timings.matches_per_sec())),
+    ]
+}
+
+/// Cambio de tiempos por debajo del cual se considera ruido de medición.
+const TIMING_NOISE: f64 = 0.1;
+
+/// Panel de tiempos: el patrón actual y el anterior, sobre el mismo texto, y lo
+/// que el motor sabe del actual.
+fn render_stats(f: &mut Frame, app: &App, area: Rect) {
+    let lang = app.language;
+    let mut lines = timing_lines(lang, Text::Current, &app.timings);
+    if let (Some((_, current)), Some((_, previous))) = (&app.timings, &app.previous_timings)
+        && let Some((search, replace)) = current.change_from(previous)
+    {
+        let style = if search >= TIMING_NOISE {
+            Style::default().fg(app.palette.error).add_modifier(Modifier::BOLD)
+        } else if search <= -TIMING_NOISE {
+            Style::default().fg(app.palette.matches)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::styled(
+            format!(
+                "  {}: {} {} | {} {}",
+                tr(lang, Text::VsPrevious),
+                tr(lang, Text::Search),
+                i18n::timing_change(lang, search, TIMING_NOISE),
+                tr(lang, Text::Replace),
+                i18n::timing_change(lang, replace, TIMING_NOISE)
+            ),
+            style,
+        ));
+    }
+    if let Some((_, _, introspection)) = &app.introspection {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(tr(lang, Text::EngineTitle), Style::default().add_modifier(Modifier::BOLD)));
+        lines.extend(introspection.lines().into_iter().map(|line| Line::raw(format!("  {}", line))));
+    }
+    if let Some(summary) = &app.branches {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(tr(lang, Text::Branches), Style::default().add_modifier(Modifier::BOLD)));
+        for (index, (branch, count)) in summary.branches.iter().zip(&summary.counts).enumerate() {
+            lines.push(Line::raw(format!("  {}. /{}/: {}", index + 1, branch, count)));
+        }
+        for (location, branch) in &summary.samples {
+            lines.push(Line::raw(format!("  {}:{} «{}» ← {}", location.line, location.column, location.text, branch + 1)));
+        }
+    }
+    if let Some((pattern, cases)) = &app.stress {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            format!("{}: /{}/", tr(lang, Text::Stress), pattern),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        for case in cases {
+            let style = if case.is_slow() { Style::default().fg(app.palette.error) } else { Style::default() };
+            lines.push(Line::styled(
+                format!("  {:>8.1} MB/s  {:>7} {}  {}", case.mb_per_sec(), case.matches, tr(lang, Text::Matches), case.name),
+                style,
+            ));
+        }
+    }
+    let previous = timing_lines(lang, Text::Previous, &app.previous_timings);
+    if !previous.is_empty() {
+        lines.push(Line::raw(""));
+        lines.extend(previous);
+    }
+    if lines.is_empty() {
+        lines.push(Line::raw(tr(lang, Text::NoMeasurements)));
+    }
+    f.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(bordered(app).title(format!(" [{}] ('{}') ", tr(lang, Text::Timings), app.keymap.key_label(Action::ToggleStats)))),
+        area,
+    );
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+fn render_history_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(70, 60, f.area());
+    let items: Vec<ListItem> = app
+        .history
+        .search(&app.history_query)
+        .into_iter()
+        .map(|entry| ListItem::new(format!("{}  [{}]  {}", entry.timestamp, entry.flags.summary(), entry.pattern)))
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.history_selected));
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(bordered(app).title(format!(
+                " [{}] {}: {} ",
+                tr(app.language, Text::History),
+                tr(app.language, Text::Search),
+                app.history_query
+            ))),
+        area,
+        &mut state,
+    );
+}
+
+fn render_library_popup(f: &mut Frame, app: &App) {
+    let lang = app.language;
+    let area = centered_rect(70, 60, f.area());
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+    let entries = app.library.entries();
+    let view = app.library.view(app.library_sort, app.library_tag.as_deref());
+    let items: Vec<ListItem> = view
+        .iter()
+        .map(|&index| {
+            let entry = &entries[index];
+            // ● coincide con el texto fuente, ○ no, ✗ no compila.
+            let marker = if app.library_preview.error(index).is_some() {
+                "✗"
+            } else if app.library_preview.first_match(index).is_some() {
+                "●"
+            } else {
+                "○"
+            };
+            let replacement = if entry.replacement.is_empty() {
+                String::new()
+            } else {
+                format!(" → {}", entry.replacement)
+            };
+            let origin = if app.config.sync.enabled() && !entry.remote { "  (local)" } else { "" };
+            let mut text = format!(
+                "{} {}{}  /{}/{}{}  ({} {})",
+                marker,
+                entry.name,
+                origin,
+                entry.pattern,
+                entry.flags.summary(),
+                replacement,
+                entry.uses,
+                tr(lang, Text::Uses)
+            );
+            if !entry.tags.is_empty() {
+                text.push_str(&format!("  [{}]", entry.tags.join(", ")));
+            }
+            if !entry.description.is_empty() {
+                text.push_str(&format!("\n    {}", entry.description));
+            }
+            if !entry.example.is_empty() {
+                text.push_str(&format!("\n    {}: {}", tr(lang, Text::ExampleShort), entry.example));
+            }
+            ListItem::new(text)
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.library_selected));
+
+    let preview = match view.get(app.library_selected) {
+        Some(&index) => match (app.library_preview.error(index), app.library_preview.first_match(index)) {
+            (Some(error), _) => {
+                format!("{}: {}", tr(lang, Text::DoesNotCompile), error.lines().last().unwrap_or(error))
+            }
+            (None, Some(found)) => format!(
+                "{} {}, {} {}: «{}»",
+                tr(lang, Text::MatchesAtLine),
+                found.line,
+                tr(lang, Text::Column),
+                found.column,
+                found.text.replace('\n', "⏎")
+            ),
+            (None, None) => tr(lang, Text::NoMatchesInSource).to_string(),
+        },
+        None => String::new(),
+    };
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(
+                bordered(app)
+                    .title(format!(
+                        " [{}] {}: {} | {}: {} ",
+                        tr(lang, Text::Library),
+                        tr(lang, Text::Sort),
+                        i18n::sort_name(lang, app.library_sort),
+                        tr(lang, Text::Tag),
+                        app.library_tag.as_deref().unwrap_or(tr(lang, Text::AllTags))
+                    )),
+            ),
+        chunks[0],
+        &mut state,
+    );
+    f.render_widget(
+        Paragraph::new(preview).block(bordered(app).title(popup_title(app, Text::Preview))),
+        chunks[1],
+    );
+}
+
+fn render_input_popup(f: &mut Frame, app: &App, title: &str, value: &str, color: Color) {
+    let popup = centered_rect(50, 20, f.area());
+    let area = Rect { height: popup.height.min(3), ..popup };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(value)
+            .style(Style::default().fg(color))
+            .block(bordered(app).title(title)),
+        area,
+    );
+}
+
+/// Ventana modal: el mensaje ajustado al ancho y, debajo, las teclas que la
+/// responden. Los errores llevan el borde en el color de error.
+fn render_modal(f: &mut Frame, app: &App) {
+    let Some(modal) = &app.modal else {
+        return;
+    };
+    let screen = f.area();
+    let width = (screen.width * 3 / 5).max(30).min(screen.width);
+    let text_width = width.saturating_sub(2).max(1) as usize;
+    let message_rows: usize =
+        modal.message.lines().map(|line| line.width().div_ceil(text_width).max(1)).sum();
+    let height = (message_rows as u16 + 4).min(screen.height);
+    let area = Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    };
+    let block = match modal.kind {
+        ModalKind::Error if !app.screen_reader => bordered(app).border_style(Style::default().fg(app.palette.error)),
+        _ => bordered(app),
+    };
+    let keys = i18n::modal_keys(app.language, &modal.kind);
+    let mut lines: Vec<Line> = modal.message.lines().map(Line::raw).collect();
+    lines.push(Line::raw(""));
+    lines.push(Line::styled(keys, Style::default().fg(app.palette.help)));
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block.title(format!(" [{}] ", modal.title))),
+        area,
+    );
+}
+
+/// Avisos pasajeros apilados en la esquina inferior derecha, el más nuevo
+/// abajo. Cada uno ocupa una línea; lo que no entra se corta.
+fn render_toasts(f: &mut Frame, app: &App) {
+    let screen = f.area();
+    let max_width = (screen.width / 2).max(20).min(screen.width);
+    let height = if app.screen_reader { 1 } else { 3 };
+    let mut bottom = screen.y + screen.height;
+    for toast in app.toasts.iter().rev() {
+        if bottom < screen.y + height {
+            break;
+        }
+        let width = (toast.text.width() as u16 + 4).min(max_width);
+        bottom -= height;
+        let area = Rect { x: screen.x + screen.width - width, y: bottom, width, height };
+        let color = if toast.error { app.palette.error } else { app.palette.border };
+        let block = if app.screen_reader { bordered(app) } else { bordered(app).border_style(Style::default().fg(color)) };
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(format!(" {}", toast.text)).block(block), area);
+    }
+}
+
+/// Paso actual del recorrido de bienvenida: resalta el panel que presenta y
+/// pone la explicación del otro lado de la pantalla, para no taparlo.
+fn render_tour(f: &mut Frame, app: &App) {
+    let screen = f.area();
+    let (target, action) = TOUR[app.tour_step];
+    let (title, body) = i18n::tour(app.language)[app.tour_step];
+    let target = target.filter(|_| app.zoomed.is_none() && !is_compact(screen)).and_then(|target| {
+        chunks(app, screen).into_iter().find(|(kind, _)| *kind == target).map(|(_, rect)| rect)
+    });
+
+    let width = (screen.width * 3 / 5).max(40).min(screen.width);
+    let height = 8.min(screen.height);
+    let y = match target {
+        Some(rect) if rect.y + rect.height / 2 < screen.height / 2 => {
+            (rect.y + rect.height).min(screen.height - height)
+        }
+        Some(rect) => rect.y.saturating_sub(height),
+        None => (screen.height - height) / 2,
+    };
+    let area = Rect { x: screen.x + (screen.width - width) / 2, y: screen.y + y, width, height };
+
+    if let Some(rect) = target
+        && !app.screen_reader
+    {
+        let highlight = Style::default().fg(app.palette.title).add_modifier(Modifier::BOLD);
+        f.render_widget(Block::bordered().border_type(BorderType::Double).border_style(highlight), rect);
+    }
+
+    let mut lines = vec![Line::raw(body), Line::raw("")];
+    if let Some(action) = action {
+        lines.push(Line::styled(
+            format!("{}: '{}'", tr(app.language, Text::Key), app.keymap.key_label(action)),
+            Style::default().fg(app.palette.title),
+        ));
+    }
+    lines.push(Line::styled(
+        format!("{}/{} | {}", app.tour_step + 1, TOUR.len(), i18n::mode_keys(app.language, &app.input_mode)),
+        Style::default().fg(app.palette.help),
+    ));
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(bordered(app).title(format!(" [{}] ", title))),
+        area,
+    );
+}
+
+fn render_loading_popup(f: &mut Frame, app: &App) {
+    let Some(loading) = &app.loading else {
+        return;
+    };
+    let area = centered_rect(60, 20, f.area());
+    let ratio = if loading.total == 0 { 0.0 } else { (loading.read as f64 / loading.total as f64).min(1.0) };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Gauge::default()
+            .block(bordered(app).title(format!(" [{} {}] ", tr(app.language, Text::Loading), loading.path.display())))
+            .gauge_style(Style::default().fg(app.palette.source))
+            .ratio(ratio)
+            .label(format!("{} / {} KiB", loading.read / 1024, loading.total / 1024)),
+        area,
+    );
+}
+
+fn render_recent_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(70, 60, f.area());
+    let items: Vec<ListItem> = app
+        .recent
+        .files()
+        .iter()
+        .map(|path| ListItem::new(path.display().to_string()))
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.recent_selected));
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(bordered(app).title(popup_title(app, Text::RecentFiles))),
+        area,
+        &mut state,
+    );
+}
+
+fn render_export_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(85, 70, f.area());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(area);
+    let items: Vec<ListItem> = Target::ALL.iter().map(|target| ListItem::new(i18n::target_name(app.language, *target))).collect();
+    let mut state = ListState::default().with_selected(Some(app.export_selected));
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(bordered(app).title(popup_title(app, Text::ExportTo))),
+        columns[0],
+        &mut state,
+    );
+    f.render_widget(
+        Paragraph::new(app.export_preview.as_str()).block(bordered(app).title(popup_title(app, Text::Preview))),
+        columns[1],
+    );
+}
+
+fn render_catalog_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(80, 70, f.area());
+    let items: Vec<ListItem> = CATALOG
+        .iter()
+        .map(|entry| ListItem::new(format!("{} — {}\n    {}", entry.name, entry.description, entry.pattern)))
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.catalog_selected));
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(bordered(app).title(popup_title(app, Text::Catalog))),
+        area,
+        &mut state,
+    );
+}
+
+/// Las variantes de un carácter del ejemplo, marcadas según el patrón las acepte o no.
+fn render_near_misses_popup(f: &mut Frame, app: &App) {
+    let Some((example, misses)) = &app.near_misses else {
+        return;
+    };
+    let area = centered_rect(80, 70, f.area());
+    let items: Vec<ListItem> = misses
+        .iter()
+        .map(|miss| {
+            let (mark, color) = if miss.matches { ("✓", app.palette.matches) } else { ("✗", app.palette.error) };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", mark), Style::default().fg(color)),
+                Span::raw(format!("{:?}", miss.text)),
+                Span::styled(format!("  {}", miss.change), Style::default().add_modifier(Modifier::DIM)),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.near_miss_selected));
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(bordered(app).title(format!("{}{:?} ", popup_title(app, Text::NearMisses), example))),
+        area,
+        &mut state,
+    );
+}
+
+/// Líneas de un texto markdown sencillo: títulos (`#`) en negrita, listas (`- `)
+/// con viñeta y `código` en el color del patrón. Los párrafos se unen como en
+/// markdown: una línea en blanco los separa.
+fn markdown_lines(app: &App, text: &str) -> Vec<Line<'static>> {
+    let code = Style::default().fg(app.palette.regex).add_modifier(Modifier::BOLD);
+    let inline = |text: &str, base: Style| -> Vec<Span<'static>> {
+        text.split('`')
+            .enumerate()
+            .filter(|(_, piece)| !piece.is_empty())
+            .map(|(index, piece)| Span::styled(piece.to_string(), if index % 2 == 1 { code } else { base }))
+            .collect()
+    };
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut paragraph = String::new();
+    let flush = |paragraph: &mut String, lines: &mut Vec<Line<'static>>| {
+        if !paragraph.is_empty() {
+            lines.push(Line::from(inline(&std::mem::take(paragraph), Style::default())));
+        }
+    };
+    for line in text.lines().map(str::trim_end) {
+        if let Some(title) = line.strip_prefix("# ") {
+            flush(&mut paragraph, &mut lines);
+            lines.push(Line::from(inline(title, Style::default().fg(app.palette.title).add_modifier(Modifier::BOLD))));
+        } else if let Some(item) = line.strip_prefix("- ") {
+            flush(&mut paragraph, &mut lines);
+            paragraph.push_str("• ");
+            paragraph.push_str(item);
+        } else if line.is_empty() {
+            flush(&mut paragraph, &mut lines);
+            lines.push(Line::raw(""));
+        } else {
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(line.trim_start());
+        }
+    }
+    flush(&mut paragraph, &mut lines);
+    lines
+}
+
+/// Los ejercicios de cada lección, con los resueltos marcados y el progreso en
+/// el título, y al lado la explicación de la lección del seleccionado.
+fn render_exercises_popup(f: &mut Frame, app: &App) {
+    let popup = centered_rect(90, 80, f.area());
+    let [area, lesson_area] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .areas(popup);
+    let entries = app.exercise_entries();
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|&(lesson, index)| {
+            let content = &app.lessons[lesson];
+            let solved = app.progress.is_solved(&lessons::exercise_key(content, index));
+            let current = app.exercise == Some((lesson, index));
+            ListItem::new(format!(
+                "{} {}.{} {} — {}{}",
+                if solved { "✓" } else { " " },
+                lesson + 1,
+                index + 1,
+                content.title,
+                content.exercises[index].prompt,
+                if current { " ◀" } else { "" }
+            ))
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.exercise_selected));
+    let solved = entries
+        .iter()
+        .filter(|&&(lesson, index)| app.progress.is_solved(&lessons::exercise_key(&app.lessons[lesson], index)))
+        .count();
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(bordered(app).title(format!("{}{}/{} ", popup_title(app, Text::Exercises), solved, entries.len()))),
+        area,
+        &mut state,
+    );
+
+    let Some(&(lesson, _)) = entries.get(app.exercise_selected) else {
+        return;
+    };
+    let content = &app.lessons[lesson];
+    f.render_widget(Clear, lesson_area);
+    f.render_widget(
+        Paragraph::new(markdown_lines(app, &content.body))
+            .wrap(Wrap { trim: false })
+            .block(bordered(app).title(format!(" {}. {} ", lesson + 1, content.title))),
+        lesson_area,
+    );
+}
+
+/// Todos los atajos de EXPLORAR con su tecla actual y las teclas de cada modo.
+fn render_help_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(80, 80, f.area());
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let lang = app.language;
+    let mut lines = vec![Line::styled(i18n::mode_name(lang, &InputMode::Normal), bold)];
+    for (action, _, _) in Action::ALL {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:>10}  ", app.keymap.key_label(action)), Style::default().fg(app.palette.regex)),
+            Span::raw(i18n::action_description(lang, action)),
+        ]));
+    }
+    lines.push(Line::raw(""));
+    for (mode, keys) in i18n::help_sections(lang) {
+        lines.push(Line::styled(mode, bold));
+        lines.push(Line::raw(format!("  {}", keys)));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .scroll((app.help_scroll, 0))
+            .block(bordered(app).title(popup_title(app, Text::Help))),
+        area,
+    );
+}
+
+fn render_command_palette(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 50, f.area());
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
+
+    let entries = app.library.entries();
+    let items: Vec<ListItem> = app
+        .command_matches()
+        .into_iter()
+        .map(|index| {
+            let entry = &entries[index];
+            let mut text = format!("{}  /{}/", entry.name, entry.pattern);
+            if !entry.tags.is_empty() {
+                text.push_str(&format!("  [{}]", entry.tags.join(", ")));
+            }
+            if !entry.description.is_empty() {
+                text.push_str(&format!("  — {}", entry.description));
+            }
+            ListItem::new(text)
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.command_selected));
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(format!("> {}", app.command_query))
+            .style(Style::default().fg(app.palette.regex))
+            .block(bordered(app).title(popup_title(app, Text::SearchLibrary))),
+        chunks[0],
+    );
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(bordered(app)),
+        chunks[1],
+        &mut state,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::update::{Msg, update};
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::path::PathBuf;
+
+    /// Aplica el patrón y espera el resultado del hilo de trabajo.
+    fn settle(app: &mut App) {
+        app.apply_transform();
+        while app.worker.busy() {
+            std::thread::sleep(Duration::from_millis(1));
+            app.receive_transform();
+        }
+    }
+
+    fn app_with(source: &str, pattern: &str, replacement: &str) -> App {
+        let mut app = App {
+            source_text: Rope::from_str(source),
+            regex_input: pattern.to_string(),
+            replace_input: replacement.to_string(),
+            ..App::default()
+        };
+        settle(&mut app);
+        app
+    }
+
+    /// Texto del buffer, una fila por línea y sin espacios finales.
+    fn render(app: &App) -> String {
+        render_sized(app, 80, 24)
+    }
+
+    fn render_sized(app: &App, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let mut text = String::new();
+        for y in 0..buffer.area.height {
+            let row: String = (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect();
+            text.push_str(row.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Compara con `src/snapshots/<name>.txt`. Si no existe, o con
+    /// `UPDATE_SNAPSHOTS=1`, lo graba en lugar de comparar.
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/snapshots").join(format!("{}.txt", name));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Falta {} (UPDATE_SNAPSHOTS=1 para grabarlo):\n{}", path.display(), actual));
+        assert!(
+            expected == actual,
+            "La interfaz cambió respecto de {} (UPDATE_SNAPSHOTS=1 para aceptar):\n--- esperado\n{}\n--- obtenido\n{}",
+            path.display(),
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn filter_matches() {
+        let app = app_with("a1 b22 c333", r"\d+", "");
+        assert_snapshot("filter_matches", &render(&app));
+    }
+
+    #[test]
+    fn no_matches() {
+        let app = app_with("sin cifras", r"\d+", "");
+        assert_snapshot("no_matches", &render(&app));
+    }
+
+    #[test]
+    fn english_interface() {
+        let mut app = App { language: Language::En, ..App::default() };
+        app.source_text = Rope::from_str("no digits");
+        app.regex_input = r"\d+".to_string();
+        app.status_message = app.ready_message();
+        settle(&mut app);
+        assert_snapshot("english_interface", &render(&app));
+    }
+
+    #[test]
+    fn replacement() {
+        let app = app_with("2024-01-15", r"(\d+)-(\d+)-(\d+)", "$3/$2/$1");
+        assert_snapshot("replacement", &render(&app));
+    }
+
+    #[test]
+    fn regex_error() {
+        let app = app_with("texto", "(abc", "");
+        assert_snapshot("regex_error", &render(&app));
+    }
+
+    #[test]
+    fn editing_regex() {
+        let mut app = app_with("foo bar", "ba", "");
+        app.input_mode = InputMode::EditingRegex;
+        app.regex_cursor = app.regex_input.len();
+        assert_snapshot("editing_regex", &render(&app));
+    }
+
+    #[test]
+    fn editing_source() {
+        let mut app = app_with("línea uno\nlínea dos", "", "");
+        app.input_mode = InputMode::EditingSource;
+        assert_snapshot("editing_source", &render(&app));
+    }
+
+    #[test]
+    fn export_popup() {
+        let mut app = app_with("a1", r"\d", "");
+        app.select_export(0);
+        app.input_mode = InputMode::ExportingCode;
+        assert_snapshot("export_popup", &render(&app));
+    }
+
+    #[test]
+    fn exercises_popup() {
+        let mut app = app_with("", "", "");
+        app.exercise_selected = 1;
+        app.start_exercise();
+        app.regex_input = r"\d".to_string();
+        settle(&mut app);
+        app.input_mode = InputMode::Exercises;
+        assert_snapshot("exercises_popup", &render(&app));
+    }
+
+    #[test]
+    fn lesson_text() {
+        let mut app = app_with("", "", "");
+        app.perform(Action::OpenExercises);
+        for _ in 0..4 {
+            app.jump_lesson(1);
+        }
+        assert_eq!(app.exercise_entries()[app.exercise_selected], (4, 0));
+        assert_snapshot("lesson_text", &render_sized(&app, 120, 40));
+    }
+
+    #[test]
+    fn token_explanation() {
+        let mut app = app_with("a1 b22", r"[a-z]\d+", "");
+        app.input_mode = InputMode::EditingRegex;
+        app.regex_cursor = app.regex_input.len();
+        assert_snapshot("token_explanation", &render(&app));
+    }
+
+    #[test]
+    fn token_explanation_follows_cursor() {
+        let mut app = app_with("a1 b22", r"[a-z]\d+", "");
+        app.input_mode = InputMode::EditingRegex;
+        update(&mut app, Msg::Home);
+        update(&mut app, Msg::Right);
+        update(&mut app, Msg::Char('_'));
+        update(&mut app, Msg::End);
+        update(&mut app, Msg::Backspace);
+        assert_eq!(app.regex_input, r"[_a-z]\d");
+        update(&mut app, Msg::Left);
+        update(&mut app, Msg::Left);
+        update(&mut app, Msg::Delete);
+        update(&mut app, Msg::Char('\\'));
+        assert_eq!((app.regex_input.as_str(), app.regex_cursor()), (r"[_a-z]\d", 7));
+        update(&mut app, Msg::Left);
+        settle(&mut app);
+        assert_snapshot("token_explanation_follows_cursor", &render(&app));
+    }
+
+    #[test]
+    fn alternation_branches() {
+        let mut app = App {
+            source_text: Rope::from_str("WARN disco\nERROR red\nFATAL memoria\nWARN cpu"),
+            regex_input: "ERROR|WARN|FATAL.*".to_string(),
+            show_stats: true,
+            ..App::default()
+        };
+        settle(&mut app);
+        // Los tiempos medidos varían de una corrida a otra.
+        app.timings = Some((
+            app.regex_input.clone(),
+            Timings { compile: None, search: Duration::from_millis(1), replace: Duration::from_millis(1), matches: 3, corpus: 0 },
+        ));
+        assert_snapshot("alternation_branches", &render_sized(&app, 100, 40));
+    }
+
+    #[test]
+    fn marked_spans() {
+        let mut app = app_with("id 12, id 345, código 6", r"\d{2,}", "");
+        // Se esperan los tres números: "6" falta, y "345" se marca mal (solo "34").
+        app.expected_spans = vec![3..5, 10..12, 23..24];
+        settle(&mut app);
+        app.perform(Action::MarkSpans);
+        app.move_mark_cursor(3);
+        app.toggle_mark();
+        app.move_mark_cursor(2);
+        assert_snapshot("marked_spans", &render(&app));
+    }
+
+    #[test]
+    fn near_misses_popup() {
+        let mut app = app_with("", r"[a-z]+@\w+\.com", "");
+        app.open_near_misses();
+        assert_snapshot("near_misses_popup", &render(&app));
+    }
+
+    #[test]
+    fn pattern_comparison() {
+        let mut app = app_with("a1 b22 c333", r"\d+", "");
+        app.compare_input = r"\d{2}".to_string();
+        settle(&mut app);
+        assert_snapshot("pattern_comparison", &render(&app));
+    }
+
+    #[test]
+    fn pattern_coverage() {
+        let mut app = app_with("ERROR red\nWARN cpu", r"(?:ERROR|WARN|FATAL) \w+(?: \(\d+\))?", "");
+        app.perform(Action::ToggleCoverage);
+        settle(&mut app);
+        assert_snapshot("pattern_coverage", &render(&app));
+    }
+
+    #[test]
+    fn class_expansion() {
+        let mut app = app_with("snake_case", r"\b[\w&&[^_]]", "");
+        app.input_mode = InputMode::EditingRegex;
+        app.regex_cursor = app.regex_input.len();
+        assert_snapshot("class_expansion", &render(&app));
+    }
+
+    #[test]
+    fn golden_drift() {
+        let mut app = app_with("a1 b22\nc333", r"\d+", "");
+        app.golden = app.to_session().output(1 << 20).ok();
+        app.regex_input = r"\d{2,}".to_string();
+        settle(&mut app);
+        app.perform(Action::CheckGolden);
+        assert_snapshot("golden_drift", &render(&app));
+    }
+
+    #[test]
+    fn timing_change() {
+        let mut app = app_with("aaaa", "a+", "");
+        app.show_stats = true;
+        let measured = |search_ms: u64, corpus: u64| Timings {
+            compile: None,
+            search: Duration::from_millis(search_ms),
+            replace: Duration::from_millis(2),
+            matches: 1,
+            corpus,
+        };
+        let (search, replace) = measured(14, 7).change_from(&measured(10, 7)).unwrap();
+        assert_eq!(i18n::timing_change(Language::Es, search, TIMING_NOISE), "+40% más lento");
+        assert_eq!(i18n::timing_change(Language::Es, replace, TIMING_NOISE), "≈ igual");
+        // Sobre otro texto fuente los tiempos no se comparan.
+        assert_eq!(measured(14, 8).change_from(&measured(10, 7)), None);
+
+        app.previous_timings = Some(("a*".to_string(), measured(10, 7)));
+        app.timings = Some(("a+".to_string(), measured(14, 7)));
+        assert_snapshot("timing_change", &render_sized(&app, 100, 40));
+    }
+
+    #[test]
+    fn custom_pane_order() {
+        let mut app = app_with("a1 b22 c333", r"\d+", "");
+        app.config.layout.panes = vec![PaneKind::Output, PaneKind::Regex, PaneKind::Source, PaneKind::Status];
+        assert_snapshot("custom_pane_order", &render(&app));
+    }
+
+    #[test]
+    fn test_cases() {
+        let mut app = App {
+            test_cases: "+ 2024-01-15 -> 2024 | 01 | 15\n- 15/01/2024\n+ 2024-1-15\n# fechas ISO\nsin signo".to_string(),
+            regex_input: r"(\d{4})-(\d{2})-(\d{2})".to_string(),
+            ..App::default()
+        };
+        settle(&mut app);
+        assert_snapshot("test_cases", &render(&app));
+    }
+
+    #[test]
+    fn debugger_step() {
+        let mut app = app_with("id: ab12 cd34", r"(?P<letras>[a-z]+)(\d+)", "");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            app.start_debugger();
+            let traced = app.finished.recv().await.unwrap();
+            app.handle_background(traced);
+        });
+        app.debug_step(6);
+        assert_snapshot("debugger_step", &render(&app));
+    }
+
+    #[test]
+    fn welcome_tour() {
+        let mut app = app_with("a1 b22", r"(\d)+", "");
+        app.start_tour();
+        app.tour_next();
+        app.tour_next();
+        assert_snapshot("welcome_tour", &render(&app));
+    }
+
+    #[test]
+    fn compact_layout() {
+        let app = app_with("a1 b22 c333", r"\d+", "");
+        assert_snapshot("compact_layout", &render_sized(&app, 40, 12));
+    }
+
+    #[test]
+    fn wide_characters() {
+        let source = "東京の天気は晴れ ☀️ 気温は25度、明日は雨の予報で傘が必要になりそうです 🌂 以上\nfin";
+        let mut app = app_with(source, r"\d+", "");
+        app.input_mode = InputMode::EditingSource;
+        assert_snapshot("wide_characters", &render(&app));
+    }
+}
@@ -0,0 +1,365 @@
+//! Entrada de la interfaz al estilo Elm: cada tecla se traduce a un `Msg`
+//! independiente de crossterm, y `update` aplica el mensaje sobre el estado
+//! según el modo activo.
+
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use std::path::PathBuf;
+
+use regex_wysiwyg::catalog::CATALOG;
+use regex_wysiwyg::export::Target;
+
+use crate::app::{App, InputMode, Pane};
+use crate::keymap::{Action, Keymap};
+use crate::ui::pane_at;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    /// Acción del modo EXPLORAR, ya resuelta por el mapa de teclas.
+    Perform(Action),
+    /// Ir a la pestaña con ese índice (teclas 1-9 en EXPLORAR, si no están remapeadas).
+    SwitchWorkspace(usize),
+    /// Rueda del mouse sobre un panel: líneas a desplazar, negativas hacia arriba.
+    Wheel(Pane, i16),
+    Char(char),
+    Backspace,
+    Delete,
+    Submit,
+    Cancel,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+}
+
+impl Msg {
+    /// Mensaje de una tecla en `mode`: en EXPLORAR las teclas pasan por el mapa
+    /// de teclas; en el resto se toman tal cual.
+    pub fn from_key(keymap: &Keymap, mode: &InputMode, key: &KeyEvent) -> Option<Msg> {
+        if *mode == InputMode::Normal {
+            return keymap.action_for(key).map(Msg::Perform).or(match key.code {
+                KeyCode::Char(c @ '1'..='9') => Some(Msg::SwitchWorkspace(c as usize - '1' as usize)),
+                _ => None,
+            });
+        }
+        match key.code {
+            KeyCode::Char(c) => Some(Msg::Char(c)),
+            KeyCode::Backspace => Some(Msg::Backspace),
+            KeyCode::Delete => Some(Msg::Delete),
+            KeyCode::Enter => Some(Msg::Submit),
+            KeyCode::Esc => Some(Msg::Cancel),
+            KeyCode::Up => Some(Msg::Up),
+            KeyCode::Down => Some(Msg::Down),
+            KeyCode::Left => Some(Msg::Left),
+            KeyCode::Right => Some(Msg::Right),
+            KeyCode::Home => Some(Msg::Home),
+            KeyCode::End => Some(Msg::End),
+            _ => None,
+        }
+    }
+
+    /// Mensaje de un evento del mouse sobre una pantalla `screen`: la rueda
+    /// desplaza el panel que está debajo del puntero. El resto se ignora.
+    pub fn from_mouse(app: &App, screen: Rect, mouse: &MouseEvent) -> Option<Msg> {
+        let lines = match mouse.kind {
+            MouseEventKind::ScrollUp => -WHEEL_LINES,
+            MouseEventKind::ScrollDown => WHEEL_LINES,
+            _ => return None,
+        };
+        pane_at(app, screen, mouse.column, mouse.row).map(|pane| Msg::Wheel(pane, lines))
+    }
+}
+
+/// Líneas que desplaza cada paso de la rueda del mouse.
+const WHEEL_LINES: i16 = 3;
+
+/// Aplica `msg` sobre el estado según el modo actual y programa el recálculo de
+/// la salida.
+pub fn update(app: &mut App, msg: Msg) {
+    // La rueda desplaza los paneles en cualquier modo sin ventana encima.
+    if let Msg::Wheel(pane, lines) = msg {
+        if matches!(
+            app.input_mode,
+            InputMode::Normal
+                | InputMode::EditingSource
+                | InputMode::EditingRegex
+                | InputMode::EditingReplace
+                | InputMode::EditingTests
+                | InputMode::EditingCompare
+        ) {
+            app.scroll(pane, lines);
+        }
+        return;
+    }
+    match app.input_mode {
+        InputMode::Normal => match msg {
+            Msg::Perform(Action::Quit) => app.quit = true,
+            Msg::Perform(action) => app.perform(action),
+            Msg::SwitchWorkspace(index) => app.switch_workspace(index),
+            _ => {}
+        },
+        InputMode::EditingSource => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Char(c) => app.push_source(c),
+            Msg::Backspace => app.pop_source(),
+            Msg::Submit => app.push_source('\n'),
+            _ => {}
+        },
+        InputMode::EditingRegex => match msg {
+            Msg::Cancel | Msg::Submit => {
+                app.record_history();
+                app.input_mode = InputMode::Normal;
+            }
+            Msg::Char(c) => app.insert_regex(c),
+            Msg::Backspace => app.delete_regex(false),
+            Msg::Delete => app.delete_regex(true),
+            Msg::Left => app.move_regex_cursor(-1),
+            Msg::Right => app.move_regex_cursor(1),
+            Msg::Home => app.regex_cursor = 0,
+            Msg::End => app.regex_cursor = app.regex_input.len(),
+            _ => {}
+        },
+        InputMode::EditingReplace => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Char(c) => app.replace_input.push(c),
+            Msg::Backspace => { app.replace_input.pop(); },
+            Msg::Submit => app.input_mode = InputMode::Normal,
+            _ => {}
+        },
+        InputMode::EditingCompare => match msg {
+            Msg::Cancel | Msg::Submit => app.input_mode = InputMode::Normal,
+            Msg::Char(c) => app.compare_input.push(c),
+            Msg::Backspace => { app.compare_input.pop(); },
+            _ => {}
+        },
+        InputMode::EditingTests => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Char(c) => app.test_cases.push(c),
+            Msg::Backspace => { app.test_cases.pop(); },
+            Msg::Submit => app.test_cases.push('\n'),
+            _ => {}
+        },
+        InputMode::Debugging => match msg {
+            Msg::Cancel => app.close_debugger(),
+            Msg::Down | Msg::Submit | Msg::Char(' ' | 'l') => app.debug_step(1),
+            Msg::Up | Msg::Backspace | Msg::Char('h') => app.debug_step(-1),
+            Msg::Char('n') => app.debug_step(100),
+            Msg::Char('N') => app.debug_step(-100),
+            Msg::Char('p') => {
+                if let Some(debugger) = &mut app.debugger {
+                    debugger.playing = !debugger.playing;
+                }
+            }
+            _ => {}
+        },
+        InputMode::NearMisses => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Char('+') => app.add_near_miss_case(true),
+            Msg::Char('-') => app.add_near_miss_case(false),
+            Msg::Up => app.near_miss_selected = app.near_miss_selected.saturating_sub(1),
+            Msg::Down => {
+                let len = app.near_misses.as_ref().map_or(0, |(_, misses)| misses.len());
+                if app.near_miss_selected + 1 < len {
+                    app.near_miss_selected += 1;
+                }
+            }
+            _ => {}
+        },
+        InputMode::MarkingSpans => match msg {
+            Msg::Cancel if app.mark_anchor.is_some() => app.mark_anchor = None,
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Left => app.move_mark_cursor(-1),
+            Msg::Right => app.move_mark_cursor(1),
+            Msg::Up => app.move_mark_line(-1),
+            Msg::Down => app.move_mark_line(1),
+            Msg::Submit | Msg::Char(' ') => app.toggle_mark(),
+            Msg::Delete | Msg::Backspace => app.delete_mark(),
+            Msg::Char('c') => {
+                app.expected_spans.clear();
+                app.mark_anchor = None;
+            }
+            _ => {}
+        },
+        InputMode::History => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.load_history_selection(),
+            Msg::Up => app.history_selected = app.history_selected.saturating_sub(1),
+            Msg::Down => {
+                let len = app.history.search(&app.history_query).len();
+                if app.history_selected + 1 < len {
+                    app.history_selected += 1;
+                }
+            }
+            Msg::Char(c) => {
+                app.history_query.push(c);
+                app.history_selected = 0;
+            }
+            Msg::Backspace => {
+                app.history_query.pop();
+                app.history_selected = 0;
+            }
+            _ => {}
+        },
+        InputMode::Library => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.load_library_selection(),
+            Msg::Up => app.library_selected = app.library_selected.saturating_sub(1),
+            Msg::Down => {
+                let len = app.library.view(app.library_sort, app.library_tag.as_deref()).len();
+                if app.library_selected + 1 < len {
+                    app.library_selected += 1;
+                }
+            }
+            Msg::Delete => app.delete_library_selection(),
+            Msg::Char('o') => {
+                app.library_sort = app.library_sort.next();
+                app.library_selected = 0;
+            }
+            Msg::Char('t') => app.cycle_library_tag(),
+            _ => {}
+        },
+        InputMode::OpeningFile => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => {
+                let path = PathBuf::from(app.path_input.trim());
+                app.open_file(path);
+            }
+            Msg::Char(c) => app.path_input.push(c),
+            Msg::Backspace => { app.path_input.pop(); },
+            _ => {}
+        },
+        InputMode::Recent => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => {
+                if let Some(path) = app.recent.files().get(app.recent_selected).cloned() {
+                    app.open_file(path);
+                } else {
+                    app.input_mode = InputMode::Normal;
+                }
+            }
+            Msg::Up => app.recent_selected = app.recent_selected.saturating_sub(1),
+            Msg::Down if app.recent_selected + 1 < app.recent.files().len() => app.recent_selected += 1,
+            _ => {}
+        },
+        InputMode::Loading => {
+            if msg == Msg::Cancel {
+                app.cancel_load();
+            }
+        }
+        InputMode::Tour => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit | Msg::Char(' ') | Msg::Down => app.tour_next(),
+            Msg::Backspace | Msg::Up => app.tour_step = app.tour_step.saturating_sub(1),
+            _ => {}
+        },
+        InputMode::Modal => match msg {
+            Msg::Char(c) => app.answer_modal(Some(c)),
+            Msg::Submit => app.answer_modal(Some('\n')),
+            Msg::Cancel => app.answer_modal(None),
+            _ => {}
+        },
+        InputMode::ExportingLibrary
+        | InputMode::ExportingReport
+        | InputMode::ImportingLibrary
+        | InputMode::ImportingRegex101
+        | InputMode::ImportingTests => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit if app.input_mode == InputMode::ImportingRegex101 => app.import_regex101(),
+            Msg::Submit if app.input_mode == InputMode::ImportingTests => app.import_tests(),
+            Msg::Submit if app.input_mode == InputMode::ExportingLibrary => app.export_library(),
+            Msg::Submit if app.input_mode == InputMode::ExportingReport => app.export_report(),
+            Msg::Submit => app.import_library(),
+            Msg::Char(c) => app.path_input.push(c),
+            Msg::Backspace => { app.path_input.pop(); },
+            _ => {}
+        },
+        InputMode::EnteringApiKey => match msg {
+            Msg::Cancel => {
+                app.secret_input.clear();
+                app.input_mode = InputMode::Normal;
+            }
+            Msg::Submit => app.save_api_key(),
+            Msg::Char(c) => app.secret_input.push(c),
+            Msg::Backspace => { app.secret_input.pop(); },
+            _ => {}
+        },
+        InputMode::Help => match msg {
+            Msg::Cancel | Msg::Submit | Msg::Char('?') => app.input_mode = InputMode::Normal,
+            Msg::Up => app.help_scroll = app.help_scroll.saturating_sub(1),
+            Msg::Down => app.help_scroll = app.help_scroll.saturating_add(1),
+            _ => {}
+        },
+        InputMode::CommandPalette => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.load_command_selection(),
+            Msg::Up => app.command_selected = app.command_selected.saturating_sub(1),
+            Msg::Down if app.command_selected + 1 < app.command_matches().len() => app.command_selected += 1,
+            Msg::Char(c) => {
+                app.command_query.push(c);
+                app.command_selected = 0;
+            }
+            Msg::Backspace => {
+                app.command_query.pop();
+                app.command_selected = 0;
+            }
+            _ => {}
+        },
+        InputMode::ExportingCode => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.copy_export_selection(),
+            Msg::Up => app.select_export(app.export_selected.saturating_sub(1)),
+            Msg::Down if app.export_selected + 1 < Target::ALL.len() => app.select_export(app.export_selected + 1),
+            _ => {}
+        },
+        InputMode::Exercises => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.start_exercise(),
+            Msg::Char('h') => app.show_exercise_hint(),
+            Msg::Delete => app.leave_exercise(),
+            Msg::Left => app.jump_lesson(-1),
+            Msg::Right => app.jump_lesson(1),
+            Msg::Up => app.exercise_selected = app.exercise_selected.saturating_sub(1),
+            Msg::Down if app.exercise_selected + 1 < app.exercise_entries().len() => app.exercise_selected += 1,
+            _ => {}
+        },
+        InputMode::Catalog => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.insert_catalog_selection(false),
+            Msg::Char('a') => app.insert_catalog_selection(true),
+            Msg::Up => app.catalog_selected = app.catalog_selected.saturating_sub(1),
+            Msg::Down if app.catalog_selected + 1 < CATALOG.len() => app.catalog_selected += 1,
+            _ => {}
+        },
+        InputMode::NamingPattern => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.confirm_pattern_name(),
+            Msg::Char(c) => app.name_input.push(c),
+            Msg::Backspace => { app.name_input.pop(); },
+            _ => {}
+        },
+        InputMode::DescribingPattern => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.input_mode = InputMode::TaggingPattern,
+            Msg::Char(c) => app.description_input.push(c),
+            Msg::Backspace => { app.description_input.pop(); },
+            _ => {}
+        },
+        InputMode::TaggingPattern => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.input_mode = InputMode::ExemplifyingPattern,
+            Msg::Char(c) => app.tags_input.push(c),
+            Msg::Backspace => { app.tags_input.pop(); },
+            _ => {}
+        },
+        InputMode::ExemplifyingPattern => match msg {
+            Msg::Cancel => app.input_mode = InputMode::Normal,
+            Msg::Submit => app.save_to_library(),
+            Msg::Char(c) => app.example_input.push(c),
+            Msg::Backspace => { app.example_input.pop(); },
+            _ => {}
+        },
+    }
+    app.schedule_transform();
+}
@@ -0,0 +1,293 @@
+//! Hilo de trabajo que aplica el patrón fuera del bucle de dibujo. Cada petición
+//! lleva un número de generación; si llegan varias seguidas solo se procesa la
+//! última, y las respuestas de generaciones ya superadas se descartan.
+//!
+//! El hilo conserva su copia del texto fuente: solo se vuelve a enviar (como un
+//! clon del rope, que no copia el texto) cuando cambia, y una petición idéntica
+//! a la anterior no se repite.
+//!
+//! Junto con la salida se calculan los análisis que necesitan recorrer el texto
+//! fuente (ver `Analyses`), para no copiarlo ni buscar de nuevo en la interfaz.
+//! Si solo cambian los análisis pedidos, la salida anterior se reutiliza.
+
+use ropey::Rope;
+use std::ops::Range;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::assertions::{self, SpanScore};
+use crate::branches::{self, BranchSummary};
+use crate::coverage::{self, Coverage};
+use crate::engine::MatchLocation;
+use crate::{
+    EngineOptions, RegexCache, Transform, count_matches, has_match, locate_matches, match_spans, transform_capped,
+};
+
+/// Tiempos de una aplicación del patrón.
+#[derive(Debug, Clone, Copy)]
+pub struct Timings {
+    /// `None` si la regex se reutilizó ya compilada.
+    pub compile: Option<Duration>,
+    pub search: Duration,
+    pub replace: Duration,
+    pub matches: usize,
+    /// Huella del texto fuente medido: solo se comparan tiempos del mismo texto.
+    pub corpus: u64,
+}
+
+impl Timings {
+    pub fn matches_per_sec(&self) -> f64 {
+        self.matches as f64 / self.search.as_secs_f64().max(1e-9)
+    }
+
+    /// Cambio relativo de los tiempos de búsqueda y de reemplazo respecto de
+    /// `previous` (0.4 es un 40 % más lento), o `None` si se midieron sobre
+    /// textos distintos.
+    pub fn change_from(&self, previous: &Timings) -> Option<(f64, f64)> {
+        let change = |now: Duration, before: Duration| now.as_secs_f64() / before.as_secs_f64().max(1e-9) - 1.0;
+        (self.corpus == previous.corpus)
+            .then(|| (change(self.search, previous.search), change(self.replace, previous.replace)))
+    }
+}
+
+/// Qué calcular además de la salida.
+#[derive(Debug, Clone, Hash)]
+pub struct Analyses {
+    /// Coincidencias examinadas como máximo en cada análisis.
+    pub limit: usize,
+    /// Cronometrar cada fase (a costa de una búsqueda extra).
+    pub timings: bool,
+    /// Resumir qué alternativa produjo cada coincidencia.
+    pub branches: bool,
+    /// Medir qué partes del patrón participan en las coincidencias.
+    pub coverage: bool,
+    /// Buscar los tramos que encuentra el patrón, para compararlos con los marcados.
+    pub spans: bool,
+    /// Patrón B cuyas coincidencias se comparan con las del principal.
+    pub compare: Option<String>,
+}
+
+/// Resultados de los análisis de `Analyses`; vacíos si el patrón no compila.
+#[derive(Debug, Default)]
+pub struct Findings {
+    /// Posición de las primeras coincidencias.
+    pub locations: Vec<MatchLocation>,
+    pub branches: Option<BranchSummary>,
+    pub coverage: Option<Coverage>,
+    pub spans: Vec<Range<usize>>,
+    /// Coincidencias del patrón B frente a las del principal, o el error del B.
+    pub comparison: Option<Result<SpanScore, String>>,
+}
+
+struct Job {
+    generation: u64,
+    /// `None` si el texto fuente no cambió desde la petición anterior.
+    source: Option<Rope>,
+    pattern: String,
+    replacement: String,
+    options: EngineOptions,
+    output_cap: usize,
+    analyses: Analyses,
+    /// Huella del texto fuente, para `Timings::corpus`.
+    corpus: u64,
+    /// Huella de todo lo que determina la salida (no los análisis).
+    output_print: u64,
+}
+
+struct Outcome {
+    generation: u64,
+    result: Result<Transform, regex::Error>,
+    findings: Findings,
+    timings: Option<Timings>,
+}
+
+pub struct Worker {
+    jobs: Sender<Job>,
+    outcomes: Receiver<Outcome>,
+    /// Última generación pedida.
+    requested: u64,
+    /// Última generación recibida.
+    received: u64,
+    /// Huellas del último texto fuente enviado y de la última petición completa.
+    sent_source: Option<u64>,
+    last_request: Option<u64>,
+}
+
+/// Lo que el hilo conserva entre peticiones.
+#[derive(Default)]
+struct State {
+    source: String,
+    cache: RegexCache,
+    /// Regex del patrón B de la comparación.
+    compare_cache: RegexCache,
+    /// Última salida calculada, con su `Job::output_print`.
+    last_output: Option<(u64, Transform)>,
+}
+
+fn fingerprint(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Worker {
+    pub fn spawn() -> Self {
+        let (jobs, job_rx) = mpsc::channel::<Job>();
+        let (outcome_tx, outcomes) = mpsc::channel();
+        thread::spawn(move || {
+            let mut state = State::default();
+            while let Ok(mut job) = job_rx.recv() {
+                while let Ok(mut newer) = job_rx.try_recv() {
+                    if newer.source.is_none() {
+                        newer.source = job.source.take();
+                    }
+                    job = newer;
+                }
+                if let Some(new_source) = job.source.take() {
+                    state.source = new_source.to_string();
+                }
+                let outcome = run(&mut state, job);
+                if outcome_tx.send(outcome).is_err() {
+                    break;
+                }
+            }
+        });
+        Worker { jobs, outcomes, requested: 0, received: 0, sent_source: None, last_request: None }
+    }
+
+    /// Encola un cálculo; invalida cualquier resultado anterior aún en curso. La
+    /// salida se corta en `output_cap` bytes. Si nada cambió desde la última
+    /// petición, no hace nada.
+    pub fn submit(
+        &mut self,
+        source: &Rope,
+        pattern: &str,
+        replacement: &str,
+        options: EngineOptions,
+        output_cap: usize,
+        analyses: Analyses,
+    ) {
+        let source_print = fingerprint(source);
+        let output_print = fingerprint((
+            source_print,
+            pattern,
+            replacement,
+            options.unit,
+            options.flags,
+            options.size_limit,
+            output_cap,
+            analyses.timings,
+        ));
+        let request = fingerprint((output_print, &analyses));
+        if self.last_request == Some(request) {
+            return;
+        }
+        self.last_request = Some(request);
+
+        let source = (self.sent_source != Some(source_print)).then(|| source.clone());
+        self.sent_source = Some(source_print);
+        self.requested += 1;
+        let _ = self.jobs.send(Job {
+            generation: self.requested,
+            source,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            options,
+            output_cap,
+            analyses,
+            corpus: source_print,
+            output_print,
+        });
+    }
+
+    /// Descarta lo que esté en curso sin pedir nada nuevo (p. ej. con el patrón
+    /// vacío, que no necesita cálculo).
+    pub fn invalidate(&mut self) {
+        self.requested += 1;
+        self.received = self.requested;
+        self.last_request = None;
+    }
+
+    /// Resultado de la última petición, si ya llegó y no se había entregado.
+    pub fn poll(&mut self) -> Option<(Result<Transform, regex::Error>, Findings, Option<Timings>)> {
+        let mut latest = None;
+        while let Ok(outcome) = self.outcomes.try_recv() {
+            if outcome.generation == self.requested {
+                self.received = outcome.generation;
+                latest = Some((outcome.result, outcome.findings, outcome.timings));
+            }
+        }
+        latest
+    }
+
+    /// Hay una petición pendiente cuyo resultado aún no llegó.
+    pub fn busy(&self) -> bool {
+        self.received < self.requested
+    }
+}
+
+fn run(state: &mut State, job: Job) -> Outcome {
+    let generation = job.generation;
+    let source = state.source.as_str();
+    if job.pattern.is_empty() {
+        let result = Ok(Transform { output: source.to_string(), match_count: 0, truncated: false });
+        return Outcome { generation, result, findings: Findings::default(), timings: None };
+    }
+
+    let cached = state.cache.is_current(&job.pattern, &job.options);
+    let started = Instant::now();
+    let re = match state.cache.get(&job.pattern, &job.options) {
+        Ok(re) => re,
+        Err(e) => return Outcome { generation, result: Err(e), findings: Findings::default(), timings: None },
+    };
+    let compile = (!cached).then(|| started.elapsed());
+    let (result, timings) = match &state.last_output {
+        Some((print, result)) if *print == job.output_print => (result.clone(), None),
+        _ if !job.analyses.timings => {
+            // Un reemplazo sin coincidencias deja el texto igual: no se copia, y la
+            // interfaz muestra el fuente al ver cero coincidencias.
+            if !job.replacement.is_empty() && !has_match(re, source, job.options.unit) {
+                (Transform { output: String::new(), match_count: 0, truncated: false }, None)
+            } else {
+                (transform_capped(re, source, &job.replacement, job.options.unit, job.output_cap), None)
+            }
+        }
+        _ => {
+            let started = Instant::now();
+            let matches = count_matches(re, source, job.options.unit);
+            let search = started.elapsed();
+            let started = Instant::now();
+            let result = transform_capped(re, source, &job.replacement, job.options.unit, job.output_cap);
+            let replace = started.elapsed();
+            (result, Some(Timings { compile, search, replace, matches, corpus: job.corpus }))
+        }
+    };
+
+    let analyses = &job.analyses;
+    let mut findings = Findings::default();
+    if result.match_count > 0 {
+        findings.locations = locate_matches(re, source, job.options.unit, analyses.limit);
+    }
+    if analyses.branches {
+        findings.branches = branches::summarize(&job.pattern, source, &job.options, analyses.limit);
+    }
+    if analyses.coverage {
+        findings.coverage = coverage::coverage(&job.pattern, source, &job.options, analyses.limit);
+    }
+    if analyses.spans || analyses.compare.is_some() {
+        let spans = match_spans(re, source, job.options.unit, analyses.limit);
+        if let Some(compare) = &analyses.compare {
+            findings.comparison = Some(match state.compare_cache.get(compare, &job.options) {
+                Ok(b) => Ok(assertions::score(&spans, &match_spans(b, source, job.options.unit, analyses.limit))),
+                Err(e) => Err(e.to_string()),
+            });
+        }
+        if analyses.spans {
+            findings.spans = spans;
+        }
+    }
+    state.last_output = Some((job.output_print, result.clone()));
+    Outcome { generation, result: Ok(result), findings, timings }
+}
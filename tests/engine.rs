@@ -0,0 +1,373 @@
+//! Propiedades del motor que deben valer para cualquier patrón y texto.
+
+use proptest::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use regex_wysiwyg::assertions;
+use regex_wysiwyg::branches::{branch_of_matches, tag_branches};
+use regex_wysiwyg::cases::{self, Verdict};
+use regex_wysiwyg::classes::class_at;
+use regex_wysiwyg::coverage::{coverage, tag_parts};
+use regex_wysiwyg::debugger::{StepKind, trace};
+use regex_wysiwyg::explain::explain_at;
+use regex_wysiwyg::lessons;
+use regex_wysiwyg::nearmiss::near_misses;
+use regex_wysiwyg::search::search_files_cancellable;
+use regex_wysiwyg::server::matches_json;
+use regex_wysiwyg::stress::stress;
+use regex_wysiwyg::syntax::{TokenKind, tokenize};
+use regex_wysiwyg::{
+    EngineOptions, ProcessingUnit, RegexFlags, compile, count_matches, has_match, locate_matches, match_spans, transform,
+    transform_capped, transform_with,
+};
+
+/// Patrones válidos armados con piezas comunes, para que casi todos coincidan en algo.
+fn pattern() -> impl Strategy<Value = String> {
+    let atom = prop::sample::select(vec![
+        "a", "b", "ab", r"\d", r"\d+", "[a-c]", "[^a ]", ".", " ", "a+", "b*", r"\w+", "(a|b)", "c?", "^", "$", r"\b",
+    ]);
+    prop::collection::vec(atom, 1..4).prop_map(|atoms| atoms.concat())
+}
+
+/// Texto sin `|` ni `:`, que el modo filtro usa como separadores en la salida.
+fn source() -> impl Strategy<Value = String> {
+    "[abc01 \r\n]{0,64}"
+}
+
+fn unit() -> impl Strategy<Value = ProcessingUnit> {
+    prop_oneof![Just(ProcessingUnit::WholeText), Just(ProcessingUnit::PerLine)]
+}
+
+fn options(unit: ProcessingUnit) -> EngineOptions {
+    EngineOptions { unit, flags: RegexFlags::default(), size_limit: 1 << 20 }
+}
+
+proptest! {
+    #[test]
+    fn filter_output_pieces_appear_in_source(pattern in pattern(), source in source(), unit in unit()) {
+        let result = transform(&source, &pattern, "", &options(unit)).unwrap();
+        let rows: Vec<&str> = match unit {
+            ProcessingUnit::WholeText => vec![result.output.as_str()],
+            ProcessingUnit::PerLine if result.output.is_empty() => Vec::new(),
+            ProcessingUnit::PerLine => result
+                .output
+                .split('\n')
+                .map(|row| row.split_once(": ").expect("cada fila empieza con su número de línea").1)
+                .collect(),
+        };
+        for row in rows {
+            for piece in row.split(" | ") {
+                prop_assert!(source.contains(piece), "{:?} no está en {:?}", piece, source);
+            }
+        }
+    }
+
+    #[test]
+    fn filter_counts_every_match(pattern in pattern(), source in source(), unit in unit()) {
+        let re = compile(&pattern, &options(unit)).unwrap();
+        let result = transform_with(&re, &source, "", unit);
+        prop_assert_eq!(result.match_count, count_matches(&re, &source, unit));
+    }
+
+    #[test]
+    fn empty_pattern_is_identity(source in source(), unit in unit(), replacement in "[a-z]{0,4}") {
+        let result = transform(&source, "", &replacement, &options(unit)).unwrap();
+        prop_assert_eq!(result.output, source);
+        prop_assert_eq!(result.match_count, 0);
+    }
+
+    #[test]
+    fn match_offsets_are_within_bounds(pattern in pattern(), source in source(), unit in unit()) {
+        let re = compile(&pattern, &options(unit)).unwrap();
+        let lines: Vec<&str> = source.lines().collect();
+        for found in matches_json(&re, &source, unit) {
+            let text = match found["line"].as_u64() {
+                Some(line) => lines[line as usize - 1],
+                None => source.as_str(),
+            };
+            let (start, end) = (found["start"].as_u64().unwrap() as usize, found["end"].as_u64().unwrap() as usize);
+            prop_assert!(start <= end && end <= text.len());
+            prop_assert!(text.is_char_boundary(start) && text.is_char_boundary(end));
+            prop_assert_eq!(&text[start..end], found["text"].as_str().unwrap());
+        }
+    }
+
+    #[test]
+    fn capped_output_is_a_prefix_of_the_full_output(
+        pattern in pattern(),
+        source in source(),
+        unit in unit(),
+        replacement in "[xy]{1,3}|\\$0\\$0",
+        cap in 0usize..48,
+    ) {
+        let re = compile(&pattern, &options(unit)).unwrap();
+        let full = transform_with(&re, &source, &replacement, unit);
+        let capped = transform_capped(&re, &source, &replacement, unit, cap);
+        prop_assert!(capped.output.len() <= cap);
+        prop_assert!(full.output.starts_with(&capped.output));
+        prop_assert_eq!(capped.truncated, full.output.len() > cap);
+        prop_assert_eq!(capped.match_count, full.match_count);
+    }
+}
+
+proptest! {
+    #[test]
+    fn per_line_modes_split_the_same_lines(
+        pattern in prop_oneof![pattern(), Just("^$".to_string()), Just("1$".to_string())],
+        source in source(),
+        ending in prop_oneof![Just(""), Just("\n"), Just("\r\n")],
+    ) {
+        let source = format!("{}{}", source, ending);
+        let re = compile(&pattern, &options(ProcessingUnit::PerLine)).unwrap();
+        let filtered = transform_with(&re, &source, "", ProcessingUnit::PerLine);
+        let replaced = transform_with(&re, &source, "$0", ProcessingUnit::PerLine);
+        prop_assert_eq!(filtered.match_count, replaced.match_count);
+        prop_assert_eq!(replaced.match_count, count_matches(&re, &source, ProcessingUnit::PerLine));
+        prop_assert_eq!(has_match(&re, &source, ProcessingUnit::PerLine), replaced.match_count > 0);
+        // Reemplazar cada coincidencia por sí misma conserva los fines de línea.
+        prop_assert_eq!(replaced.output, source);
+    }
+}
+
+proptest! {
+    #[test]
+    fn located_matches_point_at_their_text(pattern in pattern(), source in source(), unit in unit()) {
+        let re = compile(&pattern, &options(unit)).unwrap();
+        let locations = locate_matches(&re, &source, unit, usize::MAX);
+        prop_assert_eq!(locations.len(), count_matches(&re, &source, unit));
+        let lines: Vec<&str> = source.split('\n').collect();
+        for location in locations {
+            let line = lines[location.line - 1];
+            let start: usize = line.chars().take(location.column - 1).map(char::len_utf8).sum();
+            let rest = lines[location.line - 1..].join("\n");
+            prop_assert!(rest[start..].starts_with(&location.text));
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn tokens_cover_any_pattern(pattern in r"[ab()\[\]^|*+?{}1,:\\-]{0,16}") {
+        let tokens = tokenize(&pattern);
+        let mut end = 0;
+        for token in &tokens {
+            prop_assert_eq!(token.range.start, end);
+            end = token.range.end;
+        }
+        prop_assert_eq!(end, pattern.len());
+    }
+
+    #[test]
+    fn valid_patterns_have_balanced_brackets(pattern in pattern()) {
+        let tokens = tokenize(&pattern);
+        prop_assert!(tokens.iter().all(|token| token.kind != TokenKind::Unmatched));
+        let opened = tokens.iter().filter(|token| token.kind == TokenKind::Open).count();
+        let closed = tokens.iter().filter(|token| token.kind == TokenKind::Close).count();
+        prop_assert_eq!(opened, closed);
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_cases_agree_with_matching(pattern in pattern(), input in "[abc01 ]{1,16}") {
+        let re = compile(&pattern, &options(ProcessingUnit::WholeText)).unwrap();
+        let results = cases::evaluate(&re, &format!("+ {}\n- {}", input, input));
+        prop_assert_eq!(results.len(), 2);
+        prop_assert_eq!(results[0].verdict == Verdict::Pass, re.is_match(&input));
+        prop_assert_eq!(results[1].verdict == Verdict::Pass, !re.is_match(&input));
+    }
+}
+
+proptest! {
+    #[test]
+    fn debugger_steps_forward_through_every_match(pattern in pattern(), source in source(), unit in unit()) {
+        let re = compile(&pattern, &options(unit)).unwrap();
+        let steps = trace(&re, &source, unit, usize::MAX);
+        prop_assert!(steps.windows(2).all(|pair| pair[0].at <= pair[1].at));
+        let matches = steps.iter().filter(|step| matches!(step.kind, StepKind::Match(_))).count();
+        prop_assert_eq!(matches, count_matches(&re, &source, unit));
+    }
+
+    #[test]
+    fn limited_debugger_trace_is_a_prefix(pattern in pattern(), source in source(), unit in unit(), limit in 0usize..40) {
+        let re = compile(&pattern, &options(unit)).unwrap();
+        let full = trace(&re, &source, unit, usize::MAX);
+        let limited = trace(&re, &source, unit, limit);
+        prop_assert_eq!(&limited[..], &full[..limit.min(full.len())]);
+    }
+}
+
+#[test]
+fn lesson_solutions_solve_their_exercises() {
+    for lesson in lessons::lessons() {
+        assert!(!lesson.body.is_empty(), "{}", lesson.id);
+        for exercise in &lesson.exercises {
+            let re = compile(&exercise.solution, &options(ProcessingUnit::WholeText)).unwrap();
+            assert!(lessons::check(&re, exercise), "{}: {}", lesson.id, exercise.prompt);
+            let wrong = compile(".", &options(ProcessingUnit::WholeText)).unwrap();
+            assert!(!lessons::check(&wrong, exercise), "{}: {}", lesson.id, exercise.prompt);
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn every_token_of_a_valid_pattern_is_explained(pattern in pattern()) {
+        for token in tokenize(&pattern) {
+            prop_assert!(explain_at(&pattern, &RegexFlags::default(), token.range.start).is_some(), "{:?}", token);
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn branch_tagging_keeps_the_matches(first in pattern(), second in pattern(), source in source(), unit in unit()) {
+        let pattern = format!("{}|{}", first, second);
+        let (branches, tagged) = tag_branches(&pattern, &options(unit)).unwrap();
+        prop_assert_eq!(branches, vec![first, second]);
+        let re = compile(&pattern, &options(unit)).unwrap();
+        let tagged = compile(&tagged, &options(unit)).unwrap();
+        prop_assert_eq!(locate_matches(&tagged, &source, unit, 1000), locate_matches(&re, &source, unit, 1000));
+        let tags = branch_of_matches(&tagged, &source, unit, 1000);
+        prop_assert_eq!(tags.len(), count_matches(&re, &source, unit).min(1000));
+        prop_assert!(tags.iter().all(|&branch| branch < 2));
+    }
+}
+
+#[test]
+fn toml_corpus_loads_as_test_cases() {
+    let path = std::env::temp_dir().join(format!("corpus-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "[[case]]\ninput = \"2024-01-15\"\ncaptures = [\"2024\", \"01\"]\n\n[[case]]\ninput = \"15/01/2024\"\nmatches = false\n",
+    )
+    .unwrap();
+    let text = cases::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(text, "+ 2024-01-15 -> 2024 | 01\n- 15/01/2024");
+    let re = compile(r"(\d{4})-(\d{2})", &options(ProcessingUnit::WholeText)).unwrap();
+    assert!(cases::evaluate(&re, &text).iter().all(|result| result.verdict == Verdict::Pass));
+}
+
+proptest! {
+    #[test]
+    fn coverage_tagging_keeps_the_matches(pattern in pattern(), source in source(), unit in unit()) {
+        let options = options(unit);
+        if let Some((_, tagged)) = tag_parts(&pattern, &options) {
+            let original = compile(&pattern, &options).unwrap();
+            let tagged = compile(&tagged, &options).unwrap();
+            prop_assert_eq!(
+                locate_matches(&original, &source, unit, 1000),
+                locate_matches(&tagged, &source, unit, 1000)
+            );
+        }
+    }
+}
+
+#[test]
+fn coverage_finds_dead_branches() {
+    let pattern = r"(?:ERROR|WARN|FATAL) \w+(?: \(\d+\))?";
+    let found = coverage(pattern, "ERROR red\nWARN cpu", &options(ProcessingUnit::PerLine), 100).unwrap();
+    let unused: Vec<&str> = found.unused().into_iter().map(|range| &pattern[range]).collect();
+    assert_eq!(unused, vec!["FATAL", r"(?: \(\d+\))"]);
+}
+
+#[test]
+fn class_expansion_resolves_set_operators() {
+    let flags = RegexFlags::default();
+    for class in [r"[\w&&[^_]]", r"[a-z--[aeiou]]", r"[^0-9]", r"\p{Greek}", r"[[:alpha:]~~[a-f]]", r"\d", "[x]"] {
+        let expansion = class_at(class, &flags, class.len() - 1).unwrap();
+        assert_eq!(expansion.source, class);
+        let re = compile(&format!(r"\A{}\z", class), &options(ProcessingUnit::WholeText)).unwrap();
+        assert!(expansion.included.iter().all(|c| re.is_match(&c.to_string())), "{}", class);
+        assert!(expansion.excluded.iter().all(|c| !re.is_match(&c.to_string())), "{}", class);
+    }
+    // Lo que quitan los operadores aparece primero entre los excluidos.
+    assert_eq!(class_at(r"[\w&&[^_]]", &flags, 0).unwrap().excluded[0], '_');
+    // Dentro de la clase anidada se expande la que la contiene.
+    assert_eq!(class_at(r"[\w&&[^_]]", &flags, 7), class_at(r"[\w&&[^_]]", &flags, 0));
+    assert_eq!(class_at(r"[a-z--[aeiou]]", &flags, 0).unwrap().excluded[..5], ['a', 'e', 'i', 'o', 'u']);
+    assert_eq!(class_at(r"[^0-9]", &flags, 0).unwrap().excluded[0], '0');
+    assert_eq!(class_at("[a-c]", &flags, 0).unwrap().count(), 3);
+    assert!(class_at("ab", &flags, 0).is_none());
+}
+
+#[test]
+fn tightened_pattern_reports_regressions() {
+    let corpus = "+ 2024-01-15\n+ 2024-1-5\n- fecha\n# comentario";
+    let options = options(ProcessingUnit::WholeText);
+    let before = compile(r"\d{4}-\d{1,2}-\d{1,2}", &options).unwrap();
+    let after = compile(r"\d{4}-\d{2}-\d{2}", &options).unwrap();
+    assert_eq!(cases::regressions(&before, &after, corpus), vec![(1, "no coincide".to_string())]);
+    assert!(cases::regressions(&after, &before, corpus).is_empty());
+}
+
+proptest! {
+    #[test]
+    fn marked_spans_score_against_the_matches(pattern in pattern(), source in source(), unit in unit(), keep in 0usize..4) {
+        let re = compile(&pattern, &options(unit)).unwrap();
+        let actual = match_spans(&re, &source, unit, usize::MAX);
+        prop_assert_eq!(actual.len(), count_matches(&re, &source, unit));
+        prop_assert!(actual.iter().all(|span| source.get(span.clone()).is_some()));
+        // Marcar solo algunas coincidencias: ninguna falta y el resto sobra.
+        let expected: Vec<_> = actual.iter().take(keep).cloned().collect();
+        let score = assertions::score(&expected, &actual);
+        prop_assert_eq!(score.correct.len(), expected.len());
+        prop_assert!(score.missed.is_empty());
+        prop_assert_eq!(score.extra.len(), actual.len() - expected.len());
+    }
+}
+
+proptest! {
+    #[test]
+    fn near_misses_differ_from_a_full_match(pattern in pattern()) {
+        let options = options(ProcessingUnit::WholeText);
+        if let Some((example, misses)) = near_misses(&pattern, &options, 100).unwrap() {
+            let full = compile(&format!(r"\A(?:{})\z", pattern), &options).unwrap();
+            prop_assert!(full.is_match(&example));
+            for miss in misses {
+                prop_assert_ne!(&miss.text, &example);
+                prop_assert_eq!(miss.matches, full.is_match(&miss.text));
+            }
+        }
+    }
+}
+
+#[test]
+fn near_misses_expose_loose_boundaries() {
+    let (example, misses) = near_misses(r"\d{4}-\d+", &options(ProcessingUnit::WholeText), 100).unwrap().unwrap();
+    assert_eq!(example, "0000-0");
+    // Un dígito de más al final sigue coincidiendo; uno de menos al principio, no.
+    assert!(misses.iter().any(|miss| miss.text == "0000-00" && miss.matches));
+    assert!(misses.iter().any(|miss| miss.text == "000-0" && !miss.matches));
+}
+
+#[test]
+fn stress_inputs_cover_every_quantifier() {
+    let cases = stress(r"(\w+\s?)+:\d{2,}", &options(ProcessingUnit::WholeText), 1000).unwrap();
+    assert!(cases.iter().all(|case| case.len >= 1000));
+    assert!(cases.iter().any(|case| case.name.contains(r#""a""#)));
+    assert!(cases.iter().any(|case| case.name.contains(r#""0""#)));
+    assert!(cases.windows(2).all(|pair| pair[0].mb_per_sec() <= pair[1].mb_per_sec()));
+}
+
+#[test]
+fn file_search_reports_progress_and_stops_when_cancelled() {
+    let dir = std::env::temp_dir().join(format!("search-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let paths: Vec<_> = (0..4).map(|n| dir.join(format!("{}.txt", n))).collect();
+    for (n, path) in paths.iter().enumerate() {
+        std::fs::write(path, format!("linea {}\nsin\n", n)).unwrap();
+    }
+    let re = compile(r"\d", &options(ProcessingUnit::PerLine)).unwrap();
+
+    let done = AtomicUsize::new(0);
+    let results = search_files_cancellable(&re, &paths, &AtomicBool::new(false), || {
+        done.fetch_add(1, Ordering::Relaxed);
+    });
+    assert_eq!(done.load(Ordering::Relaxed), 4);
+    assert!(results.iter().zip(&paths).all(|(file, path)| &file.path == path && file.match_count() == 1));
+
+    let cancelled = search_files_cancellable(&re, &paths, &AtomicBool::new(true), || {});
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(cancelled.is_empty());
+}